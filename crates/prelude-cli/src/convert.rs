@@ -0,0 +1,191 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use clap::ValueEnum;
+
+use crate::{
+    document::Document,
+    errors::CliError,
+    rows::{rows, COLUMNS},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// The whole export as one JSON document.
+    Json,
+    /// One JSON object per record, one per line.
+    Jsonl,
+    /// One row per entry.
+    Csv,
+    /// One row per entry.
+    Parquet,
+}
+
+/// Write the export in `format` to `output`, or to `stdout` when there is no output file.
+pub fn convert(
+    document: &Document,
+    format: Format,
+    output: Option<&Path>,
+    stdout: &mut dyn Write,
+) -> Result<(), CliError> {
+    if format == Format::Parquet {
+        let output = output.ok_or(CliError::ParquetToStdout)?;
+        return write_parquet(document, File::create(output)?);
+    }
+
+    let mut out: BufWriter<Box<dyn Write + '_>> = match output {
+        Some(output) => BufWriter::new(Box::new(File::create(output)?)),
+        None => BufWriter::new(Box::new(stdout)),
+    };
+
+    match format {
+        Format::Json => {
+            document.write_json(&mut out)?;
+            writeln!(out)?;
+        }
+        Format::Jsonl => document.write_jsonl(&mut out)?,
+        Format::Csv => write_csv(document, &mut out)?,
+        Format::Parquet => unreachable!("Parquet is written above"),
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+fn write_csv(document: &Document, out: impl Write) -> Result<(), CliError> {
+    // The header is written by hand so that an export without entries still gets one.
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(out);
+    writer.write_record(COLUMNS)?;
+
+    for row in rows(document) {
+        writer.serialize(row)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet(document: &Document, file: File) -> Result<(), CliError> {
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, RecordBatch, StringArray, TimestampMicrosecondArray, UInt64Array};
+    use arrow_schema::{DataType, Field, Schema, TimeUnit};
+    use parquet::arrow::ArrowWriter;
+
+    let rows = rows(document);
+    let text = |column: &str| Field::new(column, DataType::Utf8, false);
+    let optional_text = |column: &str| Field::new(column, DataType::Utf8, true);
+    let schema = Arc::new(Schema::new(vec![
+        text("record"),
+        text("form"),
+        Field::new("form_index", DataType::UInt64, false),
+        text("category"),
+        text("field"),
+        text("entry_id"),
+        optional_text("value"),
+        optional_text("by"),
+        optional_text("role"),
+        Field::new(
+            "when",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            true,
+        ),
+        optional_text("reason"),
+    ]));
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|row| row.record),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|row| row.form),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            rows.iter().map(|row| row.form_index as u64),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|row| row.category),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|row| row.field),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|row| row.entry_id),
+        )),
+        Arc::new(StringArray::from_iter(rows.iter().map(|row| row.value))),
+        Arc::new(StringArray::from_iter(rows.iter().map(|row| row.by))),
+        Arc::new(StringArray::from_iter(rows.iter().map(|row| row.role))),
+        Arc::new(
+            TimestampMicrosecondArray::from_iter(
+                rows.iter()
+                    .map(|row| row.when.map(|when| when.timestamp_micros())),
+            )
+            .with_timezone("UTC"),
+        ),
+        Arc::new(StringArray::from_iter(rows.iter().map(|row| row.reason))),
+    ];
+
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+    let mut writer = ArrowWriter::try_new(file, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn write_parquet(_document: &Document, _file: File) -> Result<(), CliError> {
+    Err(CliError::ParquetUnsupported)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use prelude_xml_parser::NativeParser;
+
+    use super::*;
+
+    fn subject() -> Document {
+        let path = Path::new("../prelude-xml-parser/tests/assets/subject_native_small.xml");
+        Document::open(path, None, &NativeParser::new()).unwrap()
+    }
+
+    #[test]
+    fn jsonl_has_one_line_per_record() {
+        let document = subject();
+        let mut out = Vec::new();
+        convert(&document, Format::Jsonl, None, &mut out).unwrap();
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), document.records().len());
+    }
+
+    #[test]
+    fn csv_has_one_row_per_entry() {
+        let document = subject();
+        let mut out = Vec::new();
+        convert(&document, Format::Csv, None, &mut out).unwrap();
+
+        let mut reader = csv::Reader::from_reader(out.as_slice());
+        assert_eq!(reader.headers().unwrap(), COLUMNS.as_slice());
+        assert_eq!(reader.records().count(), document.counts().entries);
+    }
+
+    #[test]
+    fn parquet_needs_an_output_file() {
+        let result = convert(&subject(), Format::Parquet, None, &mut Vec::new());
+
+        assert!(matches!(result, Err(CliError::ParquetToStdout)));
+    }
+}
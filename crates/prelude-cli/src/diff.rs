@@ -0,0 +1,151 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
+
+use crate::{document::Document, errors::CliError};
+
+/// Where a field sits within a record: form name, form index, category and field name.
+type FieldPath<'a> = (&'a str, usize, &'a str, &'a str);
+
+/// The current value of every field of every record, keyed by record id.
+fn current_values(document: &Document) -> BTreeMap<&str, BTreeMap<FieldPath<'_>, Option<&str>>> {
+    let mut records = BTreeMap::new();
+
+    for record in document.records() {
+        let values: &mut BTreeMap<_, _> = records.entry(record.id).or_default();
+        for form in record.forms {
+            for category in form.categories.as_deref().into_iter().flatten() {
+                for field in category.fields.as_deref().into_iter().flatten() {
+                    // Entries are listed newest first.
+                    let current = field
+                        .entries
+                        .as_deref()
+                        .and_then(|entries| entries.first())
+                        .and_then(|entry| entry.value.as_ref())
+                        .map(|value| value.value.as_str());
+                    values.insert(
+                        (&*form.name, form.form_index, &*category.name, &*field.name),
+                        current,
+                    );
+                }
+            }
+        }
+    }
+
+    records
+}
+
+fn show(value: Option<&str>) -> String {
+    match value {
+        Some(value) => format!("{value:?}"),
+        None => "(no value)".to_string(),
+    }
+}
+
+/// Print the records and current field values that differ between two exports.
+///
+/// Returns whether the exports are the same.
+pub fn diff(old: &Document, new: &Document, mut out: impl Write) -> Result<bool, CliError> {
+    if old.kind() != new.kind() {
+        return Err(CliError::KindMismatch(
+            old.kind().to_string(),
+            new.kind().to_string(),
+        ));
+    }
+
+    let tag = old.kind().record_tag();
+    let old_records = current_values(old);
+    let new_records = current_values(new);
+    let ids: BTreeSet<_> = old_records.keys().chain(new_records.keys()).collect();
+    let mut same = true;
+
+    for id in ids {
+        let (old_values, new_values) = match (old_records.get(id), new_records.get(id)) {
+            (Some(old_values), Some(new_values)) => (old_values, new_values),
+            (Some(_), None) => {
+                same = false;
+                writeln!(out, "- {tag} {id}")?;
+                continue;
+            }
+            (None, Some(_)) => {
+                same = false;
+                writeln!(out, "+ {tag} {id}")?;
+                continue;
+            }
+            (None, None) => unreachable!("every id comes from one of the exports"),
+        };
+
+        let paths: BTreeSet<_> = old_values.keys().chain(new_values.keys()).collect();
+        for path @ (form, form_index, category, field) in paths {
+            let location = format!("{tag} {id} {form}[{form_index}]/{category}/{field}");
+            match (old_values.get(path), new_values.get(path)) {
+                (Some(old_value), Some(new_value)) if old_value != new_value => writeln!(
+                    out,
+                    "~ {location}: {} -> {}",
+                    show(*old_value),
+                    show(*new_value)
+                )?,
+                (Some(old_value), None) => writeln!(out, "- {location}: {}", show(*old_value))?,
+                (None, Some(new_value)) => writeln!(out, "+ {location}: {}", show(*new_value))?,
+                _ => continue,
+            }
+            same = false;
+        }
+    }
+
+    Ok(same)
+}
+
+#[cfg(test)]
+mod tests {
+    use prelude_xml_parser::NativeParser;
+
+    use super::*;
+    use crate::document::Kind;
+
+    fn subject(patients: &str) -> Document {
+        let xml = format!("<export_from_vision_EDC>{patients}</export_from_vision_EDC>");
+        Document::parse_str(&xml, Kind::Subject, &NativeParser::new()).unwrap()
+    }
+
+    fn patient(id: &str, breed: &str) -> String {
+        format!(
+            r#"<patient patientId="{id}" uniqueId="1" creator="Paul" siteName="Site" siteUniqueId="2">
+  <form name="demographics" formTitle="Demographics" formIndex="1" formState="In-Work">
+    <category name="Demographics" type="normal" highestIndex="0">
+      <field name="breed" type="text" dataType="string" errorCode="valid" keepHistory="true">
+        <entry id="1"><value by="Paul" byUniqueId="" role="PM" when="">{breed}</value></entry>
+      </field>
+    </category>
+  </form>
+</patient>"#
+        )
+    }
+
+    #[test]
+    fn same_exports_have_no_differences() {
+        let old = subject(&patient("ABC-001", "Lab"));
+        let mut out = Vec::new();
+
+        assert!(diff(&old, &old, &mut out).unwrap());
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn reports_changed_values_and_records() {
+        let old = subject(&(patient("ABC-001", "Lab") + &patient("ABC-002", "Pug")));
+        let new = subject(&(patient("ABC-001", "Labrador") + &patient("ABC-003", "Pug")));
+        let mut out = Vec::new();
+
+        assert!(!diff(&old, &new, &mut out).unwrap());
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            concat!(
+                "~ patient ABC-001 demographics[1]/Demographics/breed: \"Lab\" -> \"Labrador\"\n",
+                "- patient ABC-002\n",
+                "+ patient ABC-003\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+};
+
+use clap::ValueEnum;
+use prelude_xml_parser::{
+    native::{
+        common::{Export, Form},
+        site_native::SiteNative,
+        subject_native::SubjectNative,
+        user_native::UserNative,
+    },
+    NativeParser,
+};
+use quick_xml::{events::Event, Reader};
+use serde::Serialize;
+
+use crate::errors::CliError;
+
+/// How much of a file is read to work out what kind of export it is.
+const DETECT_LIMIT: u64 = 64 * 1024;
+
+/// Which of the three native exports a file holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Subject,
+    Site,
+    User,
+}
+
+impl Kind {
+    /// The element that holds one record of this kind.
+    pub fn record_tag(self) -> &'static str {
+        match self {
+            Kind::Subject => "patient",
+            Kind::Site => "site",
+            Kind::User => "user",
+        }
+    }
+
+    /// Work out the kind of export from the first record in the file.
+    pub fn detect(path: &Path) -> Result<Self, CliError> {
+        let mut prefix = Vec::new();
+        File::open(path)?
+            .take(DETECT_LIMIT)
+            .read_to_end(&mut prefix)?;
+
+        // Only the element names matter here, and they are ASCII in every encoding an export can
+        // use, so dropping the zero bytes of UTF-16 and decoding lossily is enough to find them.
+        prefix.retain(|&b| b != 0);
+
+        Self::detect_str(&String::from_utf8_lossy(&prefix))
+            .ok_or_else(|| CliError::UnknownKind(path.to_path_buf()))
+    }
+
+    /// Work out the kind of export from the first child of the root element.
+    pub fn detect_str(xml: &str) -> Option<Self> {
+        let mut reader = Reader::from_str(xml);
+        let mut depth = 0;
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) if depth == 1 => {
+                    return match e.local_name().as_ref() {
+                        b"patient" => Some(Kind::Subject),
+                        b"site" => Some(Kind::Site),
+                        b"user" => Some(Kind::User),
+                        _ => None,
+                    };
+                }
+                Ok(Event::Start(_)) => depth += 1,
+                Ok(Event::End(_)) => depth -= 1,
+                Ok(Event::Eof) | Err(_) => return None,
+                _ => {}
+            }
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::Subject => write!(f, "subject"),
+            Kind::Site => write!(f, "site"),
+            Kind::User => write!(f, "user"),
+        }
+    }
+}
+
+/// A parsed export of any kind.
+pub enum Document {
+    Subject(SubjectNative),
+    Site(SiteNative),
+    User(UserNative),
+}
+
+/// One record of an export, whichever kind it is.
+pub struct Record<'a> {
+    /// What identifies the record to a person: a patient's id, a site's name or a user's id.
+    pub id: &'a str,
+    /// The number of forms the record says it has.
+    pub declared_forms: usize,
+    pub forms: &'a [Form],
+}
+
+/// How much an export holds.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Counts {
+    pub records: usize,
+    pub forms: usize,
+    pub distinct_forms: usize,
+    pub fields: usize,
+    pub entries: usize,
+    pub comments: usize,
+    pub queries: usize,
+}
+
+impl Document {
+    /// Parse `path`, detecting its kind unless one is given.
+    pub fn open(path: &Path, kind: Option<Kind>, parser: &NativeParser) -> Result<Self, CliError> {
+        let kind = match kind {
+            Some(kind) => kind,
+            None => Kind::detect(path)?,
+        };
+
+        Ok(match kind {
+            Kind::Subject => Document::Subject(parser.parse_file(path)?),
+            Kind::Site => Document::Site(parser.parse_file(path)?),
+            Kind::User => Document::User(parser.parse_file(path)?),
+        })
+    }
+
+    /// Parse an export that has already been read into memory.
+    pub fn parse_str(xml: &str, kind: Kind, parser: &NativeParser) -> Result<Self, CliError> {
+        Ok(match kind {
+            Kind::Subject => Document::Subject(parser.parse_str(xml)?),
+            Kind::Site => Document::Site(parser.parse_str(xml)?),
+            Kind::User => Document::User(parser.parse_str(xml)?),
+        })
+    }
+
+    pub fn kind(&self) -> Kind {
+        match self {
+            Document::Subject(_) => Kind::Subject,
+            Document::Site(_) => Kind::Site,
+            Document::User(_) => Kind::User,
+        }
+    }
+
+    pub fn export(&self) -> Option<&Export> {
+        match self {
+            Document::Subject(native) => native.export.as_ref(),
+            Document::Site(native) => native.export.as_ref(),
+            Document::User(native) => native.export.as_ref(),
+        }
+    }
+
+    pub fn records(&self) -> Vec<Record<'_>> {
+        match self {
+            Document::Subject(native) => native
+                .patients
+                .iter()
+                .map(|patient| Record {
+                    id: &patient.patient_id,
+                    declared_forms: patient.number_of_forms,
+                    forms: patient.forms.as_deref().map_or(&[], Vec::as_slice),
+                })
+                .collect(),
+            Document::Site(native) => native
+                .sites
+                .iter()
+                .map(|site| Record {
+                    id: &site.name,
+                    declared_forms: site.number_of_forms,
+                    forms: site.forms.as_deref().map_or(&[], Vec::as_slice),
+                })
+                .collect(),
+            Document::User(native) => native
+                .users
+                .iter()
+                .map(|user| Record {
+                    id: &user.unique_id,
+                    declared_forms: user.number_of_forms,
+                    forms: user.forms.as_deref().map_or(&[], Vec::as_slice),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn counts(&self) -> Counts {
+        let records = self.records();
+        let mut counts = Counts {
+            records: records.len(),
+            ..Counts::default()
+        };
+        let mut form_names = HashSet::new();
+
+        for form in records.iter().flat_map(|record| record.forms) {
+            counts.forms += 1;
+            form_names.insert(&*form.name);
+
+            let categories = form.categories.as_deref().into_iter().flatten();
+            for field in
+                categories.flat_map(|category| category.fields.as_deref().into_iter().flatten())
+            {
+                counts.fields += 1;
+                counts.entries += field.entries.as_ref().map_or(0, |entries| entries.len());
+                counts.comments += field.comments.as_ref().map_or(0, |comments| comments.len());
+                counts.queries += field.queries.as_ref().map_or(0, |queries| queries.len());
+            }
+        }
+
+        counts.distinct_forms = form_names.len();
+        counts
+    }
+
+    /// Write the whole export as one JSON document.
+    pub fn write_json(&self, out: impl Write) -> Result<(), CliError> {
+        match self {
+            Document::Subject(native) => serde_json::to_writer_pretty(out, native)?,
+            Document::Site(native) => serde_json::to_writer_pretty(out, native)?,
+            Document::User(native) => serde_json::to_writer_pretty(out, native)?,
+        }
+
+        Ok(())
+    }
+
+    /// Write one JSON object per record, one per line.
+    pub fn write_jsonl(&self, mut out: impl Write) -> Result<(), CliError> {
+        fn write_lines<T: Serialize>(out: &mut impl Write, records: &[T]) -> Result<(), CliError> {
+            for record in records {
+                serde_json::to_writer(&mut *out, record)?;
+                out.write_all(b"\n")?;
+            }
+
+            Ok(())
+        }
+
+        match self {
+            Document::Subject(native) => write_lines(&mut out, &native.patients),
+            Document::Site(native) => write_lines(&mut out, &native.sites),
+            Document::User(native) => write_lines(&mut out, &native.users),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tempfile::Builder;
+
+    use super::*;
+
+    #[test]
+    fn detects_each_kind() {
+        for (file, kind) in [
+            ("subject_native.xml", Kind::Subject),
+            ("site_native.xml", Kind::Site),
+            ("user_native.xml", Kind::User),
+        ] {
+            let path = Path::new("../prelude-xml-parser/tests/assets").join(file);
+            assert_eq!(Kind::detect(&path).unwrap(), kind);
+        }
+    }
+
+    #[test]
+    fn detects_utf16_exports() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-16\"?>\n<export_from_vision_EDC>\n  <!-- users -->\n  <user uniqueId=\"1\"/>\n</export_from_vision_EDC>";
+        let mut file = Builder::new().suffix(".xml").tempfile().unwrap();
+        for unit in "\u{FEFF}".encode_utf16().chain(xml.encode_utf16()) {
+            file.write_all(&unit.to_le_bytes()).unwrap();
+        }
+
+        assert_eq!(Kind::detect(file.path()).unwrap(), Kind::User);
+    }
+
+    #[test]
+    fn unknown_kind_error() {
+        let mut file = Builder::new().suffix(".xml").tempfile().unwrap();
+        file.write_all(b"<export_from_vision_EDC></export_from_vision_EDC>")
+            .unwrap();
+
+        assert!(matches!(
+            Kind::detect(file.path()),
+            Err(CliError::UnknownKind(_))
+        ));
+    }
+}
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error(transparent)]
+    Parse(#[from] prelude_xml_parser::errors::Error),
+
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
+    #[cfg(feature = "parquet")]
+    #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[cfg(feature = "parquet")]
+    #[error(transparent)]
+    Arrow(#[from] arrow_schema::ArrowError),
+
+    #[error("Could not tell whether {0:?} is a subject, site or user export; pass --kind")]
+    UnknownKind(PathBuf),
+
+    #[error("Cannot compare a {0} export with a {1} export")]
+    KindMismatch(String, String),
+
+    #[error("Parquet output has to be written to a file; pass --output")]
+    ParquetToStdout,
+
+    #[cfg(not(feature = "parquet"))]
+    #[error("This build of prelude was compiled without Parquet support")]
+    ParquetUnsupported,
+}
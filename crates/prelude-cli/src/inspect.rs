@@ -0,0 +1,142 @@
+use std::{fs, io::Write, path::Path};
+
+use prelude_xml_parser::native::common::Export;
+use serde::Serialize;
+
+use crate::{
+    document::{Counts, Document, Kind},
+    errors::CliError,
+};
+
+#[derive(Serialize)]
+struct Inspection<'a> {
+    file: &'a Path,
+    bytes: u64,
+    kind: Kind,
+    export: Option<&'a Export>,
+    #[serde(flatten)]
+    counts: Counts,
+}
+
+/// Describe an export: its header, its kind and how much it holds.
+pub fn inspect(
+    path: &Path,
+    document: &Document,
+    json: bool,
+    mut out: impl Write,
+) -> Result<(), CliError> {
+    let inspection = Inspection {
+        file: path,
+        bytes: fs::metadata(path)?.len(),
+        kind: document.kind(),
+        export: document.export(),
+        counts: document.counts(),
+    };
+
+    if json {
+        serde_json::to_writer_pretty(&mut out, &inspection)?;
+        writeln!(out)?;
+        return Ok(());
+    }
+
+    let export = inspection.export;
+    let missing = || "-".to_string();
+    let counts = &inspection.counts;
+
+    writeln!(out, "File:                {}", path.display())?;
+    writeln!(out, "Size:                {} bytes", inspection.bytes)?;
+    writeln!(out, "Kind:                {}", inspection.kind)?;
+    writeln!(
+        out,
+        "Exported:            {}",
+        export
+            .and_then(|export| export.date)
+            .map_or_else(missing, |date| date.to_rfc3339())
+    )?;
+    writeln!(
+        out,
+        "Created by:          {}",
+        export
+            .and_then(|export| export.created_by.clone())
+            .unwrap_or_else(missing)
+    )?;
+    writeln!(
+        out,
+        "Role:                {}",
+        export
+            .and_then(|export| export.role.clone())
+            .unwrap_or_else(missing)
+    )?;
+    writeln!(
+        out,
+        "Subjects processed:  {}",
+        export
+            .and_then(|export| export.number_subjects_processed)
+            .map_or_else(missing, |count| count.to_string())
+    )?;
+    writeln!(
+        out,
+        "Page:                {}",
+        export
+            .and_then(|export| export.page_number.clone())
+            .unwrap_or_else(missing)
+    )?;
+    writeln!(out, "Records:             {}", counts.records)?;
+    writeln!(
+        out,
+        "Forms:               {} ({} distinct)",
+        counts.forms, counts.distinct_forms
+    )?;
+    writeln!(out, "Fields:              {}", counts.fields)?;
+    writeln!(out, "Entries:             {}", counts.entries)?;
+    writeln!(out, "Comments:            {}", counts.comments)?;
+    writeln!(out, "Queries:             {}", counts.queries)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use prelude_xml_parser::NativeParser;
+
+    use super::*;
+
+    fn inspected(file: &str, json: bool) -> String {
+        let path = Path::new("../prelude-xml-parser/tests/assets").join(file);
+        let document = Document::open(&path, None, &NativeParser::new()).unwrap();
+        let mut out = Vec::new();
+
+        inspect(&path, &document, json, &mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn describes_the_header_and_contents() {
+        let out = inspected("site_native_small.xml", false);
+
+        for line in [
+            "Kind:                site",
+            "Exported:            2024-06-01T23:17:00+00:00",
+            "Created by:          Paul Sanders",
+            "Page:                -",
+            "Records:             1",
+            "Forms:               1 (1 distinct)",
+            "Comments:            1",
+        ] {
+            assert!(out.lines().any(|got| got == line), "{line:?} in\n{out}");
+        }
+    }
+
+    #[test]
+    fn describes_as_json() {
+        let out: serde_json::Value =
+            serde_json::from_str(&inspected("user_native_small.xml", true)).unwrap();
+
+        assert_eq!(out["kind"], "user");
+        assert_eq!(out["export"]["numberSubjectsProcessed"], 3);
+        assert_eq!(out["records"], 2);
+        assert_eq!(out["distinct_forms"], 2);
+        assert_eq!(out["entries"], 4);
+    }
+}
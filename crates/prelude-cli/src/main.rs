@@ -0,0 +1,180 @@
+mod convert;
+mod diff;
+mod document;
+mod errors;
+mod inspect;
+mod rows;
+mod schema;
+mod select;
+mod validate;
+
+use std::{
+    io::{stdout, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use clap::{Args, Parser, Subcommand};
+use prelude_xml_parser::{options::History, selector::Selector, NativeParser};
+
+use crate::{
+    convert::Format,
+    document::{Document, Kind},
+    errors::CliError,
+};
+
+/// Triage Prelude EDC native XML exports without Python.
+#[derive(Parser)]
+#[command(name = "prelude", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show an export's header, its kind and how much it holds.
+    Inspect {
+        #[command(flatten)]
+        input: Input,
+
+        /// Print the summary as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Convert an export to JSON, JSON Lines, CSV or Parquet.
+    Convert {
+        #[command(flatten)]
+        input: Input,
+
+        #[arg(short, long, value_enum)]
+        format: Format,
+
+        /// Where to write the result. Defaults to standard output.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Keep only the current entry of each field rather than its full history.
+        #[arg(long)]
+        current_only: bool,
+    },
+
+    /// Check that an export's counts add up and that its datetimes can be read.
+    ///
+    /// Exits with status 1 if any problem is found.
+    Validate {
+        #[command(flatten)]
+        input: Input,
+    },
+
+    /// List the forms, categories and fields an export uses.
+    Schema {
+        #[command(flatten)]
+        input: Input,
+
+        /// Print the schema as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print the nodes matching a selector, such as
+    /// `form[name=demographics]/category/field[name=sex]/current`, with their ancestry.
+    ///
+    /// Exits with status 1 if nothing matches.
+    Select {
+        #[command(flatten)]
+        input: Input,
+
+        expression: Selector,
+
+        /// Print the matches as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Compare the records and current field values of two exports of the same kind.
+    ///
+    /// Exits with status 1 if they differ.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+
+        /// The kind of both exports, if it cannot be told from their first record.
+        #[arg(long, value_enum)]
+        kind: Option<Kind>,
+    },
+}
+
+#[derive(Args)]
+struct Input {
+    /// The native XML export to read.
+    file: PathBuf,
+
+    /// The kind of export, if it cannot be told from the first record.
+    #[arg(long, value_enum)]
+    kind: Option<Kind>,
+}
+
+fn run(command: Command, mut out: impl Write) -> Result<bool, CliError> {
+    let parser = NativeParser::new();
+
+    match command {
+        Command::Inspect { input, json } => {
+            let document = Document::open(&input.file, input.kind, &parser)?;
+            inspect::inspect(&input.file, &document, json, &mut out)?;
+        }
+        Command::Convert {
+            input,
+            format,
+            output,
+            current_only,
+        } => {
+            let parser = if current_only {
+                parser.history(History::CurrentOnly)
+            } else {
+                parser
+            };
+            let document = Document::open(&input.file, input.kind, &parser)?;
+            convert::convert(&document, format, output.as_deref(), &mut out)?;
+        }
+        Command::Validate { input } => {
+            return validate::validate(&input.file, input.kind, &parser, &mut out)
+        }
+        Command::Schema { input, json } => {
+            let document = Document::open(&input.file, input.kind, &parser)?;
+            schema::schema(&document, json, &mut out)?;
+        }
+        Command::Select {
+            input,
+            expression,
+            json,
+        } => {
+            let document = Document::open(&input.file, input.kind, &parser)?;
+            let matched = select::select(&document, &expression, json, &mut out)?;
+            out.flush()?;
+            return Ok(matched);
+        }
+        Command::Diff { old, new, kind } => {
+            let old = Document::open(&old, kind, &parser)?;
+            let new = Document::open(&new, kind, &parser)?;
+            return diff::diff(&old, &new, &mut out);
+        }
+    }
+
+    out.flush()?;
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli.command, stdout().lock()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::document::Document;
+
+/// One recorded value of a field, flattened with everything that locates it in the export.
+///
+/// Tabular formats cannot nest, so CSV and Parquet output are one of these per entry.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Row<'a> {
+    pub record: &'a str,
+    pub form: &'a str,
+    pub form_index: usize,
+    pub category: &'a str,
+    pub field: &'a str,
+    pub entry_id: &'a str,
+    pub value: Option<&'a str>,
+    pub by: Option<&'a str>,
+    pub role: Option<&'a str>,
+    pub when: Option<DateTime<Utc>>,
+    pub reason: Option<&'a str>,
+}
+
+/// The column names of a [`Row`], in order.
+pub const COLUMNS: [&str; 11] = [
+    "record",
+    "form",
+    "form_index",
+    "category",
+    "field",
+    "entry_id",
+    "value",
+    "by",
+    "role",
+    "when",
+    "reason",
+];
+
+/// Flatten every entry of every field in the export into rows.
+pub fn rows(document: &Document) -> Vec<Row<'_>> {
+    let mut rows = Vec::new();
+
+    for record in document.records() {
+        for form in record.forms {
+            for category in form.categories.as_deref().into_iter().flatten() {
+                for field in category.fields.as_deref().into_iter().flatten() {
+                    for entry in field.entries.as_deref().into_iter().flatten() {
+                        let value = entry.value.as_ref();
+                        rows.push(Row {
+                            record: record.id,
+                            form: &form.name,
+                            form_index: form.form_index,
+                            category: &category.name,
+                            field: &field.name,
+                            entry_id: &entry.entry_id,
+                            value: value.map(|value| value.value.as_str()),
+                            by: value.map(|value| &*value.by),
+                            role: value.map(|value| &*value.role),
+                            when: value.and_then(|value| value.when),
+                            reason: entry.reason.as_ref().map(|reason| reason.value.as_str()),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    rows
+}
@@ -0,0 +1,126 @@
+use std::{collections::BTreeMap, io::Write};
+
+use serde::Serialize;
+
+use crate::{document::Document, errors::CliError};
+
+/// The forms, categories and fields an export uses, and how often.
+#[derive(Debug, Default, Serialize)]
+pub struct Schema<'a> {
+    pub forms: BTreeMap<&'a str, FormSchema<'a>>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FormSchema<'a> {
+    pub title: &'a str,
+    /// How many times the form appears across all records.
+    pub instances: usize,
+    pub categories: BTreeMap<&'a str, BTreeMap<&'a str, FieldSchema<'a>>>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FieldSchema<'a> {
+    pub field_type: &'a str,
+    pub data_type: Option<&'a str>,
+    /// How many form instances have the field.
+    pub instances: usize,
+}
+
+impl<'a> Schema<'a> {
+    pub fn of(document: &'a Document) -> Self {
+        let mut schema = Schema::default();
+
+        for form in document.records().iter().flat_map(|record| record.forms) {
+            let form_schema = schema.forms.entry(&form.name).or_default();
+            form_schema.title = &form.form_title;
+            form_schema.instances += 1;
+
+            for category in form.categories.as_deref().into_iter().flatten() {
+                let fields = form_schema.categories.entry(&category.name).or_default();
+                for field in category.fields.as_deref().into_iter().flatten() {
+                    let field_schema = fields.entry(&field.name).or_default();
+                    field_schema.field_type = &field.field_type;
+                    field_schema.data_type = field.data_type.as_deref();
+                    field_schema.instances += 1;
+                }
+            }
+        }
+
+        schema
+    }
+}
+
+/// Print the schema of an export as an indented outline, or as JSON.
+pub fn schema(document: &Document, json: bool, mut out: impl Write) -> Result<(), CliError> {
+    let schema = Schema::of(document);
+
+    if json {
+        serde_json::to_writer_pretty(&mut out, &schema)?;
+        writeln!(out)?;
+        return Ok(());
+    }
+
+    for (name, form) in &schema.forms {
+        writeln!(
+            out,
+            "{name} \"{}\" ({} instances)",
+            form.title, form.instances
+        )?;
+        for (category, fields) in &form.categories {
+            writeln!(out, "  {category}")?;
+            for (field, field_schema) in fields {
+                writeln!(
+                    out,
+                    "    {field}: {}/{} ({} instances)",
+                    field_schema.field_type,
+                    field_schema.data_type.unwrap_or("-"),
+                    field_schema.instances
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use prelude_xml_parser::NativeParser;
+
+    use super::*;
+
+    fn open(file: &str) -> Document {
+        let path = Path::new("../prelude-xml-parser/tests/assets").join(file);
+        Document::open(&path, None, &NativeParser::new()).unwrap()
+    }
+
+    #[test]
+    fn prints_forms_categories_and_fields() {
+        let mut out = Vec::new();
+
+        schema(&open("site_native_small.xml"), false, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "demographic.form.name.site.demographics \"Site Demographics\" (1 instances)\n  Demographics\n    address: text/string (1 instances)\n    company: text/string (1 instances)\n"
+        );
+    }
+
+    #[test]
+    fn counts_instances_across_records() {
+        let document = open("user_native_small.xml");
+        let schema = Schema::of(&document);
+
+        assert_eq!(
+            schema
+                .forms
+                .values()
+                .map(|form| form.instances)
+                .sum::<usize>(),
+            document.counts().forms
+        );
+        assert_eq!(schema.forms.len(), document.counts().distinct_forms);
+    }
+}
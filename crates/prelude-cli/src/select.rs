@@ -0,0 +1,95 @@
+use std::io::Write;
+
+use prelude_xml_parser::selector::{Match, Node, Selector};
+use serde::Serialize;
+
+use crate::{document::Document, errors::CliError};
+
+/// One match as written by `--json`.
+#[derive(Debug, Serialize)]
+struct SelectedNode {
+    /// Each node from the record down to the match, as printed without `--json`.
+    path: Vec<String>,
+    /// The value of the matched node, if it has one.
+    value: Option<String>,
+}
+
+impl From<&Match<'_>> for SelectedNode {
+    fn from(m: &Match<'_>) -> Self {
+        let value = match m.node() {
+            Node::Entry { entry, .. } => entry.value.as_ref(),
+            Node::Comment(comment) => comment.value.as_ref(),
+            Node::Query(query) => query.value.as_ref(),
+            Node::Value(value) => Some(value),
+            _ => None,
+        };
+
+        SelectedNode {
+            path: m.ancestry.iter().map(ToString::to_string).collect(),
+            value: value.map(|value| value.value.clone()),
+        }
+    }
+}
+
+/// Print every node `selector` matches in the export, one per line, or as JSON.
+///
+/// Returns whether anything matched.
+pub fn select(
+    document: &Document,
+    selector: &Selector,
+    json: bool,
+    mut out: impl Write,
+) -> Result<bool, CliError> {
+    let matches = match document {
+        Document::Subject(native) => selector.select(native),
+        Document::Site(native) => selector.select(native),
+        Document::User(native) => selector.select(native),
+    };
+
+    if json {
+        let nodes: Vec<SelectedNode> = matches.iter().map(SelectedNode::from).collect();
+        serde_json::to_writer_pretty(&mut out, &nodes)?;
+        writeln!(out)?;
+    } else {
+        for m in &matches {
+            writeln!(out, "{m}")?;
+        }
+    }
+
+    Ok(!matches.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use prelude_xml_parser::NativeParser;
+
+    use super::*;
+
+    fn subject() -> Document {
+        let path = Path::new("../prelude-xml-parser/tests/assets/subject_native_small.xml");
+        Document::open(path, None, &NativeParser::new()).unwrap()
+    }
+
+    #[test]
+    fn prints_matches_with_their_ancestry() {
+        let selector = "form/category/field[name=breed]/current".parse().unwrap();
+        let mut out = Vec::new();
+
+        assert!(select(&subject(), &selector, false, &mut out).unwrap());
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "patient ABC-001 / form day.0.form.name.demographics[1] / category Demographics / field breed / current 1 = \"Labrador\"\n"
+        );
+    }
+
+    #[test]
+    fn reports_when_nothing_matches() {
+        let selector = "site/form".parse().unwrap();
+        let mut out = Vec::new();
+
+        assert!(!select(&subject(), &selector, true, &mut out).unwrap());
+        assert_eq!(String::from_utf8(out).unwrap(), "[]\n");
+    }
+}
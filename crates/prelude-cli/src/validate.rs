@@ -0,0 +1,234 @@
+use std::{fmt, fs, io::Write, path::Path};
+
+use prelude_xml_parser::{encoding::decode, native::parse_datetime, NativeParser};
+use quick_xml::{events::Event, Reader};
+
+use crate::{
+    document::{Document, Kind},
+    errors::CliError,
+};
+
+/// Datetime attributes that the parser drops, rather than rejects, when they cannot be read.
+///
+/// Those it rejects, such as a value's `when`, fail the whole parse and are reported that way.
+const LENIENT_DATETIMES: [(&[u8], &[u8]); 8] = [
+    (b"export_from_vision_EDC", b"date"),
+    (b"form", b"lastModified"),
+    (b"form", b"dateTimeChanged"),
+    (b"state", b"dateSigned"),
+    (b"lockState", b"dateTimeChanged"),
+    (b"entry", b"reviewedByWhen"),
+    (b"comment", b"reviewedByWhen"),
+    (b"query", b"reviewedByWhen"),
+];
+
+/// Something about an export that does not add up.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Check an export and report every problem found.
+///
+/// Returns whether the export is free of problems.
+pub fn validate(
+    path: &Path,
+    kind: Option<Kind>,
+    parser: &NativeParser,
+    mut out: impl Write,
+) -> Result<bool, CliError> {
+    let bytes = fs::read(path)?;
+    let xml = decode(&bytes)?;
+    let problems = problems(&xml, kind, parser)?;
+
+    for problem in &problems {
+        writeln!(out, "{problem}")?;
+    }
+    writeln!(
+        out,
+        "{}: {} problem{}",
+        path.display(),
+        problems.len(),
+        if problems.len() == 1 { "" } else { "s" }
+    )?;
+
+    Ok(problems.is_empty())
+}
+
+fn problems(
+    xml: &str,
+    kind: Option<Kind>,
+    parser: &NativeParser,
+) -> Result<Vec<Problem>, CliError> {
+    let mut problems = datetime_problems(xml);
+
+    let Some(kind) = kind.or_else(|| Kind::detect_str(xml)) else {
+        problems.push(Problem {
+            line: None,
+            message: "the export holds no patients, sites or users".to_string(),
+        });
+        return Ok(problems);
+    };
+
+    let document = match Document::parse_str(xml, kind, parser) {
+        Ok(document) => document,
+        Err(CliError::Parse(e)) => {
+            problems.push(Problem {
+                line: None,
+                message: format!("the export could not be parsed: {e}"),
+            });
+            return Ok(problems);
+        }
+        Err(e) => return Err(e),
+    };
+
+    let records = document.records();
+    if let Some(export) = document.export() {
+        // A paged export only holds some of the subjects it counts.
+        let declared = export
+            .number_subjects_processed
+            .filter(|_| export.page_number.is_none());
+        if let Some(declared) = declared.filter(|&declared| declared != records.len()) {
+            problems.push(Problem {
+                line: None,
+                message: format!(
+                    "the export declares {declared} subjects processed but holds {} {} records",
+                    records.len(),
+                    kind.record_tag()
+                ),
+            });
+        }
+    }
+
+    for record in &records {
+        if record.declared_forms != record.forms.len() {
+            problems.push(Problem {
+                line: None,
+                message: format!(
+                    "{} {} declares {} forms but holds {}",
+                    kind.record_tag(),
+                    record.id,
+                    record.declared_forms,
+                    record.forms.len()
+                ),
+            });
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Find the datetimes that the parser would silently drop.
+fn datetime_problems(xml: &str) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut reader = Reader::from_str(xml);
+    let mut line = 1;
+    let mut counted = 0;
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        let e = match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => e,
+            Ok(Event::Eof) | Err(_) => break,
+            _ => continue,
+        };
+
+        let name = e.local_name();
+        for attribute in e.attributes().flatten() {
+            let checked = LENIENT_DATETIMES
+                .iter()
+                .any(|&(element, key)| element == name.as_ref() && key == attribute.key.as_ref());
+            if !checked || attribute.value.is_empty() {
+                continue;
+            }
+
+            let value = String::from_utf8_lossy(&attribute.value);
+            if parse_datetime(&value).is_err() {
+                line += xml[counted..start].matches('\n').count();
+                counted = start;
+                problems.push(Problem {
+                    line: Some(line),
+                    message: format!(
+                        "{}@{} is not a datetime: {value:?}",
+                        String::from_utf8_lossy(name.as_ref()),
+                        String::from_utf8_lossy(attribute.key.as_ref())
+                    ),
+                });
+            }
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_unreadable_datetimes_and_form_counts() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<export_from_vision_EDC date="02-Jun-2024 06:59 -0500" createdBy="Paul" role="PM" numberSubjectsProcessed="1">
+  <user uniqueId="1" creator="Paul" numberOfForms="2">
+    <form name="demographics" lastModified="yesterday" formTitle="Demographics" formState="In-Work">
+      <state value="form.state.in.work" signer="Paul" signerUniqueId="1" dateSigned="2023-08-07 10:15:41 -0500"/>
+    </form>
+  </user>
+</export_from_vision_EDC>"#;
+
+        let problems = problems(xml, None, &NativeParser::new()).unwrap();
+
+        assert_eq!(
+            problems,
+            vec![
+                Problem {
+                    line: Some(4),
+                    message: "form@lastModified is not a datetime: \"yesterday\"".to_string(),
+                },
+                Problem {
+                    line: None,
+                    message: "user 1 declares 2 forms but holds 1".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_records_missing_from_the_export() {
+        let xml = r#"<export_from_vision_EDC numberSubjectsProcessed="3">
+  <site name="Some Site" uniqueId="1" creator="Paul" numberOfForms="0"></site>
+</export_from_vision_EDC>"#;
+
+        let problems = problems(xml, None, &NativeParser::new()).unwrap();
+
+        assert_eq!(
+            problems[0].message,
+            "the export declares 3 subjects processed but holds 1 site records"
+        );
+        assert_eq!(problems.len(), 1);
+    }
+
+    #[test]
+    fn reports_exports_that_do_not_parse() {
+        let xml = r#"<export_from_vision_EDC>
+  <patient patientId="ABC-001" uniqueId="1" whenCreated="not a date" creator="Paul" siteName="Site" siteUniqueId="2"></patient>
+</export_from_vision_EDC>"#;
+
+        let problems = problems(xml, None, &NativeParser::new()).unwrap();
+
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0]
+            .message
+            .starts_with("the export could not be parsed"));
+    }
+}
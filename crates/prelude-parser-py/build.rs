@@ -0,0 +1,855 @@
+//! Generates the `_prelude_parser.pyi` type stubs from the pyo3 classes and functions the module
+//! registers.
+//!
+//! The sources of both crates are parsed with syn rather than inspected at runtime, so attribute
+//! types come from the struct fields behind each getter (`Option<DateTime<Utc>>` becomes
+//! `datetime | None`) and docstrings from the Rust doc comments. The stub is written to `OUT_DIR`;
+//! the `stub_is_current` test compares it with the committed `prelude_parser/_prelude_parser.pyi`
+//! and `just stubs` updates the committed copy.
+
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use proc_macro2::{TokenStream, TokenTree};
+use syn::{
+    visit::Visit, Attribute, Expr, ExprLit, ExprMacro, ExprMethodCall, Fields, FnArg,
+    GenericArgument, ImplItem, ImplItemFn, Item, ItemFn, ItemStruct, Lit, Meta, Pat, PathArguments,
+    ReturnType, Type,
+};
+
+/// The source directories holding the module's classes and functions, relative to this crate.
+const SOURCE_DIRS: &[&str] = &["src", "../prelude-xml-parser/src"];
+
+/// The line length `ruff format` uses for the project.
+const LINE_LENGTH: usize = 100;
+
+const HEADER: &str = r#"# This file is generated from the Rust sources by `crates/prelude-parser-py/build.rs`.
+# Do not edit it by hand; run `just stubs` after changing a Python class or function.
+
+from __future__ import annotations
+
+from collections.abc import Callable, Iterator
+from datetime import datetime
+from pathlib import Path
+from typing import Generic, Literal, TypeVar, overload
+
+from prelude_parser.types import Dtype, FlatColumnSchema, FlatFormInfo
+"#;
+
+/// Classes whose stub cannot be derived from their Rust definition.
+///
+/// A `ListView` holds any of the lists in an export, so its item type only exists in Python.
+const MANUAL_CLASSES: &[(&str, &str)] = &[(
+    "ListView",
+    r#"class ListView(Generic[_T]):
+    """A read-only sequence over a list in an export, copying only the items that are accessed."""
+
+    def __len__(self) -> int: ...
+    @overload
+    def __getitem__(self, index: int) -> _T: ...
+    @overload
+    def __getitem__(self, index: slice) -> ListView[_T]: ...
+    def __iter__(self) -> Iterator[_T]: ...
+    def __repr__(self) -> str: ...
+    def to_list(self) -> list[_T]:
+        """Copy the items into a list"""
+"#,
+)];
+
+/// Python types for parameters, by name, whose Rust type accepts less precisely than Python can
+/// say.
+const PARAMETER_TYPES: &[(&str, &str)] = &[
+    ("history", r#"Literal["full", "current_only"]"#),
+    ("progress", "Callable[[Progress], object] | None"),
+    ("dtypes", "dict[str, dict[str, Dtype]] | None"),
+];
+
+/// Python return types for functions, by name, that build an untyped dict.
+const RETURN_TYPES: &[(&str, &str)] = &[
+    ("_parse_flat_file_to_dict", "dict[str, FlatFormInfo]"),
+    ("_parse_flat_string_to_dict", "dict[str, FlatFormInfo]"),
+    ("_parse_flat_bytes_to_dict", "dict[str, FlatFormInfo]"),
+    ("_parse_flat_file_to_pandas_dict", "dict[str, FlatFormInfo]"),
+    (
+        "_parse_flat_string_to_pandas_dict",
+        "dict[str, FlatFormInfo]",
+    ),
+    (
+        "_parse_flat_bytes_to_pandas_dict",
+        "dict[str, FlatFormInfo]",
+    ),
+    (
+        "_flat_schema_file",
+        "dict[str, dict[str, FlatColumnSchema]]",
+    ),
+    (
+        "_flat_schema_string",
+        "dict[str, dict[str, FlatColumnSchema]]",
+    ),
+    (
+        "_flat_schema_bytes",
+        "dict[str, dict[str, FlatColumnSchema]]",
+    ),
+];
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let mut sources = Sources::default();
+
+    for dir in SOURCE_DIRS {
+        let dir = manifest_dir.join(dir);
+        println!("cargo:rerun-if-changed={}", dir.display());
+        sources.read_dir(&dir);
+    }
+
+    let stub = sources.render();
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out_dir.join("_prelude_parser.pyi"), stub).unwrap();
+}
+
+/// What is registered with the module, in the order `_prelude_parser` registers it.
+enum Registered {
+    Class(String),
+    Function(String),
+    Exception { name: String, rust_name: String },
+    Attribute(String),
+}
+
+struct Class {
+    name: String,
+    doc: Vec<String>,
+    get_all: bool,
+    item: ItemStruct,
+}
+
+#[derive(Default)]
+struct Sources {
+    /// The `#[pyclass]` structs, by Rust name.
+    classes: HashMap<String, Class>,
+    /// The functions of the `#[pymethods]` blocks, by the Rust name of their class.
+    methods: HashMap<String, Vec<ImplItemFn>>,
+    functions: HashMap<String, ItemFn>,
+    /// The base class of each exception created with `create_exception!`, by Rust name.
+    exceptions: HashMap<String, String>,
+    registered: Vec<Registered>,
+}
+
+impl Sources {
+    fn read_dir(&mut self, dir: &Path) {
+        let mut entries = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        entries.sort();
+
+        for path in entries {
+            if path.is_dir() {
+                self.read_dir(&path);
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                let source = fs::read_to_string(&path).unwrap();
+                let file = syn::parse_file(&source)
+                    .unwrap_or_else(|e| panic!("failed to parse {}: {e}", path.display()));
+                self.read_items(file.items);
+            }
+        }
+    }
+
+    fn read_items(&mut self, items: Vec<Item>) {
+        for item in items {
+            match item {
+                Item::Struct(item) => {
+                    if let Some(args) = item.attrs.iter().find_map(pyclass_args) {
+                        let rust_name = item.ident.to_string();
+                        let name = string_arg(&args, "name").unwrap_or_else(|| rust_name.clone());
+                        let get_all = flag_arg(&args, "get_all");
+                        self.classes.insert(
+                            rust_name,
+                            Class {
+                                name,
+                                doc: doc(&item.attrs),
+                                get_all,
+                                item,
+                            },
+                        );
+                    }
+                }
+                Item::Impl(item) if item.attrs.iter().any(|attr| is(attr, "pymethods")) => {
+                    let Type::Path(self_ty) = &*item.self_ty else {
+                        continue;
+                    };
+                    let rust_name = self_ty.path.segments.last().unwrap().ident.to_string();
+                    let methods = self.methods.entry(rust_name).or_default();
+                    for item in item.items {
+                        if let ImplItem::Fn(method) = item {
+                            methods.push(method);
+                        }
+                    }
+                }
+                Item::Fn(item) if item.attrs.iter().any(|attr| is(attr, "pymodule")) => {
+                    let mut registrations = Registrations::default();
+                    registrations.visit_block(&item.block);
+                    self.registered = registrations.registered;
+                }
+                Item::Fn(item) if item.attrs.iter().any(|attr| is(attr, "pyfunction")) => {
+                    self.functions.insert(item.sig.ident.to_string(), item);
+                }
+                Item::Macro(item) if item.mac.path.is_ident("create_exception") => {
+                    let args = split_commas(item.mac.tokens.clone());
+                    let rust_name = tokens_to_string(&args[1]);
+                    let base = match tokens_to_string(&args[2]).as_str() {
+                        "PyException" => "Exception",
+                        "PyValueError" => "ValueError",
+                        other => panic!("no Python exception for `{other}`"),
+                    };
+                    self.exceptions.insert(rust_name, base.to_string());
+                }
+                Item::Mod(item) => {
+                    if let Some((_, items)) = item.content {
+                        self.read_items(items);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn class_names(&self) -> HashMap<&str, &str> {
+        self.classes
+            .iter()
+            .map(|(rust_name, class)| (rust_name.as_str(), class.name.as_str()))
+            .collect()
+    }
+
+    fn render(&self) -> String {
+        let names = self.class_names();
+        let mut stub = String::from(HEADER);
+
+        for registered in &self.registered {
+            if let Registered::Attribute(name) = registered {
+                stub.push_str(&format!("\n{name}: str\n"));
+            }
+        }
+        stub.push_str("\n_T = TypeVar(\"_T\")\n");
+
+        for registered in &self.registered {
+            if let Registered::Class(rust_name) = registered {
+                stub.push('\n');
+                stub.push_str(&self.render_class(rust_name, &names));
+            }
+        }
+
+        let mut functions = Vec::new();
+        for registered in &self.registered {
+            if let Registered::Function(rust_name) = registered {
+                let function = self
+                    .functions
+                    .get(rust_name)
+                    .unwrap_or_else(|| panic!("no #[pyfunction] `{rust_name}`"));
+                let types = Types {
+                    names: &names,
+                    self_name: None,
+                };
+                functions.push(render_function(
+                    &function.attrs,
+                    &function.sig,
+                    &types,
+                    "",
+                    false,
+                ));
+            }
+        }
+        stub.push('\n');
+        stub.push_str(&join_functions(&functions));
+
+        for registered in &self.registered {
+            if let Registered::Exception { name, rust_name } = registered {
+                let base = self
+                    .exceptions
+                    .get(rust_name)
+                    .unwrap_or_else(|| panic!("no exception `{rust_name}`"));
+                stub.push_str(&format!("\nclass {name}({base}):\n    pass\n"));
+            }
+        }
+
+        stub
+    }
+
+    fn render_class(&self, rust_name: &str, names: &HashMap<&str, &str>) -> String {
+        let class = self
+            .classes
+            .get(rust_name)
+            .unwrap_or_else(|| panic!("no #[pyclass] `{rust_name}`"));
+        if let Some((_, stub)) = MANUAL_CLASSES.iter().find(|(name, _)| *name == class.name) {
+            return stub.to_string();
+        }
+
+        let types = Types {
+            names,
+            self_name: Some(&class.name),
+        };
+        let fields = match &class.item.fields {
+            Fields::Named(fields) => fields.named.iter().collect(),
+            _ => Vec::new(),
+        };
+        let methods = self
+            .methods
+            .get(rust_name)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let mut attributes = Vec::new();
+        for field in &fields {
+            if class.get_all || field.attrs.iter().any(|attr| pyo3_flag(attr, "get")) {
+                let name = field.ident.as_ref().unwrap().to_string();
+                let ty = types.python(&field.ty, Lists::List);
+                attributes.push((name, ty, doc(&field.attrs)));
+            }
+        }
+        for method in methods {
+            let Some(attr) = method.attrs.iter().find(|attr| is(attr, "getter")) else {
+                continue;
+            };
+            let name = match &attr.meta {
+                Meta::List(list) => list.tokens.to_string(),
+                _ => method.sig.ident.to_string(),
+            };
+            let field = fields
+                .iter()
+                .find(|field| field.ident.as_ref().is_some_and(|ident| *ident == name));
+            let returns_view = matches!(
+                &method.sig.output,
+                ReturnType::Type(_, ty) if mentions(ty, "ListView")
+            );
+            let ty = match field {
+                Some(field) if returns_view => types.python(&field.ty, Lists::View),
+                Some(field) => types.python(&field.ty, Lists::List),
+                None => types.returns(&method.sig.output),
+            };
+            let doc = match doc(&method.attrs) {
+                lines if lines.is_empty() => {
+                    field.map(|field| doc(&field.attrs)).unwrap_or_default()
+                }
+                lines => lines,
+            };
+            // An explicit getter replaces the one `get_all` made for the field.
+            attributes.retain(|(attribute, _, _)| *attribute != name);
+            attributes.push((name, ty, doc));
+        }
+
+        let mut stub = format!("class {}:\n", class.name);
+        let mut sections = Vec::new();
+        if !class.doc.is_empty() {
+            sections.push(docstring(&class.doc, "    "));
+        }
+        if !attributes.is_empty() {
+            let mut section = String::new();
+            for (name, ty, doc) in attributes {
+                section.push_str(&format!("    {name}: {ty}\n"));
+                if !doc.is_empty() {
+                    section.push_str(&docstring(&doc, "    "));
+                }
+            }
+            sections.push(section);
+        }
+        let rendered = methods
+            .iter()
+            .filter(|method| {
+                !method
+                    .attrs
+                    .iter()
+                    .any(|attr| is(attr, "getter") || is(attr, "setter"))
+            })
+            .map(|method| {
+                let is_static = method.attrs.iter().any(|attr| is(attr, "staticmethod"));
+                render_function(&method.attrs, &method.sig, &types, "    ", !is_static)
+            })
+            .collect::<Vec<_>>();
+        let rendered = join_functions(&rendered);
+        if !rendered.is_empty() {
+            sections.push(rendered);
+        }
+        if sections.is_empty() {
+            sections.push("    pass\n".to_string());
+        }
+
+        stub.push_str(&sections.join("\n"));
+        stub
+    }
+}
+
+/// Collects what `_prelude_parser` registers from the calls in its body.
+#[derive(Default)]
+struct Registrations {
+    registered: Vec<Registered>,
+}
+
+impl<'ast> Visit<'ast> for Registrations {
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        match call.method.to_string().as_str() {
+            "add_class" => {
+                let Some(GenericArgument::Type(ty)) = call
+                    .turbofish
+                    .as_ref()
+                    .and_then(|turbofish| turbofish.args.first())
+                else {
+                    panic!("add_class without a class");
+                };
+                self.registered.push(Registered::Class(last_ident(ty)));
+            }
+            "add_function" => {
+                let function = match call.args.first() {
+                    Some(Expr::Try(function)) => Some(&*function.expr),
+                    function => function,
+                };
+                let Some(Expr::Macro(ExprMacro { mac, .. })) = function else {
+                    panic!("add_function without wrap_pyfunction!");
+                };
+                let args = split_commas(mac.tokens.clone());
+                self.registered
+                    .push(Registered::Function(tokens_to_string(&args[0])));
+            }
+            "add" => {
+                let Some(Expr::Lit(ExprLit {
+                    lit: Lit::Str(name),
+                    ..
+                })) = call.args.first()
+                else {
+                    panic!("add without a name");
+                };
+                let name = name.value();
+                match call.args.get(1) {
+                    Some(Expr::MethodCall(value)) if value.method == "get_type" => {
+                        let Some(GenericArgument::Type(ty)) = value
+                            .turbofish
+                            .as_ref()
+                            .and_then(|turbofish| turbofish.args.first())
+                        else {
+                            panic!("get_type without a type");
+                        };
+                        self.registered.push(Registered::Exception {
+                            name,
+                            rust_name: last_ident(ty),
+                        });
+                    }
+                    _ => self.registered.push(Registered::Attribute(name)),
+                }
+            }
+            _ => {}
+        }
+
+        syn::visit::visit_expr_method_call(self, call);
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Lists {
+    /// Exposed through a `ListView`, as getters do.
+    View,
+    /// Converted to a Python list.
+    List,
+}
+
+/// Maps Rust types onto Python ones.
+struct Types<'a> {
+    /// The Python names of the classes, by Rust name.
+    names: &'a HashMap<&'a str, &'a str>,
+    /// The Python name `Self` stands for.
+    self_name: Option<&'a str>,
+}
+
+impl Types<'_> {
+    fn returns(&self, output: &ReturnType) -> String {
+        match output {
+            ReturnType::Default => "None".to_string(),
+            ReturnType::Type(_, ty) => self.python(ty, Lists::List),
+        }
+    }
+
+    fn python(&self, ty: &Type, lists: Lists) -> String {
+        let path = match ty {
+            Type::Reference(reference) => return self.python(&reference.elem, lists),
+            Type::Tuple(tuple) if tuple.elems.is_empty() => return "None".to_string(),
+            Type::Tuple(tuple) => {
+                let elems = tuple
+                    .elems
+                    .iter()
+                    .map(|elem| self.python(elem, lists))
+                    .collect::<Vec<_>>();
+                return format!("tuple[{}]", elems.join(", "));
+            }
+            Type::Slice(slice) if last_ident(&slice.elem) == "u8" => return "bytes".to_string(),
+            Type::Path(path) => &path.path,
+            other => panic!("no Python type for `{}`", quote_type(other)),
+        };
+        let segment = path.segments.last().unwrap();
+        let args = match &segment.arguments {
+            PathArguments::AngleBracketed(args) => args
+                .args
+                .iter()
+                .filter_map(|arg| match arg {
+                    GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let arg = |index: usize| self.python(args[index], lists);
+
+        match segment.ident.to_string().as_str() {
+            "Option" => {
+                let inner = arg(0);
+                if inner.ends_with(" | None") {
+                    inner
+                } else {
+                    format!("{inner} | None")
+                }
+            }
+            "PyResult" | "Result" => arg(0),
+            "Arc" | "Box" | "Rc" | "Py" | "Bound" | "PyRef" | "Cow" => {
+                self.python(args.last().unwrap(), lists)
+            }
+            "Vec" => match lists {
+                Lists::View => format!("ListView[{}]", arg(0)),
+                Lists::List => format!("list[{}]", arg(0)),
+            },
+            "HashMap" | "BTreeMap" | "IndexMap" => format!("dict[{}, {}]", arg(0), arg(1)),
+            "String" | "str" | "PyString" => "str".to_string(),
+            "bool" => "bool".to_string(),
+            "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize" => {
+                "int".to_string()
+            }
+            "f32" | "f64" => "float".to_string(),
+            "DateTime" | "NaiveDateTime" | "PyDateTime" => "datetime".to_string(),
+            "PathBuf" | "Path" => "str | Path".to_string(),
+            "PyBytes" => "bytes".to_string(),
+            "PyDict" => "dict".to_string(),
+            "PyList" => "list".to_string(),
+            "PyAny" => "object".to_string(),
+            "Self" => self
+                .self_name
+                .expect("`Self` outside of a class")
+                .to_string(),
+            name => match self.names.get(name) {
+                Some(name) => name.to_string(),
+                None => panic!("no Python type for `{name}`"),
+            },
+        }
+    }
+}
+
+/// Render a `#[pyfunction]` or a method as a stub, with its docstring as the body if it has one.
+fn render_function(
+    attrs: &[Attribute],
+    sig: &syn::Signature,
+    types: &Types<'_>,
+    indent: &str,
+    is_method: bool,
+) -> String {
+    let rust_name = sig.ident.to_string();
+    let is_new = attrs.iter().any(|attr| is(attr, "new"));
+    let name = if is_new {
+        "__init__".to_string()
+    } else {
+        attrs
+            .iter()
+            .find_map(|attr| pyo3_arg(attr, "name"))
+            .map(|name| tokens_to_string(&name).trim_matches('"').to_string())
+            .unwrap_or_else(|| rust_name.clone())
+    };
+
+    let mut arguments = Vec::new();
+    for input in &sig.inputs {
+        let FnArg::Typed(arg) = input else {
+            continue;
+        };
+        // `Python<'py>` is handed over by pyo3, and `slf: PyRef<Self>` is `self` by another name.
+        if mentions(&arg.ty, "Python") || mentions(&arg.ty, "Self") {
+            continue;
+        }
+        let Pat::Ident(ident) = &*arg.pat else {
+            panic!("`{rust_name}` has a pattern argument");
+        };
+        let name = ident.ident.to_string();
+        let ty = PARAMETER_TYPES
+            .iter()
+            .find(|(parameter, _)| *parameter == name)
+            .map(|(_, ty)| ty.to_string())
+            .unwrap_or_else(|| types.python(&arg.ty, Lists::List));
+        arguments.push((name, ty));
+    }
+
+    let mut parameters = Vec::new();
+    if is_method {
+        parameters.push("self".to_string());
+    }
+    match attrs.iter().find_map(|attr| pyo3_arg(attr, "signature")) {
+        Some(signature) => {
+            let Some(TokenTree::Group(group)) = signature.first() else {
+                panic!("`{rust_name}` has a malformed signature");
+            };
+            for parameter in split_commas(group.stream()) {
+                let name = tokens_to_string(&parameter[..1]);
+                if name == "*" && parameter.len() == 1 {
+                    parameters.push(name);
+                    continue;
+                }
+                let (_, ty) = arguments
+                    .iter()
+                    .find(|(argument, _)| *argument == name)
+                    .unwrap_or_else(|| panic!("`{rust_name}` has no argument `{name}`"));
+                match parameter.get(2..) {
+                    Some(default) => parameters.push(format!(
+                        "{name}: {ty} = {}",
+                        python_default(&tokens_to_string(default))
+                    )),
+                    None => parameters.push(format!("{name}: {ty}")),
+                }
+            }
+        }
+        None => parameters.extend(arguments.iter().map(|(name, ty)| format!("{name}: {ty}"))),
+    }
+
+    let returns = if is_new {
+        "None".to_string()
+    } else {
+        RETURN_TYPES
+            .iter()
+            .find(|(function, _)| *function == rust_name)
+            .map(|(_, ty)| ty.to_string())
+            .unwrap_or_else(|| types.returns(&sig.output))
+    };
+
+    let doc = doc(attrs);
+    let end = if doc.is_empty() { " ..." } else { "" };
+    let mut stub = String::new();
+    if attrs.iter().any(|attr| is(attr, "staticmethod")) {
+        stub.push_str(&format!("{indent}@staticmethod\n"));
+    }
+
+    let line = format!(
+        "{indent}def {name}({}) -> {returns}:{end}",
+        parameters.join(", ")
+    );
+    let wrapped = format!("{indent}    {}", parameters.join(", "));
+    if line.len() <= LINE_LENGTH {
+        stub.push_str(&line);
+    } else if wrapped.len() <= LINE_LENGTH && !parameters.iter().any(|p| p == "self") {
+        stub.push_str(&format!(
+            "{indent}def {name}(\n{wrapped}\n{indent}) -> {returns}:{end}"
+        ));
+    } else {
+        stub.push_str(&format!("{indent}def {name}(\n"));
+        for parameter in &parameters {
+            stub.push_str(&format!("{indent}    {parameter},\n"));
+        }
+        stub.push_str(&format!("{indent}) -> {returns}:{end}"));
+    }
+    stub.push('\n');
+
+    if !doc.is_empty() {
+        stub.push_str(&docstring(&doc, &format!("{indent}    ")));
+    }
+
+    stub
+}
+
+/// Join rendered functions, setting those with a docstring apart from the next.
+fn join_functions(functions: &[String]) -> String {
+    let mut joined = String::new();
+    for (index, function) in functions.iter().enumerate() {
+        if index > 0 && functions[index - 1].ends_with("\"\"\"\n") {
+            joined.push('\n');
+        }
+        joined.push_str(function);
+    }
+
+    joined
+}
+
+/// The lines of an item's doc comment, up to its first heading.
+///
+/// Headings start the sections written for Rust readers, such as examples.
+fn doc(attrs: &[Attribute]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        let Meta::NameValue(meta) = &attr.meta else {
+            continue;
+        };
+        if !meta.path.is_ident("doc") {
+            continue;
+        }
+        let Expr::Lit(ExprLit {
+            lit: Lit::Str(line),
+            ..
+        }) = &meta.value
+        else {
+            continue;
+        };
+        let line = line.value();
+        let line = line
+            .strip_prefix(' ')
+            .unwrap_or(&line)
+            .trim_end()
+            .to_string();
+        if line.starts_with("# ") {
+            break;
+        }
+        lines.push(line);
+    }
+
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    lines
+}
+
+fn docstring(lines: &[String], indent: &str) -> String {
+    let escape = |line: &str| line.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+
+    let mut last = escape(lines.last().unwrap());
+    if last.ends_with('"') {
+        last.pop();
+        last.push_str("\\\"");
+    }
+    if lines.len() == 1 {
+        return format!("{indent}\"\"\"{last}\"\"\"\n");
+    }
+
+    let mut docstring = format!("{indent}\"\"\"{}\n", escape(&lines[0]));
+    for line in &lines[1..lines.len() - 1] {
+        if line.is_empty() {
+            docstring.push('\n');
+        } else {
+            docstring.push_str(&format!("{indent}{}\n", escape(line)));
+        }
+    }
+    docstring.push_str(&format!("{indent}{last}\n{indent}\"\"\"\n"));
+    docstring
+}
+
+/// Whether an attribute is `#[name]` or `#[name(...)]`.
+fn is(attr: &Attribute, name: &str) -> bool {
+    attr.path().is_ident(name)
+}
+
+/// The arguments of `#[pyclass(...)]`, also when it is behind `#[cfg_attr(feature = "python")]`.
+fn pyclass_args(attr: &Attribute) -> Option<Vec<Vec<TokenTree>>> {
+    if is(attr, "pyclass") {
+        return Some(match &attr.meta {
+            Meta::List(list) => split_commas(list.tokens.clone()),
+            _ => Vec::new(),
+        });
+    }
+    if !is(attr, "cfg_attr") {
+        return None;
+    }
+    let Meta::List(list) = &attr.meta else {
+        return None;
+    };
+
+    split_commas(list.tokens.clone())
+        .into_iter()
+        .find_map(|meta| match meta.as_slice() {
+            [TokenTree::Ident(ident)] if ident == "pyclass" => Some(Vec::new()),
+            [TokenTree::Ident(ident), TokenTree::Group(group)] if ident == "pyclass" => {
+                Some(split_commas(group.stream()))
+            }
+            _ => None,
+        })
+}
+
+/// The value of `key = value` among an attribute's arguments.
+fn key_arg(args: &[Vec<TokenTree>], key: &str) -> Option<Vec<TokenTree>> {
+    args.iter().find_map(|arg| match arg.as_slice() {
+        [TokenTree::Ident(ident), TokenTree::Punct(punct), value @ ..]
+            if ident == key && punct.as_char() == '=' =>
+        {
+            Some(value.to_vec())
+        }
+        _ => None,
+    })
+}
+
+fn string_arg(args: &[Vec<TokenTree>], key: &str) -> Option<String> {
+    key_arg(args, key).map(|value| tokens_to_string(&value).trim_matches('"').to_string())
+}
+
+fn flag_arg(args: &[Vec<TokenTree>], flag: &str) -> bool {
+    args.iter()
+        .any(|arg| matches!(arg.as_slice(), [TokenTree::Ident(ident)] if ident == flag))
+}
+
+/// The value of `key = value` in a `#[pyo3(...)]` attribute.
+fn pyo3_arg(attr: &Attribute, key: &str) -> Option<Vec<TokenTree>> {
+    match &attr.meta {
+        Meta::List(list) if is(attr, "pyo3") => key_arg(&split_commas(list.tokens.clone()), key),
+        _ => None,
+    }
+}
+
+/// Whether a `#[pyo3(...)]` attribute holds a bare `flag`.
+fn pyo3_flag(attr: &Attribute, flag: &str) -> bool {
+    match &attr.meta {
+        Meta::List(list) if is(attr, "pyo3") => flag_arg(&split_commas(list.tokens.clone()), flag),
+        _ => false,
+    }
+}
+
+fn split_commas(tokens: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut parts = vec![Vec::new()];
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => parts.push(Vec::new()),
+            _ => parts.last_mut().unwrap().push(token),
+        }
+    }
+
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+fn tokens_to_string(tokens: &[TokenTree]) -> String {
+    tokens.iter().map(ToString::to_string).collect()
+}
+
+/// Whether `name` appears anywhere in a type, such as `Self` in `PyRef<'_, Self>`.
+fn mentions(ty: &Type, name: &str) -> bool {
+    fn walk(tokens: TokenStream, name: &str) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => ident == name,
+            TokenTree::Group(group) => walk(group.stream(), name),
+            _ => false,
+        })
+    }
+
+    walk(quote_tokens(ty), name)
+}
+
+fn quote_tokens(ty: &Type) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    quote::ToTokens::to_tokens(ty, &mut tokens);
+    tokens
+}
+
+fn quote_type(ty: &Type) -> String {
+    quote_tokens(ty).to_string()
+}
+
+fn last_ident(ty: &Type) -> String {
+    match ty {
+        Type::Path(path) => path.path.segments.last().unwrap().ident.to_string(),
+        other => panic!("expected a type name, found `{}`", quote_type(other)),
+    }
+}
+
+/// A default from a `#[pyo3(signature)]`, written as Python.
+fn python_default(default: &str) -> String {
+    match default {
+        "true" => "True".to_string(),
+        "false" => "False".to_string(),
+        other => other.to_string(),
+    }
+}
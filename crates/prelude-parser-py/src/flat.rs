@@ -0,0 +1,876 @@
+//! Reading flat exports into plain Rust tables.
+//!
+//! Nothing here touches Python, so a whole parse runs with the GIL released. The tables keep each
+//! value as the text it was in the file alongside the [`ColumnType`] decided for its column, and
+//! are only converted into Python objects once they are complete.
+
+use std::{
+    collections::{HashMap, HashSet},
+    str::from_utf8,
+    str::FromStr,
+};
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use pyo3::{exceptions::PyValueError, PyResult};
+use quick_xml::{
+    escape::resolve_predefined_entity,
+    events::{BytesRef, Event},
+    Reader,
+};
+
+use crate::{errors::ParsingError, partial_date::PartialDate, utils::to_snake};
+
+/// The type a whole column is given, decided from every value in it rather than value by value.
+///
+/// Per-value typing lets one column hold several Python types and, worse, merges distinct
+/// identifiers: `"0067"` and `"67"` both become `67`. Deciding per column keeps a column's type
+/// stable and keeps zero-padded identifiers intact.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ColumnType {
+    /// No non-empty value seen yet.
+    Unknown,
+    Integer,
+    Float,
+    Date,
+    DateTime,
+    Time,
+    /// A date with some of its parts unknown, such as `UN-JAN-2023`, or a full date in a column
+    /// that also has those.
+    PartialDate,
+    Boolean,
+    Text,
+    /// Text with few distinct values, which a `DataFrame` stores as a categorical.
+    Category,
+}
+
+impl ColumnType {
+    /// The name a column type is given by in `dtypes`.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ColumnType::Unknown => "unknown",
+            ColumnType::Integer => "int",
+            ColumnType::Float => "float",
+            ColumnType::Date => "date",
+            ColumnType::DateTime => "datetime",
+            ColumnType::Time => "time",
+            ColumnType::PartialDate => "partial_date",
+            ColumnType::Boolean => "bool",
+            ColumnType::Text => "str",
+            ColumnType::Category => "category",
+        }
+    }
+
+    /// Whether `value` can be read as this type.
+    fn admits(self, value: &str) -> bool {
+        if value.is_empty() {
+            return true;
+        }
+
+        match self {
+            ColumnType::Integer => value.parse::<i64>().is_ok(),
+            ColumnType::Float => value.parse::<f64>().is_ok(),
+            ColumnType::Date => parse_date(value).is_some(),
+            ColumnType::DateTime => parse_datetime(value).is_some(),
+            ColumnType::Time => parse_time(value).is_some(),
+            ColumnType::PartialDate => parse_partial_date(value).is_some(),
+            ColumnType::Boolean => parse_bool(value).is_some(),
+            ColumnType::Unknown | ColumnType::Text | ColumnType::Category => true,
+        }
+    }
+}
+
+impl FromStr for ColumnType {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "int" => Ok(ColumnType::Integer),
+            "float" => Ok(ColumnType::Float),
+            "date" => Ok(ColumnType::Date),
+            "datetime" => Ok(ColumnType::DateTime),
+            "time" => Ok(ColumnType::Time),
+            "partial_date" => Ok(ColumnType::PartialDate),
+            "bool" => Ok(ColumnType::Boolean),
+            "str" => Ok(ColumnType::Text),
+            "category" => Ok(ColumnType::Category),
+            other => Err(format!(
+                "dtype must be one of 'str', 'int', 'float', 'date', 'datetime', 'time', \
+                 'partial_date', 'bool' or 'category', got {other:?}"
+            )),
+        }
+    }
+}
+
+/// How a flat export is read.
+#[derive(Default)]
+pub(crate) struct FlatOptions {
+    /// Whether the export was made with short names, which are lowercased rather than converted
+    /// to snake case.
+    pub(crate) short_names: bool,
+    /// Column types to use instead of inferring them, by form and then column name.
+    pub(crate) dtypes: HashMap<String, HashMap<String, ColumnType>>,
+}
+
+impl FlatOptions {
+    /// The types given for columns in any form, for reading every form into one table.
+    ///
+    /// The forms share their columns there, so a type given for a column in one form applies to
+    /// the column in all of them.
+    fn column_dtypes(&self) -> PyResult<HashMap<&str, ColumnType>> {
+        let mut column_dtypes: HashMap<&str, ColumnType> = HashMap::new();
+        let mut forms: Vec<_> = self.dtypes.iter().collect();
+        forms.sort_by_key(|(form, _)| *form);
+
+        for (_, columns) in forms {
+            for (column, &dtype) in columns {
+                match column_dtypes.insert(column, dtype) {
+                    Some(other) if other != dtype => {
+                        return Err(PyValueError::new_err(format!(
+                            "dtypes give column {column:?} both {:?} and {:?}, but the forms share \
+                             their columns in a DataFrame",
+                            other.name(),
+                            dtype.name()
+                        )));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(column_dtypes)
+    }
+
+    /// Check that every form and column named in `dtypes` is in the export, so that a misspelt
+    /// name is not quietly left unused.
+    ///
+    /// `seen` has the names of the columns of each form.
+    fn check_dtypes_used(&self, seen: &HashMap<&str, HashSet<&str>>) -> PyResult<()> {
+        let mut forms: Vec<_> = self.dtypes.iter().collect();
+        forms.sort_by_key(|(form, _)| *form);
+
+        for (form, columns) in forms {
+            let Some(seen_columns) = seen.get(form.as_str()) else {
+                return Err(PyValueError::new_err(format!(
+                    "dtypes name form {form:?}, which is not in the export"
+                )));
+            };
+
+            let mut columns: Vec<_> = columns.keys().collect();
+            columns.sort();
+            if let Some(column) = columns
+                .into_iter()
+                .find(|column| !seen_columns.contains(column.as_str()))
+            {
+                return Err(PyValueError::new_err(format!(
+                    "dtypes name column {column:?} of form {form:?}, which is not in the form"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+const DATE_FORMATS: &[&str] = &["%d-%b-%Y", "%Y-%m-%d"];
+
+const DATETIME_FORMATS: &[&str] = &[
+    "%d-%b-%Y %H:%M:%S",
+    "%d-%b-%Y %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+];
+
+const TIME_FORMATS: &[&str] = &["%H:%M", "%H:%M:%S"];
+
+pub(crate) fn parse_date(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+/// Parse a datetime, or a date as its midnight.
+pub(crate) fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| parse_date(value).and_then(|date| date.and_hms_opt(0, 0, 0)))
+}
+
+pub(crate) fn parse_time(value: &str) -> Option<NaiveTime> {
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(value, format).ok())
+}
+
+/// Parse a partial date, or a full date as one with every part known.
+pub(crate) fn parse_partial_date(value: &str) -> Option<PartialDate> {
+    PartialDate::parse(value).or_else(|| parse_date(value).map(PartialDate::from_date))
+}
+
+pub(crate) fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "yes" | "y" | "true" | "1" => Some(true),
+        "no" | "n" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Classify a single value in isolation.
+fn classify(value: &str) -> ColumnType {
+    if value.is_empty() {
+        return ColumnType::Unknown;
+    }
+
+    // A zero-padded number is an identifier, not a quantity: parsing it loses both the padding
+    // and the distinction between "0067" and "67".
+    let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+    if digits.len() > 1 && digits.starts_with('0') && digits.bytes().all(|b| b.is_ascii_digit()) {
+        return ColumnType::Text;
+    }
+
+    if value.parse::<i64>().is_ok() {
+        return ColumnType::Integer;
+    }
+
+    // Requiring a digit keeps "nan", "inf" and "infinity" as the text they almost certainly are.
+    if value.bytes().any(|b| b.is_ascii_digit()) && value.parse::<f64>().is_ok() {
+        return ColumnType::Float;
+    }
+
+    // Only the words a checkbox is exported as: "1", "0", "Y" and "N" are more often codes.
+    if ["yes", "no", "true", "false"]
+        .iter()
+        .any(|word| value.eq_ignore_ascii_case(word))
+    {
+        return ColumnType::Boolean;
+    }
+
+    if parse_date(value).is_some() {
+        return ColumnType::Date;
+    }
+
+    if DATETIME_FORMATS
+        .iter()
+        .any(|format| NaiveDateTime::parse_from_str(value, format).is_ok())
+    {
+        return ColumnType::DateTime;
+    }
+
+    if parse_time(value).is_some() {
+        return ColumnType::Time;
+    }
+
+    if PartialDate::parse(value).is_some() {
+        return ColumnType::PartialDate;
+    }
+
+    ColumnType::Text
+}
+
+/// Widen a column's type to also admit `value`.
+fn widen(current: ColumnType, value: &str) -> ColumnType {
+    combine(current, classify(value))
+}
+
+/// The narrowest type that admits the values of both types.
+fn combine(current: ColumnType, seen: ColumnType) -> ColumnType {
+    match (current, seen) {
+        (ColumnType::Unknown, other) | (other, ColumnType::Unknown) => other,
+        (a, b) if a == b => a,
+        (ColumnType::Integer, ColumnType::Float) | (ColumnType::Float, ColumnType::Integer) => {
+            ColumnType::Float
+        }
+        // A date is midnight, or a partial date with every part known.
+        (ColumnType::Date, ColumnType::DateTime) | (ColumnType::DateTime, ColumnType::Date) => {
+            ColumnType::DateTime
+        }
+        (ColumnType::Date, ColumnType::PartialDate)
+        | (ColumnType::PartialDate, ColumnType::Date) => ColumnType::PartialDate,
+        _ => ColumnType::Text,
+    }
+}
+
+pub(crate) fn xml_error(e: impl std::fmt::Display) -> pyo3::PyErr {
+    ParsingError::new_err(format!("Error parsing xml file: {e}"))
+}
+
+/// Append a `&...;` reference to the text being accumulated.
+///
+/// quick-xml reports references as their own events, so a value's text has to be reassembled from
+/// the surrounding text events and these. This matches what a DOM parser hands back as the node's
+/// text. An unresolvable reference is kept verbatim rather than dropped.
+fn push_general_ref(text: &mut String, reference: &BytesRef<'_>) -> PyResult<()> {
+    if let Some(character) = reference.resolve_char_ref().map_err(xml_error)? {
+        text.push(character);
+        return Ok(());
+    }
+
+    let name = reference.xml10_content().map_err(xml_error)?;
+
+    match resolve_predefined_entity(&name) {
+        Some(resolved) => text.push_str(resolved),
+        None => {
+            text.push('&');
+            text.push_str(&name);
+            text.push(';');
+        }
+    }
+
+    Ok(())
+}
+
+fn convert_name(raw: &str, short_names: bool) -> String {
+    if short_names {
+        raw.to_lowercase()
+    } else {
+        to_snake(raw)
+    }
+}
+
+/// The columns of a table, in the order they were first seen.
+#[derive(Default)]
+pub(crate) struct Columns {
+    pub(crate) names: Vec<String>,
+    pub(crate) types: Vec<ColumnType>,
+    /// Whether each column's type was given in [`FlatOptions::dtypes`] rather than inferred.
+    fixed: Vec<bool>,
+    index: HashMap<Vec<u8>, usize>,
+}
+
+impl Columns {
+    /// The position of the column for the element `raw`, adding it if it is new.
+    ///
+    /// `dtypes` are the column types given for the form the column is first seen in.
+    fn column<K>(
+        &mut self,
+        raw: &[u8],
+        short_names: bool,
+        dtypes: Option<&HashMap<K, ColumnType>>,
+    ) -> PyResult<usize>
+    where
+        K: std::borrow::Borrow<str> + std::hash::Hash + Eq,
+    {
+        if let Some(index) = self.index.get(raw) {
+            return Ok(*index);
+        }
+
+        let name = convert_name(from_utf8(raw).map_err(xml_error)?, short_names);
+        let dtype = dtypes.and_then(|dtypes| dtypes.get(name.as_str()));
+        self.types
+            .push(dtype.copied().unwrap_or(ColumnType::Unknown));
+        self.fixed.push(dtype.is_some());
+        self.names.push(name);
+        self.index.insert(raw.to_vec(), self.names.len() - 1);
+
+        Ok(self.names.len() - 1)
+    }
+
+    /// Widen a column's type to also admit `value`, or check that it does if the type was given.
+    fn widen(&mut self, column: usize, value: &str, form: &str) -> PyResult<()> {
+        let column_type = self.types[column];
+        if !self.fixed[column] {
+            self.types[column] = widen(column_type, value);
+        } else if !column_type.admits(value) {
+            return Err(PyValueError::new_err(format!(
+                "Cannot read {value:?} in column {:?} of form {form:?} as {}",
+                self.names[column],
+                column_type.name()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// A form's accumulated rows plus the columns discovered for it.
+pub(crate) struct FormTable {
+    pub(crate) name: String,
+    pub(crate) columns: Columns,
+    pub(crate) rows: Vec<Vec<(usize, Option<String>)>>,
+}
+
+/// What was inferred about one column of a form, for spotting values that do not fit.
+pub(crate) struct ColumnSchema<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) column_type: ColumnType,
+    /// Records where the column is empty or missing.
+    pub(crate) null_count: usize,
+    /// Distinct non-empty values.
+    pub(crate) distinct_count: usize,
+    /// For a column inferred as text, the first value that does not fit the type the rest of its
+    /// values would have.
+    pub(crate) widened_by: Option<&'a str>,
+}
+
+/// The first of a column's values that does not fit the type most of them are read as.
+///
+/// Which value is reported does not depend on where the dirty values are: a column of dates
+/// reports its `N/A` whether that comes first or last.
+fn widened_by<'a>(values: &[&'a str]) -> Option<&'a str> {
+    // How many values are read as each type, in the order the types are first seen.
+    let mut counts: Vec<(ColumnType, usize)> = Vec::new();
+    for value in values {
+        let seen = classify(value);
+        match counts
+            .iter_mut()
+            .find(|(column_type, _)| *column_type == seen)
+        {
+            Some((_, count)) => *count += 1,
+            None => counts.push((seen, 1)),
+        }
+    }
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    // Widen from the most common type by every other type that fits alongside it, such as floats
+    // in a column of integers.
+    let mut expected = ColumnType::Unknown;
+    for (seen, _) in counts {
+        let combined = combine(expected, seen);
+        if combined != ColumnType::Text {
+            expected = combined;
+        }
+    }
+    if expected == ColumnType::Unknown {
+        return None;
+    }
+
+    values
+        .iter()
+        .copied()
+        .find(|value| widen(expected, value) == ColumnType::Text)
+}
+
+impl FormTable {
+    /// Describe each of the form's columns, in the order they were first seen.
+    pub(crate) fn schema(&self) -> Vec<ColumnSchema<'_>> {
+        let mut values: Vec<Vec<&str>> = vec![Vec::new(); self.columns.names.len()];
+        let mut distinct: Vec<HashSet<&str>> = vec![HashSet::new(); self.columns.names.len()];
+        let mut present = vec![0usize; self.columns.names.len()];
+        // The last row each column was counted as present in, since a record can repeat an
+        // element.
+        let mut counted_in: Vec<Option<usize>> = vec![None; self.columns.names.len()];
+        for (position, row) in self.rows.iter().enumerate() {
+            for (column, value) in row {
+                if let Some(value) = value.as_deref().filter(|value| !value.is_empty()) {
+                    values[*column].push(value);
+                    distinct[*column].insert(value);
+                    if counted_in[*column] != Some(position) {
+                        counted_in[*column] = Some(position);
+                        present[*column] += 1;
+                    }
+                }
+            }
+        }
+
+        self.columns
+            .names
+            .iter()
+            .enumerate()
+            .map(|(column, name)| {
+                let column_type = self.columns.types[column];
+                let inferred_text = column_type == ColumnType::Text && !self.columns.fixed[column];
+                ColumnSchema {
+                    name,
+                    column_type,
+                    null_count: self.rows.len() - present[column],
+                    distinct_count: distinct[column].len(),
+                    widened_by: inferred_text.then(|| widened_by(&values[column])).flatten(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Every value in an export, by column rather than by row, with the columns of all its forms
+/// together.
+#[derive(Default)]
+pub(crate) struct ColumnTable {
+    pub(crate) columns: Columns,
+    pub(crate) values: Vec<Vec<Option<String>>>,
+}
+
+fn no_root() -> pyo3::PyErr {
+    ParsingError::new_err("Error parsing xml file: no root element found")
+}
+
+/// Read an export into one table per form, in the order the forms first appear.
+pub(crate) fn read_tables(contents: &str, options: &FlatOptions) -> PyResult<Vec<FormTable>> {
+    let mut reader = Reader::from_str(contents);
+    reader.config_mut().trim_text(false);
+
+    // Forms are kept in the order they appear so the resulting dict is reproducible.
+    let mut tables: Vec<FormTable> = Vec::new();
+    let mut table_index: HashMap<Vec<u8>, usize> = HashMap::new();
+
+    let mut table_for = |raw: &[u8], tables: &mut Vec<FormTable>| -> PyResult<usize> {
+        if let Some(index) = table_index.get(raw) {
+            return Ok(*index);
+        }
+
+        let name = convert_name(from_utf8(raw).map_err(xml_error)?, options.short_names);
+        tables.push(FormTable {
+            name,
+            columns: Columns::default(),
+            rows: Vec::new(),
+        });
+        table_index.insert(raw.to_vec(), tables.len() - 1);
+
+        Ok(tables.len() - 1)
+    };
+
+    let mut depth = 0usize;
+    let mut saw_root = false;
+    let mut current_table: Option<usize> = None;
+    let mut row: Vec<(usize, Option<String>)> = Vec::new();
+    let mut column: Option<usize> = None;
+    let mut text: Option<String> = None;
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Eof => break,
+
+            Event::Start(e) => {
+                depth += 1;
+                saw_root = true;
+
+                match depth {
+                    2 => {
+                        current_table = Some(table_for(e.name().into_inner(), &mut tables)?);
+                        row = Vec::new();
+                    }
+                    3 => {
+                        if let Some(index) = current_table {
+                            let table = &mut tables[index];
+                            column = Some(table.columns.column(
+                                e.name().into_inner(),
+                                options.short_names,
+                                options.dtypes.get(&table.name),
+                            )?);
+                        }
+                        text = None;
+                    }
+                    _ => {}
+                }
+            }
+
+            Event::Empty(e) => match depth + 1 {
+                2 => {
+                    let index = table_for(e.name().into_inner(), &mut tables)?;
+                    tables[index].rows.push(Vec::new());
+                }
+                3 => {
+                    if let Some(index) = current_table {
+                        let table = &mut tables[index];
+                        let column = table.columns.column(
+                            e.name().into_inner(),
+                            options.short_names,
+                            options.dtypes.get(&table.name),
+                        )?;
+                        row.push((column, None));
+                    }
+                }
+                _ => {}
+            },
+
+            Event::Text(e) if depth == 3 => {
+                let decoded = e.xml10_content().map_err(xml_error)?;
+                text.get_or_insert_with(String::new).push_str(&decoded);
+            }
+
+            Event::GeneralRef(ref e) if depth == 3 => {
+                push_general_ref(text.get_or_insert_with(String::new), e)?;
+            }
+
+            Event::End(_) => {
+                match depth {
+                    3 => {
+                        if let (Some(index), Some(column)) = (current_table, column.take()) {
+                            let value = text.take();
+                            if let Some(ref value) = value {
+                                let table = &mut tables[index];
+                                table.columns.widen(column, value, &table.name)?;
+                            }
+                            row.push((column, value));
+                        }
+                        text = None;
+                    }
+                    2 => {
+                        if let Some(index) = current_table.take() {
+                            tables[index].rows.push(std::mem::take(&mut row));
+                        }
+                    }
+                    _ => {}
+                }
+                depth -= 1;
+            }
+
+            _ => {}
+        }
+    }
+
+    if !saw_root {
+        return Err(no_root());
+    }
+
+    let seen = tables
+        .iter()
+        .map(|table| {
+            let columns = table.columns.names.iter().map(String::as_str).collect();
+            (table.name.as_str(), columns)
+        })
+        .collect();
+    options.check_dtypes_used(&seen)?;
+
+    Ok(tables)
+}
+
+/// Read an export into a single table by column, for a `DataFrame`.
+pub(crate) fn read_columns(contents: &str, options: &FlatOptions) -> PyResult<ColumnTable> {
+    let mut reader = Reader::from_str(contents);
+    reader.config_mut().trim_text(false);
+
+    let mut table = ColumnTable::default();
+    // The names of the forms, converted once per form rather than once per record.
+    let mut forms: Vec<String> = Vec::new();
+    let mut form_index: HashMap<Vec<u8>, usize> = HashMap::new();
+    // The columns seen in each form, by position in `forms`.
+    let mut form_columns: Vec<HashSet<usize>> = Vec::new();
+    let column_dtypes = options.column_dtypes()?;
+
+    let column_for = |raw: &[u8], table: &mut ColumnTable| -> PyResult<usize> {
+        let column = table
+            .columns
+            .column(raw, options.short_names, Some(&column_dtypes))?;
+        if column == table.values.len() {
+            table.values.push(Vec::new());
+        }
+
+        Ok(column)
+    };
+
+    let mut depth = 0usize;
+    let mut saw_root = false;
+    let mut form = None;
+    let mut column: Option<usize> = None;
+    let mut text: Option<String> = None;
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Eof => break,
+
+            Event::Start(e) => {
+                depth += 1;
+                saw_root = true;
+                match depth {
+                    2 => {
+                        let raw = e.name().into_inner();
+                        form = match form_index.get(raw) {
+                            Some(index) => Some(*index),
+                            None => {
+                                let name = convert_name(
+                                    from_utf8(raw).map_err(xml_error)?,
+                                    options.short_names,
+                                );
+                                forms.push(name);
+                                form_columns.push(HashSet::new());
+                                form_index.insert(raw.to_vec(), forms.len() - 1);
+                                Some(forms.len() - 1)
+                            }
+                        };
+                    }
+                    3 => {
+                        let found = column_for(e.name().into_inner(), &mut table)?;
+                        if let Some(index) = form {
+                            form_columns[index].insert(found);
+                        }
+                        column = Some(found);
+                        text = None;
+                    }
+                    _ => {}
+                }
+            }
+
+            Event::Empty(e) if depth + 1 == 3 => {
+                let column = column_for(e.name().into_inner(), &mut table)?;
+                if let Some(index) = form {
+                    form_columns[index].insert(column);
+                }
+                table.values[column].push(None);
+            }
+
+            Event::Text(e) if depth == 3 => {
+                let decoded = e.xml10_content().map_err(xml_error)?;
+                text.get_or_insert_with(String::new).push_str(&decoded);
+            }
+
+            Event::GeneralRef(ref e) if depth == 3 => {
+                push_general_ref(text.get_or_insert_with(String::new), e)?;
+            }
+
+            Event::End(_) => {
+                if depth == 3 {
+                    if let Some(column) = column.take() {
+                        let value = text.take();
+                        if let Some(ref value) = value {
+                            let form = form.map_or("", |index| forms[index].as_str());
+                            table.columns.widen(column, value, form)?;
+                        }
+                        table.values[column].push(value);
+                    }
+                    text = None;
+                }
+                depth -= 1;
+            }
+
+            _ => {}
+        }
+    }
+
+    if !saw_root {
+        return Err(no_root());
+    }
+
+    let seen = forms
+        .iter()
+        .zip(&form_columns)
+        .map(|(form, columns)| {
+            let columns = columns
+                .iter()
+                .map(|&column| table.columns.names[column].as_str())
+                .collect();
+            (form.as_str(), columns)
+        })
+        .collect();
+    options.check_dtypes_used(&seen)?;
+
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dtype_names_parse() {
+        for column_type in [
+            ColumnType::Integer,
+            ColumnType::Float,
+            ColumnType::Date,
+            ColumnType::DateTime,
+            ColumnType::Time,
+            ColumnType::PartialDate,
+            ColumnType::Boolean,
+            ColumnType::Text,
+            ColumnType::Category,
+        ] {
+            assert_eq!(column_type.name().parse(), Ok(column_type));
+        }
+
+        assert!("unknown".parse::<ColumnType>().is_err());
+    }
+
+    #[test]
+    fn classify_values() {
+        assert_eq!(classify("15-Apr-2023"), ColumnType::Date);
+        assert_eq!(classify("2023-04-15"), ColumnType::Date);
+        assert_eq!(classify("15-Apr-2023 12:09"), ColumnType::DateTime);
+        assert_eq!(classify("12:09"), ColumnType::Time);
+        assert_eq!(classify("Yes"), ColumnType::Boolean);
+        assert_eq!(classify("UN-JAN-2023"), ColumnType::PartialDate);
+        assert_eq!(classify("1"), ColumnType::Integer);
+        assert_eq!(classify("Y"), ColumnType::Text);
+    }
+
+    #[test]
+    fn widen_dates() {
+        assert_eq!(
+            widen(ColumnType::Date, "15-Apr-2023 12:09"),
+            ColumnType::DateTime
+        );
+        assert_eq!(
+            widen(ColumnType::Date, "2023-UN-UN"),
+            ColumnType::PartialDate
+        );
+        assert_eq!(
+            widen(ColumnType::PartialDate, "15-Apr-2023"),
+            ColumnType::PartialDate
+        );
+        assert_eq!(widen(ColumnType::DateTime, "2023-UN-UN"), ColumnType::Text);
+        assert_eq!(widen(ColumnType::Boolean, "1"), ColumnType::Text);
+        assert_eq!(widen(ColumnType::Time, "12:09:02"), ColumnType::Time);
+    }
+
+    /// A form with one column per entry of `columns`, each holding the given values by record.
+    fn table(columns: &[(&str, &[Option<&str>])]) -> FormTable {
+        let mut table = FormTable {
+            name: "visits".to_string(),
+            columns: Columns::default(),
+            rows: Vec::new(),
+        };
+        for (column, (name, values)) in columns.iter().enumerate() {
+            table.columns.names.push(name.to_string());
+            table.columns.fixed.push(false);
+            let mut column_type = ColumnType::Unknown;
+            for (position, value) in values.iter().enumerate() {
+                if position == table.rows.len() {
+                    table.rows.push(Vec::new());
+                }
+                if let Some(value) = value {
+                    column_type = widen(column_type, value);
+                }
+                table.rows[position].push((column, value.map(str::to_string)));
+            }
+            table.columns.types.push(column_type);
+        }
+
+        table
+    }
+
+    #[test]
+    fn schema_counts_repeated_elements_once() {
+        let mut table = table(&[("a", &[Some("1"), None])]);
+        table.rows[0].push((0, Some("2".to_string())));
+
+        let schema = table.schema();
+
+        assert_eq!(schema[0].null_count, 1);
+        assert_eq!(schema[0].distinct_count, 2);
+    }
+
+    #[test]
+    fn schema_reports_dirty_values_wherever_they_are() {
+        let first = [Some("N/A"), Some("15-Apr-2023"), Some("16-Apr-2023")];
+        let last = [Some("15-Apr-2023"), Some("16-Apr-2023"), Some("N/A")];
+
+        for values in [&first[..], &last[..]] {
+            assert_eq!(table(&[("a", values)]).schema()[0].widened_by, Some("N/A"));
+        }
+    }
+
+    #[test]
+    fn schema_widened_by() {
+        for (values, expected) in [
+            (&[Some("1"), Some("1.5"), Some("x")][..], Some("x")),
+            (&[Some("x"), Some("y")][..], None),
+            (
+                &[Some("1"), Some("2"), Some("15-Apr-2023")][..],
+                Some("15-Apr-2023"),
+            ),
+            (&[Some("1"), Some("2.5")][..], None),
+            (&[None, None][..], None),
+        ] {
+            assert_eq!(table(&[("a", values)]).schema()[0].widened_by, expected);
+        }
+    }
+
+    #[test]
+    fn parse_datetime_accepts_dates() {
+        assert_eq!(
+            parse_datetime("15-Apr-2023"),
+            NaiveDate::from_ymd_opt(2023, 4, 15).and_then(|date| date.and_hms_opt(0, 0, 0))
+        );
+        assert!(parse_datetime("15-Apr-2023 12:09").is_some());
+        assert!(parse_datetime("Apr 2023").is_none());
+    }
+}
@@ -1,39 +1,43 @@
 mod errors;
+mod flat;
+mod partial_date;
+mod progress;
 mod utils;
 
-use std::{collections::HashMap, fs::read_to_string, path::PathBuf, str::from_utf8};
+use std::{collections::HashMap, fs::read, path::PathBuf};
 
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, Timelike};
 use prelude_xml_parser::{
+    encoding::decode,
     native::{
         common::{
             Category, Comment, Entry, Export, Field, File, Form, LockState, Query, Reason, State,
             Value,
         },
+        list_view::ListView,
         site_native::{Site, SiteNative},
         subject_native::{Patient, SubjectNative},
         user_native::{User, UserNative},
     },
-    parse_site_native_file as parse_site_native_file_rs,
-    parse_site_native_string as parse_site_native_string_rs,
-    parse_subject_native_file as parse_subject_native_file_rs,
-    parse_subject_native_string as parse_subject_native_string_rs,
-    parse_user_native_file as parse_user_native_file_rs,
-    parse_user_native_string as parse_user_native_string_rs,
+    options::History,
+    selector::PyMatch,
+    NativeParser,
 };
 use pyo3::{
+    exceptions::PyValueError,
     prelude::*,
-    types::{PyDict, PyList, PyString},
-};
-use quick_xml::{
-    escape::resolve_predefined_entity,
-    events::{BytesRef, Event},
-    Reader,
+    types::{PyDate, PyDateTime, PyDict, PyList, PyString, PyTime},
 };
 
 use crate::{
     errors::{FileNotFoundError, InvalidFileTypeError, ParsingError, XmlFileValidationError},
-    utils::{to_snake, validate_file},
+    flat::{
+        parse_bool, parse_date, parse_datetime, parse_partial_date, parse_time, read_columns,
+        read_tables, xml_error, ColumnTable, ColumnType, FlatOptions, FormTable,
+    },
+    partial_date::PartialDate,
+    progress::{run_with_progress, ParseProgress},
+    utils::validate_file,
 };
 
 fn check_valid_file(xml_file: &PathBuf) -> PyResult<()> {
@@ -60,70 +64,20 @@ fn check_valid_file(xml_file: &PathBuf) -> PyResult<()> {
     Ok(())
 }
 
-/// The type a whole column is given, decided from every value in it rather than value by value.
-///
-/// Per-value typing lets one column hold several Python types and, worse, merges distinct
-/// identifiers: `"0067"` and `"67"` both become `67`. Deciding per column keeps a column's type
-/// stable and keeps zero-padded identifiers intact.
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum ColumnType {
-    /// No non-empty value seen yet.
-    Unknown,
-    Integer,
-    Float,
-    Date,
-    Text,
-}
-
-/// Classify a single value in isolation.
-fn classify(value: &str) -> ColumnType {
-    if value.is_empty() {
-        return ColumnType::Unknown;
-    }
-
-    // A zero-padded number is an identifier, not a quantity: parsing it loses both the padding
-    // and the distinction between "0067" and "67".
-    let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
-    if digits.len() > 1 && digits.starts_with('0') && digits.bytes().all(|b| b.is_ascii_digit()) {
-        return ColumnType::Text;
-    }
-
-    if value.parse::<i64>().is_ok() {
-        return ColumnType::Integer;
-    }
-
-    // Requiring a digit keeps "nan", "inf" and "infinity" as the text they almost certainly are.
-    if value.bytes().any(|b| b.is_ascii_digit()) && value.parse::<f64>().is_ok() {
-        return ColumnType::Float;
-    }
-
-    if NaiveDate::parse_from_str(value, "%d-%b-%Y").is_ok() {
-        return ColumnType::Date;
-    }
+/// Read a flat export from disk, decoded from the encoding it declares.
+fn read_flat_file(xml_file: &PathBuf) -> PyResult<String> {
+    check_valid_file(xml_file)?;
+    let bytes = read(xml_file).map_err(xml_error)?;
+    let contents = decode(&bytes).map_err(xml_error)?;
 
-    ColumnType::Text
-}
-
-/// Widen a column's type to also admit `value`.
-fn widen(current: ColumnType, value: &str) -> ColumnType {
-    let seen = classify(value);
-
-    match (current, seen) {
-        (ColumnType::Unknown, other) | (other, ColumnType::Unknown) => other,
-        (a, b) if a == b => a,
-        (ColumnType::Integer, ColumnType::Float) | (ColumnType::Float, ColumnType::Integer) => {
-            ColumnType::Float
-        }
-        _ => ColumnType::Text,
-    }
+    Ok(contents.into_owned())
 }
 
 /// Convert a value according to the type decided for its column.
-fn to_py_value<'py>(
-    py: Python<'py>,
+fn to_py_value(
+    py: Python<'_>,
     value: Option<&str>,
     column_type: ColumnType,
-    date: &Bound<'py, PyAny>,
 ) -> PyResult<Py<PyAny>> {
     let Some(text) = value else {
         return Ok(py.None());
@@ -142,12 +96,48 @@ fn to_py_value<'py>(
             Ok(v) => v.into_pyobject(py)?.into_any().unbind(),
             Err(_) => text.into_pyobject(py)?.into_any().unbind(),
         },
-        ColumnType::Date => match NaiveDate::parse_from_str(text, "%d-%b-%Y") {
-            Ok(d) => date
-                .call1((d.year(), d.month(), d.day()))?
+        ColumnType::Date => match parse_date(text) {
+            Some(d) => PyDate::new(py, d.year(), d.month() as u8, d.day() as u8)?
                 .into_any()
                 .unbind(),
-            Err(_) => text.into_pyobject(py)?.into_any().unbind(),
+            None => text.into_pyobject(py)?.into_any().unbind(),
+        },
+        ColumnType::DateTime => match parse_datetime(text) {
+            Some(d) => PyDateTime::new(
+                py,
+                d.year(),
+                d.month() as u8,
+                d.day() as u8,
+                d.hour() as u8,
+                d.minute() as u8,
+                d.second() as u8,
+                0,
+                None,
+            )?
+            .into_any()
+            .unbind(),
+            None => text.into_pyobject(py)?.into_any().unbind(),
+        },
+        ColumnType::Time => match parse_time(text) {
+            Some(t) => PyTime::new(
+                py,
+                t.hour() as u8,
+                t.minute() as u8,
+                t.second() as u8,
+                0,
+                None,
+            )?
+            .into_any()
+            .unbind(),
+            None => text.into_pyobject(py)?.into_any().unbind(),
+        },
+        ColumnType::PartialDate => match parse_partial_date(text) {
+            Some(d) => Bound::new(py, d)?.into_any().unbind(),
+            None => text.into_pyobject(py)?.into_any().unbind(),
+        },
+        ColumnType::Boolean => match parse_bool(text) {
+            Some(v) => v.into_pyobject(py)?.to_owned().into_any().unbind(),
+            None => text.into_pyobject(py)?.into_any().unbind(),
         },
         _ => text.into_pyobject(py)?.into_any().unbind(),
     };
@@ -174,216 +164,77 @@ fn native_error(e: prelude_xml_parser::errors::Error) -> PyErr {
     }
 }
 
-fn xml_error(e: impl std::fmt::Display) -> PyErr {
-    ParsingError::new_err(format!("Error parsing xml file: {e}"))
-}
-
-/// Append a `&...;` reference to the text being accumulated.
-///
-/// quick-xml reports references as their own events, so a value's text has to be reassembled from
-/// the surrounding text events and these. This matches what a DOM parser hands back as the node's
-/// text. An unresolvable reference is kept verbatim rather than dropped.
-fn push_general_ref(text: &mut String, reference: &BytesRef<'_>) -> PyResult<()> {
-    if let Some(character) = reference.resolve_char_ref().map_err(xml_error)? {
-        text.push(character);
-        return Ok(());
-    }
-
-    let name = reference.xml10_content().map_err(xml_error)?;
-
-    match resolve_predefined_entity(&name) {
-        Some(resolved) => text.push_str(resolved),
-        None => {
-            text.push('&');
-            text.push_str(&name);
-            text.push(';');
-        }
-    }
-
-    Ok(())
-}
-
-fn convert_name(raw: &str, short_names: bool) -> String {
-    if short_names {
-        raw.to_lowercase()
-    } else {
-        to_snake(raw)
-    }
-}
-
-/// A form's accumulated rows plus the columns discovered for it.
-struct FormTable {
-    name: String,
-    keys: Vec<Py<PyString>>,
-    types: Vec<ColumnType>,
-    index: HashMap<Vec<u8>, usize>,
-    rows: Vec<Vec<(usize, Option<String>)>>,
-}
-
-impl FormTable {
-    fn column<'py>(&mut self, py: Python<'py>, raw: &[u8], short_names: bool) -> PyResult<usize> {
-        if let Some(index) = self.index.get(raw) {
-            return Ok(*index);
+/// Build a parser from the keyword arguments shared by the native parsing functions.
+fn native_parser(
+    history: &str,
+    skip_export: bool,
+    threads: Option<usize>,
+) -> PyResult<NativeParser> {
+    let history = match history {
+        "full" => History::Full,
+        "current_only" => History::CurrentOnly,
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "history must be 'full' or 'current_only', got {other:?}"
+            )))
         }
+    };
 
-        let name = convert_name(from_utf8(raw).map_err(xml_error)?, short_names);
-        self.keys.push(PyString::new(py, &name).unbind());
-        self.types.push(ColumnType::Unknown);
-        self.index.insert(raw.to_vec(), self.keys.len() - 1);
+    let parser = NativeParser::new()
+        .history(history)
+        .skip_export(skip_export);
 
-        Ok(self.keys.len() - 1)
+    match threads {
+        Some(threads) => Ok(parser.threads(threads)),
+        None => Ok(parser),
     }
 }
 
-fn parse_xml<'py>(
-    py: Python<'py>,
-    xml_file: &PathBuf,
+/// Build the options for reading a flat export from the keyword arguments shared by the flat
+/// parsing functions.
+fn flat_options(
     short_names: bool,
-) -> PyResult<Bound<'py, PyDict>> {
-    let date = py.import("datetime")?.getattr("date")?;
-    let contents = read_to_string(xml_file).map_err(xml_error)?;
-
-    let mut reader = Reader::from_str(&contents);
-    reader.config_mut().trim_text(false);
-
-    // Forms are kept in the order they appear so the resulting dict is reproducible.
-    let mut tables: Vec<FormTable> = Vec::new();
-    let mut table_index: HashMap<Vec<u8>, usize> = HashMap::new();
-
-    let mut depth = 0usize;
-    let mut saw_root = false;
-    let mut current_table: Option<usize> = None;
-    let mut row: Vec<(usize, Option<String>)> = Vec::new();
-    let mut column: Option<usize> = None;
-    let mut text: Option<String> = None;
-
-    loop {
-        match reader.read_event().map_err(xml_error)? {
-            Event::Eof => break,
-
-            Event::Start(e) => {
-                depth += 1;
-                saw_root = true;
-
-                match depth {
-                    2 => {
-                        let raw = e.name().into_inner();
-                        let index = match table_index.get(raw) {
-                            Some(index) => *index,
-                            None => {
-                                let name =
-                                    convert_name(from_utf8(raw).map_err(xml_error)?, short_names);
-                                tables.push(FormTable {
-                                    name,
-                                    keys: Vec::new(),
-                                    types: Vec::new(),
-                                    index: HashMap::new(),
-                                    rows: Vec::new(),
-                                });
-                                table_index.insert(raw.to_vec(), tables.len() - 1);
-                                tables.len() - 1
-                            }
-                        };
-                        current_table = Some(index);
-                        row = Vec::new();
-                    }
-                    3 => {
-                        if let Some(index) = current_table {
-                            column = Some(tables[index].column(
-                                py,
-                                e.name().into_inner(),
-                                short_names,
-                            )?);
-                        }
-                        text = None;
-                    }
-                    _ => {}
-                }
-            }
-
-            Event::Empty(e) => match depth + 1 {
-                2 => {
-                    let raw = e.name().into_inner();
-                    if !table_index.contains_key(raw) {
-                        let name = convert_name(from_utf8(raw).map_err(xml_error)?, short_names);
-                        tables.push(FormTable {
-                            name,
-                            keys: Vec::new(),
-                            types: Vec::new(),
-                            index: HashMap::new(),
-                            rows: Vec::new(),
-                        });
-                        table_index.insert(raw.to_vec(), tables.len() - 1);
-                    }
-                    if let Some(index) = table_index.get(raw) {
-                        let index = *index;
-                        tables[index].rows.push(Vec::new());
-                    }
-                }
-                3 => {
-                    if let Some(index) = current_table {
-                        let column =
-                            tables[index].column(py, e.name().into_inner(), short_names)?;
-                        row.push((column, None));
-                    }
-                }
-                _ => {}
-            },
-
-            Event::Text(e) if depth == 3 => {
-                let decoded = e.xml10_content().map_err(xml_error)?;
-                text.get_or_insert_with(String::new).push_str(&decoded);
-            }
-
-            Event::GeneralRef(ref e) if depth == 3 => {
-                push_general_ref(text.get_or_insert_with(String::new), e)?;
-            }
-
-            Event::End(_) => {
-                match depth {
-                    3 => {
-                        if let (Some(index), Some(column)) = (current_table, column.take()) {
-                            let value = text.take();
-                            if let Some(ref value) = value {
-                                tables[index].types[column] =
-                                    widen(tables[index].types[column], value);
-                            }
-                            row.push((column, value));
-                        }
-                        text = None;
-                    }
-                    2 => {
-                        if let Some(index) = current_table.take() {
-                            tables[index].rows.push(std::mem::take(&mut row));
-                        }
-                    }
-                    _ => {}
-                }
-                depth -= 1;
-            }
-
-            _ => {}
-        }
-    }
-
-    if !saw_root {
-        return Err(ParsingError::new_err(
-            "Error parsing xml file: no root element found",
-        ));
-    }
+    dtypes: Option<HashMap<String, HashMap<String, String>>>,
+) -> PyResult<FlatOptions> {
+    let dtypes = dtypes
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(form, columns)| {
+            let columns = columns
+                .into_iter()
+                .map(|(column, dtype)| Ok((column, dtype.parse().map_err(PyValueError::new_err)?)))
+                .collect::<PyResult<_>>()?;
+            Ok((form, columns))
+        })
+        .collect::<PyResult<_>>()?;
+
+    Ok(FlatOptions {
+        short_names,
+        dtypes,
+    })
+}
 
+/// Convert the tables of a flat export into a list of records per form.
+fn tables_to_dict<'py>(py: Python<'py>, tables: &[FormTable]) -> PyResult<Bound<'py, PyDict>> {
     let data = PyDict::new(py);
-    for table in &tables {
+    for table in tables {
         if table.name.is_empty() {
             continue;
         }
 
+        let keys = table
+            .columns
+            .names
+            .iter()
+            .map(|name| PyString::new(py, name))
+            .collect::<Vec<_>>();
         let records = PyList::empty(py);
         for row in &table.rows {
             let record = PyDict::new(py);
             for (column, value) in row {
-                let converted = to_py_value(py, value.as_deref(), table.types[*column], &date)?;
-                record.set_item(table.keys[*column].bind(py), converted)?;
+                let column_type = table.columns.types[*column];
+                let converted = to_py_value(py, value.as_deref(), column_type)?;
+                record.set_item(&keys[*column], converted)?;
             }
             records.append(record)?;
         }
@@ -393,156 +244,171 @@ fn parse_xml<'py>(
     Ok(data)
 }
 
-fn parse_xml_pandas<'py>(
-    py: Python<'py>,
-    xml_file: &PathBuf,
-    short_names: bool,
-) -> PyResult<Bound<'py, PyDict>> {
-    let date = py.import("datetime")?.getattr("date")?;
-    let contents = read_to_string(xml_file).map_err(xml_error)?;
-
-    let mut reader = Reader::from_str(&contents);
-    reader.config_mut().trim_text(false);
-
-    let mut keys: Vec<Py<PyString>> = Vec::new();
-    let mut types: Vec<ColumnType> = Vec::new();
-    let mut values: Vec<Vec<Option<String>>> = Vec::new();
-    let mut index: HashMap<Vec<u8>, usize> = HashMap::new();
-
-    let mut depth = 0usize;
-    let mut saw_root = false;
-    let mut column: Option<usize> = None;
-    let mut text: Option<String> = None;
-
-    let column_for = |py: Python<'py>,
-                      raw: &[u8],
-                      keys: &mut Vec<Py<PyString>>,
-                      types: &mut Vec<ColumnType>,
-                      values: &mut Vec<Vec<Option<String>>>,
-                      index: &mut HashMap<Vec<u8>, usize>|
-     -> PyResult<usize> {
-        if let Some(found) = index.get(raw) {
-            return Ok(*found);
+/// Convert the schema of each form into a dict of column descriptions by column name.
+fn schema_to_dict<'py>(py: Python<'py>, tables: &[FormTable]) -> PyResult<Bound<'py, PyDict>> {
+    let data = PyDict::new(py);
+    for table in tables {
+        if table.name.is_empty() {
+            continue;
         }
 
-        let name = convert_name(from_utf8(raw).map_err(xml_error)?, short_names);
-        keys.push(PyString::new(py, &name).unbind());
-        types.push(ColumnType::Unknown);
-        values.push(Vec::new());
-        index.insert(raw.to_vec(), keys.len() - 1);
-
-        Ok(keys.len() - 1)
-    };
-
-    loop {
-        match reader.read_event().map_err(xml_error)? {
-            Event::Eof => break,
-
-            Event::Start(e) => {
-                depth += 1;
-                saw_root = true;
-                if depth == 3 {
-                    column = Some(column_for(
-                        py,
-                        e.name().into_inner(),
-                        &mut keys,
-                        &mut types,
-                        &mut values,
-                        &mut index,
-                    )?);
-                    text = None;
-                }
-            }
-
-            Event::Empty(e) => {
-                if depth + 1 == 3 {
-                    let column = column_for(
-                        py,
-                        e.name().into_inner(),
-                        &mut keys,
-                        &mut types,
-                        &mut values,
-                        &mut index,
-                    )?;
-                    values[column].push(None);
-                }
-            }
-
-            Event::Text(e) if depth == 3 => {
-                let decoded = e.xml10_content().map_err(xml_error)?;
-                text.get_or_insert_with(String::new).push_str(&decoded);
-            }
-
-            Event::GeneralRef(ref e) if depth == 3 => {
-                push_general_ref(text.get_or_insert_with(String::new), e)?;
-            }
-
-            Event::End(_) => {
-                if depth == 3 {
-                    if let Some(column) = column.take() {
-                        let value = text.take();
-                        if let Some(ref value) = value {
-                            types[column] = widen(types[column], value);
-                        }
-                        values[column].push(value);
-                    }
-                    text = None;
-                }
-                depth -= 1;
-            }
-
-            _ => {}
+        let columns = PyDict::new(py);
+        for column in table.schema() {
+            let description = PyDict::new(py);
+            description.set_item("dtype", column.column_type.name())?;
+            description.set_item("null_count", column.null_count)?;
+            description.set_item("distinct_count", column.distinct_count)?;
+            description.set_item("widened_by", column.widened_by)?;
+            columns.set_item(column.name, description)?;
         }
+        data.set_item(&table.name, columns)?;
     }
 
-    if !saw_root {
-        return Err(ParsingError::new_err(
-            "Error parsing xml file: no root element found",
-        ));
-    }
+    Ok(data)
+}
 
+/// Convert a flat export read by column into a list of values per column.
+fn columns_to_dict<'py>(py: Python<'py>, table: &ColumnTable) -> PyResult<Bound<'py, PyDict>> {
     let data = PyDict::new(py);
-    for (position, key) in keys.iter().enumerate() {
+    for (position, name) in table.columns.names.iter().enumerate() {
+        let column_type = table.columns.types[position];
         let list = PyList::empty(py);
-        for value in &values[position] {
-            list.append(to_py_value(py, value.as_deref(), types[position], &date)?)?;
+        for value in &table.values[position] {
+            list.append(to_py_value(py, value.as_deref(), column_type)?)?;
         }
-        data.set_item(key.bind(py), list)?;
+        data.set_item(name, list)?;
     }
 
     Ok(data)
 }
 
 #[pyfunction]
-#[pyo3(signature = (xml_file, *, short_names=false))]
+#[pyo3(signature = (xml_file, *, short_names=false, dtypes=None))]
 fn _parse_flat_file_to_dict<'py>(
     py: Python<'py>,
     xml_file: PathBuf,
     short_names: bool,
+    dtypes: Option<HashMap<String, HashMap<String, String>>>,
 ) -> PyResult<Bound<'py, PyDict>> {
-    check_valid_file(&xml_file)?;
-    let data = parse_xml(py, &xml_file, short_names)?;
+    let options = flat_options(short_names, dtypes)?;
+    let tables = py.detach(|| read_tables(&read_flat_file(&xml_file)?, &options))?;
+    tables_to_dict(py, &tables)
+}
 
-    Ok(data)
+#[pyfunction]
+#[pyo3(signature = (xml_str, *, short_names=false, dtypes=None))]
+fn _parse_flat_string_to_dict<'py>(
+    py: Python<'py>,
+    xml_str: &str,
+    short_names: bool,
+    dtypes: Option<HashMap<String, HashMap<String, String>>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let options = flat_options(short_names, dtypes)?;
+    let tables = py.detach(|| read_tables(xml_str, &options))?;
+    tables_to_dict(py, &tables)
 }
 
 #[pyfunction]
-#[pyo3(signature = (xml_file, *, short_names=false))]
+#[pyo3(signature = (xml_bytes, *, short_names=false, dtypes=None))]
+fn _parse_flat_bytes_to_dict<'py>(
+    py: Python<'py>,
+    xml_bytes: &[u8],
+    short_names: bool,
+    dtypes: Option<HashMap<String, HashMap<String, String>>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let options = flat_options(short_names, dtypes)?;
+    let tables = py.detach(|| read_tables(&decode(xml_bytes).map_err(xml_error)?, &options))?;
+    tables_to_dict(py, &tables)
+}
+
+#[pyfunction]
+#[pyo3(signature = (xml_file, *, short_names=false, dtypes=None))]
 fn _parse_flat_file_to_pandas_dict<'py>(
     py: Python<'py>,
     xml_file: PathBuf,
     short_names: bool,
+    dtypes: Option<HashMap<String, HashMap<String, String>>>,
 ) -> PyResult<Bound<'py, PyDict>> {
-    check_valid_file(&xml_file)?;
-    let data = parse_xml_pandas(py, &xml_file, short_names)?;
+    let options = flat_options(short_names, dtypes)?;
+    let table = py.detach(|| read_columns(&read_flat_file(&xml_file)?, &options))?;
+    columns_to_dict(py, &table)
+}
 
-    Ok(data)
+#[pyfunction]
+#[pyo3(signature = (xml_str, *, short_names=false, dtypes=None))]
+fn _parse_flat_string_to_pandas_dict<'py>(
+    py: Python<'py>,
+    xml_str: &str,
+    short_names: bool,
+    dtypes: Option<HashMap<String, HashMap<String, String>>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let options = flat_options(short_names, dtypes)?;
+    let table = py.detach(|| read_columns(xml_str, &options))?;
+    columns_to_dict(py, &table)
 }
 
 #[pyfunction]
-#[pyo3(signature = (xml_file))]
-fn parse_site_native_file(py: Python, xml_file: PathBuf) -> PyResult<SiteNative> {
-    let result = py.detach(|| parse_site_native_file_rs(&xml_file));
+#[pyo3(signature = (xml_bytes, *, short_names=false, dtypes=None))]
+fn _parse_flat_bytes_to_pandas_dict<'py>(
+    py: Python<'py>,
+    xml_bytes: &[u8],
+    short_names: bool,
+    dtypes: Option<HashMap<String, HashMap<String, String>>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let options = flat_options(short_names, dtypes)?;
+    let table = py.detach(|| read_columns(&decode(xml_bytes).map_err(xml_error)?, &options))?;
+    columns_to_dict(py, &table)
+}
+
+#[pyfunction]
+#[pyo3(signature = (xml_file, *, short_names=false))]
+fn _flat_schema_file<'py>(
+    py: Python<'py>,
+    xml_file: PathBuf,
+    short_names: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let options = flat_options(short_names, None)?;
+    let tables = py.detach(|| read_tables(&read_flat_file(&xml_file)?, &options))?;
+    schema_to_dict(py, &tables)
+}
+
+#[pyfunction]
+#[pyo3(signature = (xml_str, *, short_names=false))]
+fn _flat_schema_string<'py>(
+    py: Python<'py>,
+    xml_str: &str,
+    short_names: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let options = flat_options(short_names, None)?;
+    let tables = py.detach(|| read_tables(xml_str, &options))?;
+    schema_to_dict(py, &tables)
+}
+
+#[pyfunction]
+#[pyo3(signature = (xml_bytes, *, short_names=false))]
+fn _flat_schema_bytes<'py>(
+    py: Python<'py>,
+    xml_bytes: &[u8],
+    short_names: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let options = flat_options(short_names, None)?;
+    let tables = py.detach(|| read_tables(&decode(xml_bytes).map_err(xml_error)?, &options))?;
+    schema_to_dict(py, &tables)
+}
+
+#[pyfunction]
+#[pyo3(signature = (xml_file, *, history="full", skip_export=false, threads=None, progress=None))]
+fn parse_site_native_file(
+    py: Python,
+    xml_file: PathBuf,
+    history: &str,
+    skip_export: bool,
+    threads: Option<usize>,
+    progress: Option<Py<PyAny>>,
+) -> PyResult<SiteNative> {
+    let parser = native_parser(history, skip_export, threads)?;
+    let result = run_with_progress(py, parser, progress, |parser| parser.parse_file(&xml_file))?;
+
     match result {
         Ok(native) => Ok(native),
         Err(e) => Err(native_error(e)),
@@ -550,9 +416,17 @@ fn parse_site_native_file(py: Python, xml_file: PathBuf) -> PyResult<SiteNative>
 }
 
 #[pyfunction]
-#[pyo3(signature = (xml_str))]
-fn parse_site_native_string(py: Python, xml_str: &str) -> PyResult<SiteNative> {
-    let result = py.detach(|| parse_site_native_string_rs(xml_str));
+#[pyo3(signature = (xml_str, *, history="full", skip_export=false, threads=None, progress=None))]
+fn parse_site_native_string(
+    py: Python,
+    xml_str: &str,
+    history: &str,
+    skip_export: bool,
+    threads: Option<usize>,
+    progress: Option<Py<PyAny>>,
+) -> PyResult<SiteNative> {
+    let parser = native_parser(history, skip_export, threads)?;
+    let result = run_with_progress(py, parser, progress, |parser| parser.parse_str(xml_str))?;
 
     match result {
         Ok(native) => Ok(native),
@@ -561,9 +435,17 @@ fn parse_site_native_string(py: Python, xml_str: &str) -> PyResult<SiteNative> {
 }
 
 #[pyfunction]
-#[pyo3(signature = (xml_file))]
-fn parse_subject_native_file(py: Python, xml_file: PathBuf) -> PyResult<SubjectNative> {
-    let result = py.detach(|| parse_subject_native_file_rs(&xml_file));
+#[pyo3(signature = (xml_file, *, history="full", skip_export=false, threads=None, progress=None))]
+fn parse_subject_native_file(
+    py: Python,
+    xml_file: PathBuf,
+    history: &str,
+    skip_export: bool,
+    threads: Option<usize>,
+    progress: Option<Py<PyAny>>,
+) -> PyResult<SubjectNative> {
+    let parser = native_parser(history, skip_export, threads)?;
+    let result = run_with_progress(py, parser, progress, |parser| parser.parse_file(&xml_file))?;
 
     match result {
         Ok(native) => Ok(native),
@@ -572,9 +454,18 @@ fn parse_subject_native_file(py: Python, xml_file: PathBuf) -> PyResult<SubjectN
 }
 
 #[pyfunction]
-#[pyo3(signature = (xml_str))]
-fn parse_subject_native_string(py: Python, xml_str: &str) -> PyResult<SubjectNative> {
-    let result = py.detach(|| parse_subject_native_string_rs(xml_str));
+#[pyo3(signature = (xml_str, *, history="full", skip_export=false, threads=None, progress=None))]
+fn parse_subject_native_string(
+    py: Python,
+    xml_str: &str,
+    history: &str,
+    skip_export: bool,
+    threads: Option<usize>,
+    progress: Option<Py<PyAny>>,
+) -> PyResult<SubjectNative> {
+    let parser = native_parser(history, skip_export, threads)?;
+    let result = run_with_progress(py, parser, progress, |parser| parser.parse_str(xml_str))?;
+
     match result {
         Ok(native) => Ok(native),
         Err(e) => Err(ParsingError::new_err(format!("Error parsing xml: {e:?}"))),
@@ -582,9 +473,17 @@ fn parse_subject_native_string(py: Python, xml_str: &str) -> PyResult<SubjectNat
 }
 
 #[pyfunction]
-#[pyo3(signature = (xml_file))]
-fn parse_user_native_file(py: Python, xml_file: PathBuf) -> PyResult<UserNative> {
-    let result = py.detach(|| parse_user_native_file_rs(&xml_file));
+#[pyo3(signature = (xml_file, *, history="full", skip_export=false, threads=None, progress=None))]
+fn parse_user_native_file(
+    py: Python,
+    xml_file: PathBuf,
+    history: &str,
+    skip_export: bool,
+    threads: Option<usize>,
+    progress: Option<Py<PyAny>>,
+) -> PyResult<UserNative> {
+    let parser = native_parser(history, skip_export, threads)?;
+    let result = run_with_progress(py, parser, progress, |parser| parser.parse_file(&xml_file))?;
 
     match result {
         Ok(native) => Ok(native),
@@ -593,9 +492,17 @@ fn parse_user_native_file(py: Python, xml_file: PathBuf) -> PyResult<UserNative>
 }
 
 #[pyfunction]
-#[pyo3(signature = (xml_str))]
-fn parse_user_native_string(py: Python, xml_str: &str) -> PyResult<UserNative> {
-    let result = py.detach(|| parse_user_native_string_rs(xml_str));
+#[pyo3(signature = (xml_str, *, history="full", skip_export=false, threads=None, progress=None))]
+fn parse_user_native_string(
+    py: Python,
+    xml_str: &str,
+    history: &str,
+    skip_export: bool,
+    threads: Option<usize>,
+    progress: Option<Py<PyAny>>,
+) -> PyResult<UserNative> {
+    let parser = native_parser(history, skip_export, threads)?;
+    let result = run_with_progress(py, parser, progress, |parser| parser.parse_str(xml_str))?;
 
     match result {
         Ok(native) => Ok(native),
@@ -612,7 +519,11 @@ fn _prelude_parser(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Field>()?;
     m.add_class::<File>()?;
     m.add_class::<Form>()?;
+    m.add_class::<ListView>()?;
     m.add_class::<LockState>()?;
+    m.add_class::<PyMatch>()?;
+    m.add_class::<ParseProgress>()?;
+    m.add_class::<PartialDate>()?;
     m.add_class::<Patient>()?;
     m.add_class::<Query>()?;
     m.add_class::<Reason>()?;
@@ -624,7 +535,14 @@ fn _prelude_parser(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<UserNative>()?;
     m.add_class::<Value>()?;
     m.add_function(wrap_pyfunction!(_parse_flat_file_to_dict, m)?)?;
+    m.add_function(wrap_pyfunction!(_parse_flat_string_to_dict, m)?)?;
+    m.add_function(wrap_pyfunction!(_parse_flat_bytes_to_dict, m)?)?;
     m.add_function(wrap_pyfunction!(_parse_flat_file_to_pandas_dict, m)?)?;
+    m.add_function(wrap_pyfunction!(_parse_flat_string_to_pandas_dict, m)?)?;
+    m.add_function(wrap_pyfunction!(_parse_flat_bytes_to_pandas_dict, m)?)?;
+    m.add_function(wrap_pyfunction!(_flat_schema_file, m)?)?;
+    m.add_function(wrap_pyfunction!(_flat_schema_string, m)?)?;
+    m.add_function(wrap_pyfunction!(_flat_schema_bytes, m)?)?;
     m.add_function(wrap_pyfunction!(parse_site_native_file, m)?)?;
     m.add_function(wrap_pyfunction!(parse_site_native_string, m)?)?;
     m.add_function(wrap_pyfunction!(parse_subject_native_file, m)?)?;
@@ -643,7 +561,9 @@ fn _prelude_parser(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use std::{env, fs, path::Path};
+
+    use crate::utils::to_snake;
 
     #[test]
     fn test_to_snake() {
@@ -652,4 +572,21 @@ mod tests {
             String::from("i_communications_details")
         );
     }
+
+    #[test]
+    fn stub_is_current() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/_prelude_parser.pyi"));
+        let committed =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../../prelude_parser/_prelude_parser.pyi");
+
+        if env::var_os("UPDATE_STUBS").is_some() {
+            fs::write(&committed, generated).unwrap();
+            return;
+        }
+
+        assert!(
+            fs::read_to_string(&committed).unwrap() == generated,
+            "prelude_parser/_prelude_parser.pyi is stale, run `just stubs` to regenerate it"
+        );
+    }
 }
@@ -0,0 +1,212 @@
+//! Clinical partial dates, where some of the day, month or year was not known.
+//!
+//! Case report forms record these with the unknown parts written as `UN` or `UNK`, either in the
+//! `DD-MON-YYYY` order Prelude writes dates in, e.g. `UN-JAN-2023`, or in ISO order, e.g.
+//! `2023-UN-UN`.
+
+use chrono::NaiveDate;
+use pyo3::prelude::*;
+
+/// A date that may be missing its day, month or year.
+#[pyclass(
+    module = "prelude_parser._prelude_parser",
+    frozen,
+    eq,
+    hash,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PartialDate {
+    pub year: Option<i32>,
+    pub month: Option<u32>,
+    pub day: Option<u32>,
+}
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// Whether a part of a date is one of the ways an unknown part is written.
+fn is_unknown(part: &str) -> bool {
+    ["UN", "UNK", "UNKN"]
+        .iter()
+        .any(|unknown| part.eq_ignore_ascii_case(unknown))
+}
+
+/// Read a known part made of `digits` digits, or `None` for an unknown one.
+fn number(part: &str, digits: std::ops::RangeInclusive<usize>) -> Option<Option<u32>> {
+    if is_unknown(part) {
+        return Some(None);
+    }
+    if !digits.contains(&part.len()) || !part.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    part.parse().ok().map(Some)
+}
+
+fn month_name(part: &str) -> Option<Option<u32>> {
+    if is_unknown(part) {
+        return Some(None);
+    }
+
+    MONTHS
+        .iter()
+        .position(|month| part.eq_ignore_ascii_case(month))
+        .map(|index| Some(index as u32 + 1))
+}
+
+impl PartialDate {
+    /// Parse a partial date, which has to have at least one unknown part.
+    ///
+    /// A date with every part known is a [`NaiveDate`], not a partial date.
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.split('-');
+        let (first, second, third) = (parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let (year, month, day) = if first.len() == 4 || (is_unknown(first) && third.len() <= 3) {
+            (
+                number(first, 4..=4)?,
+                number(second, 1..=2)?,
+                number(third, 1..=2)?,
+            )
+        } else {
+            (
+                number(third, 4..=4)?,
+                month_name(second)?,
+                number(first, 1..=2)?,
+            )
+        };
+
+        let date = PartialDate {
+            year: year.map(|year| year as i32),
+            month,
+            day,
+        };
+
+        (date.is_valid() && !date.is_complete()).then_some(date)
+    }
+
+    /// A full date as a partial date with every part known.
+    pub(crate) fn from_date(date: NaiveDate) -> Self {
+        use chrono::Datelike;
+
+        PartialDate {
+            year: Some(date.year()),
+            month: Some(date.month()),
+            day: Some(date.day()),
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.year.is_some() && self.month.is_some() && self.day.is_some()
+    }
+
+    /// Whether the known parts could be part of a real date.
+    fn is_valid(&self) -> bool {
+        let month_valid = self.month.is_none_or(|month| (1..=12).contains(&month));
+        let day_valid = match self.day {
+            None => true,
+            // Checking against a leap year accepts the 29th of February when the year is unknown.
+            Some(day) => {
+                NaiveDate::from_ymd_opt(self.year.unwrap_or(2000), self.month.unwrap_or(1), day)
+                    .is_some()
+            }
+        };
+
+        month_valid && day_valid
+    }
+}
+
+#[pymethods]
+impl PartialDate {
+    #[new]
+    #[pyo3(signature = (year=None, month=None, day=None))]
+    fn new(year: Option<i32>, month: Option<u32>, day: Option<u32>) -> Self {
+        PartialDate { year, month, day }
+    }
+
+    fn __repr__(&self) -> String {
+        fn part<T: std::fmt::Display>(part: Option<T>) -> String {
+            part.map_or_else(|| "None".to_string(), |part| part.to_string())
+        }
+
+        format!(
+            "PartialDate(year={}, month={}, day={})",
+            part(self.year),
+            part(self.month),
+            part(self.day)
+        )
+    }
+
+    /// The date in ISO order with `UN` for the unknown parts, e.g. `2023-01-UN`
+    fn __str__(&self) -> String {
+        let year = self
+            .year
+            .map_or_else(|| "UNKN".to_string(), |year| format!("{year:04}"));
+        let month = self
+            .month
+            .map_or_else(|| "UN".to_string(), |month| format!("{month:02}"));
+        let day = self
+            .day
+            .map_or_else(|| "UN".to_string(), |day| format!("{day:02}"));
+
+        format!("{year}-{month}-{day}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial(year: Option<i32>, month: Option<u32>, day: Option<u32>) -> Option<PartialDate> {
+        Some(PartialDate { year, month, day })
+    }
+
+    #[test]
+    fn parse_prelude_order() {
+        assert_eq!(
+            PartialDate::parse("UN-JAN-2023"),
+            partial(Some(2023), Some(1), None)
+        );
+        assert_eq!(
+            PartialDate::parse("un-unk-2023"),
+            partial(Some(2023), None, None)
+        );
+        assert_eq!(
+            PartialDate::parse("15-UNK-UNKN"),
+            partial(None, None, Some(15))
+        );
+    }
+
+    #[test]
+    fn parse_iso_order() {
+        assert_eq!(
+            PartialDate::parse("2023-UN-UN"),
+            partial(Some(2023), None, None)
+        );
+        assert_eq!(
+            PartialDate::parse("2023-02-UN"),
+            partial(Some(2023), Some(2), None)
+        );
+        assert_eq!(
+            PartialDate::parse("UNKN-02-29"),
+            partial(None, Some(2), Some(29))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_other_values() {
+        assert_eq!(PartialDate::parse("15-JAN-2023"), None);
+        assert_eq!(PartialDate::parse("2023-01-15"), None);
+        assert_eq!(PartialDate::parse("2023-13-UN"), None);
+        assert_eq!(PartialDate::parse("UN-FOO-2023"), None);
+        assert_eq!(PartialDate::parse("30-FEB-UNKN"), None);
+        assert_eq!(PartialDate::parse("ABC-001"), None);
+        assert_eq!(PartialDate::parse("UN-UN"), None);
+    }
+}
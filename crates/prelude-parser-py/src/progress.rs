@@ -0,0 +1,118 @@
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+    time::Duration,
+};
+
+use prelude_xml_parser::{
+    errors::Error,
+    options::{CancellationToken, Progress},
+    NativeParser,
+};
+use pyo3::prelude::*;
+
+/// How often the calling thread wakes up to report progress and check for `KeyboardInterrupt`.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How far a native parse has got, passed to the `progress` callback.
+#[pyclass(
+    name = "Progress",
+    module = "prelude_parser._prelude_parser",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseProgress {
+    records: usize,
+    total_records: usize,
+    bytes: usize,
+    total_bytes: usize,
+}
+
+#[pymethods]
+impl ParseProgress {
+    fn __repr__(&self) -> String {
+        format!(
+            "Progress(records={}, total_records={}, bytes={}, total_bytes={})",
+            self.records, self.total_records, self.bytes, self.total_bytes
+        )
+    }
+}
+
+impl From<Progress> for ParseProgress {
+    fn from(progress: Progress) -> Self {
+        Self {
+            records: progress.records,
+            total_records: progress.total_records,
+            bytes: progress.bytes,
+            total_bytes: progress.total_bytes,
+        }
+    }
+}
+
+/// Run `parse` on a worker thread with the GIL released.
+///
+/// Signal handlers only run on Python's main thread and a parse can take minutes, so the calling
+/// thread does not parse itself: it wakes every [`POLL_INTERVAL`] to run them and to hand the
+/// latest progress to `callback`. If either raises, the parse is cancelled, the worker is waited
+/// for, and that exception is returned.
+pub fn run_with_progress<T, F>(
+    py: Python<'_>,
+    parser: NativeParser,
+    callback: Option<Py<PyAny>>,
+    parse: F,
+) -> PyResult<Result<T, Error>>
+where
+    T: Send,
+    F: FnOnce(&NativeParser) -> Result<T, Error> + Send,
+{
+    let token = CancellationToken::new();
+    let latest = Arc::new(Mutex::new(None));
+    let sink = latest.clone();
+    let parser = parser
+        .cancellation(token.clone())
+        .progress(move |progress| {
+            *sink.lock().unwrap_or_else(PoisonError::into_inner) = Some(progress);
+        });
+
+    thread::scope(|scope| {
+        let caller = thread::current();
+        let parser = &parser;
+        let worker = scope.spawn(move || {
+            let result = parse(parser);
+            caller.unpark();
+            result
+        });
+
+        let mut reported = None;
+        loop {
+            py.detach(|| thread::park_timeout(POLL_INTERVAL));
+            let finished = worker.is_finished();
+
+            let current = *latest.lock().unwrap_or_else(PoisonError::into_inner);
+            let checked = py.check_signals().and_then(|_| match (&callback, current) {
+                (Some(callback), Some(progress)) if reported != current => {
+                    reported = current;
+                    callback
+                        .call1(py, (ParseProgress::from(progress),))
+                        .map(drop)
+                }
+                _ => Ok(()),
+            });
+
+            if let Err(e) = checked {
+                token.cancel();
+                let _ = py.detach(|| worker.join());
+                return Err(e);
+            }
+
+            if finished {
+                return match worker.join() {
+                    Ok(result) => Ok(result),
+                    Err(panic) => std::panic::resume_unwind(panic),
+                };
+            }
+        }
+    })
+}
@@ -5,8 +5,9 @@ use criterion::{
     Throughput,
 };
 use prelude_xml_parser::{
-    parse_site_native_file, parse_site_native_string, parse_subject_native_file,
-    parse_subject_native_string, parse_user_native_file, parse_user_native_string,
+    native::subject_native::SubjectNative, parse_site_native_file, parse_site_native_string,
+    parse_subject_native_borrowed, parse_subject_native_file, parse_subject_native_string,
+    parse_user_native_file, parse_user_native_string, NativeParser,
 };
 use tempfile::{Builder, NamedTempFile};
 
@@ -143,6 +144,43 @@ fn bench_parse_user(c: &mut Criterion) {
     );
 }
 
+/// Compares parsing with every thread sharing the document's interned strings against parsing
+/// on one thread, and against the borrowed model, which interns nothing.
+fn bench_interning(c: &mut Criterion) {
+    let fixture = load_fixture("tests/assets/subject_native.xml");
+    let mut group = c.benchmark_group("parse_subject_native_interning");
+
+    for copies in COPIES {
+        let xml = scaled_xml(&fixture, "patient", copies);
+
+        configure(&mut group, copies);
+        group.throughput(Throughput::Bytes(xml.len() as u64));
+
+        for (name, parser) in [
+            ("parallel", NativeParser::new()),
+            ("single_threaded", NativeParser::new().single_threaded()),
+        ] {
+            group.bench_with_input(BenchmarkId::new(name, copies), &xml, |b, xml| {
+                b.iter(|| {
+                    let native: SubjectNative = parser
+                        .parse_str(black_box(xml))
+                        .unwrap_or_else(|e| panic!("Parsing failed: {e}"));
+                    native
+                })
+            });
+        }
+
+        group.bench_with_input(BenchmarkId::new("borrowed", copies), &xml, |b, xml| {
+            b.iter(|| {
+                parse_subject_native_borrowed(black_box(xml))
+                    .unwrap_or_else(|e| panic!("Parsing failed: {e}"));
+            })
+        });
+    }
+
+    group.finish();
+}
+
 fn bench_parse_subject_real_file(c: &mut Criterion) {
     let Ok(path) = std::env::var("BENCH_XML_FILE") else {
         return;
@@ -176,6 +214,7 @@ criterion_group!(
     bench_parse_subject,
     bench_parse_site,
     bench_parse_user,
+    bench_interning,
     bench_parse_subject_real_file
 );
 criterion_main!(benches);
@@ -0,0 +1,173 @@
+use memchr::{memchr, memchr3};
+
+/// Split the document into one slice per top-level record.
+///
+/// Records are parsed independently, and in parallel, so the document only needs to be cut at
+/// the right places here rather than fully parsed. The scan is a small tokenizer rather than a
+/// text search: comments, CDATA sections, processing instructions and the doctype are skipped
+/// whole, attribute values are skipped with their quotes respected, and the element depth within
+/// a record is tracked. A `</patient>` that appears anywhere but as the record's own closing tag
+/// therefore does not end the record.
+///
+/// A record that is never closed is dropped, as is anything that follows it.
+pub(crate) fn extract_chunks<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let mut tokens = Tokens { xml, pos: 0 };
+    let mut chunks = Vec::new();
+    // Where the open record starts, and how many of its elements are still open.
+    let mut record: Option<(usize, usize)> = None;
+
+    while let Some(token) = tokens.next() {
+        match (token, record) {
+            (Token::Start { name, start }, None) if name == tag => record = Some((start, 1)),
+            (Token::Empty { name, start }, None) if name == tag => {
+                chunks.push(&xml[start..tokens.pos]);
+            }
+            (Token::Start { .. }, Some((start, depth))) => record = Some((start, depth + 1)),
+            (Token::End, Some((start, 1))) => {
+                chunks.push(&xml[start..tokens.pos]);
+                record = None;
+            }
+            (Token::End, Some((start, depth))) => record = Some((start, depth - 1)),
+            _ => {}
+        }
+    }
+
+    chunks
+}
+
+/// The markup the chunker cares about. Everything else, text included, is passed over.
+enum Token<'a> {
+    Start { name: &'a str, start: usize },
+    Empty { name: &'a str, start: usize },
+    End,
+    Other,
+}
+
+struct Tokens<'a> {
+    xml: &'a str,
+    pos: usize,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    /// Read the next piece of markup, leaving `pos` just past it.
+    ///
+    /// Returns `None` once no markup is left, or at markup that is never terminated.
+    fn next(&mut self) -> Option<Token<'a>> {
+        let bytes = self.xml.as_bytes();
+        let start = self.pos + memchr(b'<', &bytes[self.pos..])?;
+        let rest = &self.xml[start..];
+
+        let (token, len) = match rest.as_bytes().get(1)? {
+            b'!' if rest.starts_with("<!--") => (Token::Other, rest.find("-->")? + "-->".len()),
+            b'!' if rest.starts_with("<![CDATA[") => {
+                (Token::Other, rest.find("]]>")? + "]]>".len())
+            }
+            b'!' => (Token::Other, declaration_len(rest)?),
+            b'?' => (Token::Other, rest.find("?>")? + "?>".len()),
+            b'/' => (Token::End, memchr(b'>', rest.as_bytes())? + 1),
+            _ => {
+                let len = tag_len(rest.as_bytes())?;
+                let name_len = rest.as_bytes()[1..len]
+                    .iter()
+                    .position(|&c| c.is_ascii_whitespace() || c == b'>' || c == b'/')
+                    .unwrap_or(len - 1);
+                let name = &rest[1..1 + name_len];
+
+                if rest.as_bytes()[len - 2] == b'/' {
+                    (Token::Empty { name, start }, len)
+                } else {
+                    (Token::Start { name, start }, len)
+                }
+            }
+        };
+
+        self.pos = start + len;
+        Some(token)
+    }
+}
+
+/// The length of a start or empty tag, which ends at the first `>` outside an attribute value.
+fn tag_len(tag: &[u8]) -> Option<usize> {
+    let mut pos = 0;
+
+    loop {
+        pos += memchr3(b'>', b'"', b'\'', &tag[pos..])?;
+        match tag[pos] {
+            b'>' => return Some(pos + 1),
+            quote => pos += 1 + memchr(quote, &tag[pos + 1..])? + 1,
+        }
+    }
+}
+
+/// The length of a declaration such as `<!DOCTYPE ...>`, including any internal subset, whose own
+/// declarations contain `>`.
+fn declaration_len(declaration: &str) -> Option<usize> {
+    let mut in_subset = false;
+
+    for (i, c) in declaration.bytes().enumerate() {
+        match c {
+            b'[' => in_subset = true,
+            b']' => in_subset = false,
+            b'>' if !in_subset => return Some(i + 1),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_records_at_their_own_closing_tags() {
+        let xml = "<root><patient id=\"1\"><form/></patient>\n<patient><patient/></patient></root>";
+
+        assert_eq!(
+            extract_chunks(xml, "patient"),
+            vec![
+                "<patient id=\"1\"><form/></patient>",
+                "<patient><patient/></patient>"
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_tags_that_only_start_with_the_record_name() {
+        let xml = "<root><patients><patient/></patients><patient_note/></root>";
+
+        assert_eq!(extract_chunks(xml, "patient"), vec!["<patient/>"]);
+    }
+
+    #[test]
+    fn ignores_closing_tags_outside_markup() {
+        let xml = concat!(
+            "<!DOCTYPE root [<!ENTITY end \"</patient>\">]>",
+            "<root><patient note='a > b'>",
+            "<!-- </patient> --><?pi </patient>?><![CDATA[</patient>]]>",
+            "</patient></root>"
+        );
+
+        assert_eq!(
+            extract_chunks(xml, "patient"),
+            vec![concat!(
+                "<patient note='a > b'>",
+                "<!-- </patient> --><?pi </patient>?><![CDATA[</patient>]]>",
+                "</patient>"
+            )]
+        );
+    }
+
+    #[test]
+    fn drops_an_unclosed_record() {
+        let xml = "<root><patient id=\"1\"></patient><patient id=\"2\"><!-- </patient>";
+
+        assert_eq!(
+            extract_chunks(xml, "patient"),
+            vec!["<patient id=\"1\"></patient>"]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Decoding of XML documents that are not UTF-8.
+//!
+//! Older Prelude installs, and exports that have been round-tripped through Excel, are written as
+//! ISO-8859-1 or Windows-1252 with a matching `encoding` declaration, and some carry a UTF-16
+//! byte order mark. The parsers work on `&str`, so such documents are transcoded up front.
+
+use std::{borrow::Cow, str::from_utf8};
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+use crate::errors::Error;
+
+/// How far into the document the `<?xml ... ?>` declaration is looked for.
+const PROLOG_LIMIT: usize = 1024;
+
+/// Decode a raw XML document into text.
+///
+/// The encoding is taken from the byte order mark if there is one, then from the `encoding`
+/// declaration in the prolog, and is UTF-8 otherwise. UTF-8 input, the overwhelmingly common
+/// case, is only validated and returned borrowed.
+///
+/// # Example
+///
+/// ```
+/// use prelude_xml_parser::encoding::decode;
+///
+/// let latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><site name=\"Z\xfcrich\"/>";
+/// let text = decode(latin1).unwrap();
+///
+/// assert_eq!(
+///     text,
+///     "<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><site name=\"Zürich\"/>"
+/// );
+/// ```
+pub fn decode(bytes: &[u8]) -> Result<Cow<'_, str>, Error> {
+    let (encoding, bom_len) = match Encoding::for_bom(bytes) {
+        Some((encoding, bom_len)) => (encoding, bom_len),
+        None => (detect(bytes)?, 0),
+    };
+    let bytes = &bytes[bom_len..];
+
+    if encoding == UTF_8 {
+        return Ok(Cow::Borrowed(from_utf8(bytes)?));
+    }
+
+    encoding
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .ok_or_else(|| Error::InvalidEncoding(encoding.name()))
+}
+
+/// Work out the encoding of a document that has no byte order mark.
+fn detect(bytes: &[u8]) -> Result<&'static Encoding, Error> {
+    // `<?` as UTF-16 without a byte order mark.
+    match bytes {
+        [0x3C, 0x00, 0x3F, 0x00, ..] => return Ok(UTF_16LE),
+        [0x00, 0x3C, 0x00, 0x3F, ..] => return Ok(UTF_16BE),
+        _ => {}
+    }
+
+    let Some(label) = declared_encoding(bytes) else {
+        return Ok(UTF_8);
+    };
+
+    match Encoding::for_label(label) {
+        // The document has already been read as single bytes, so a declaration of UTF-16 that
+        // got this far is wrong and the bytes themselves are the better guide.
+        Some(encoding) if encoding == UTF_16LE || encoding == UTF_16BE => Ok(UTF_8),
+        Some(encoding) => Ok(encoding),
+        None => Err(Error::UnsupportedEncoding(
+            String::from_utf8_lossy(label).into_owned(),
+        )),
+    }
+}
+
+/// The `encoding` named in the document's `<?xml ... ?>` declaration, if it has one.
+fn declared_encoding(bytes: &[u8]) -> Option<&[u8]> {
+    let prolog = &bytes[..bytes.len().min(PROLOG_LIMIT)];
+    let prolog = prolog.strip_prefix(b"<?xml")?;
+    let prolog = &prolog[..prolog.windows(2).position(|w| w == b"?>")?];
+
+    let at = prolog.windows(8).position(|w| w == b"encoding")?;
+    let rest = prolog[at + 8..].trim_ascii_start().strip_prefix(b"=")?;
+    let rest = rest.trim_ascii_start();
+
+    let quote = *rest.first().filter(|&&c| c == b'"' || c == b'\'')?;
+    let rest = &rest[1..];
+    let end = rest.iter().position(|&c| c == quote)?;
+
+    Some(&rest[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str, big_endian: bool, bom: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let units = bom.then_some(0xFEFF).into_iter().chain(text.encode_utf16());
+        for unit in units {
+            if big_endian {
+                bytes.extend(unit.to_be_bytes());
+            } else {
+                bytes.extend(unit.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn utf8_is_borrowed() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?><site name=\"Zürich\"/>";
+
+        assert!(matches!(decode(xml.as_bytes()), Ok(Cow::Borrowed(text)) if text == xml));
+    }
+
+    #[test]
+    fn utf8_bom_is_stripped() {
+        let xml = "<site name=\"Zürich\"/>";
+        let bytes = [b"\xEF\xBB\xBF".as_slice(), xml.as_bytes()].concat();
+
+        assert_eq!(decode(&bytes).unwrap(), xml);
+    }
+
+    #[test]
+    fn windows_1252_from_declaration() {
+        let bytes =
+            b"<?xml version='1.0' encoding = 'windows-1252' ?><value>\x93caf\xe9\x94</value>";
+
+        assert_eq!(
+            decode(bytes).unwrap(),
+            "<?xml version='1.0' encoding = 'windows-1252' ?><value>\u{201C}café\u{201D}</value>"
+        );
+    }
+
+    #[test]
+    fn utf16_with_and_without_bom() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-16\"?><site name=\"Zürich\"/>";
+
+        for big_endian in [false, true] {
+            for bom in [false, true] {
+                let bytes = utf16(xml, big_endian, bom);
+                assert_eq!(decode(&bytes).unwrap(), xml, "{big_endian} {bom}");
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_declared_encoding_error() {
+        let bytes = b"<?xml version=\"1.0\" encoding=\"EBCDIC-XYZ\"?><site/>";
+
+        assert!(matches!(
+            decode(bytes),
+            Err(Error::UnsupportedEncoding(label)) if label == "EBCDIC-XYZ"
+        ));
+    }
+
+    #[test]
+    fn undeclared_non_utf8_error() {
+        assert!(matches!(
+            decode(b"<site name=\"Z\xfcrich\"/>"),
+            Err(Error::InvalidUtf8(_))
+        ));
+    }
+}
@@ -18,10 +18,38 @@ pub enum Error {
     #[error(transparent)]
     IO(#[from] std::io::Error),
 
+    /// The file is not valid UTF-8.
+    #[error(transparent)]
+    InvalidUtf8(#[from] std::str::Utf8Error),
+
+    /// The encoding declared in the XML prolog is not one that can be decoded.
+    #[error("Unsupported encoding: {0}")]
+    UnsupportedEncoding(String),
+
+    /// The file is not valid text in the encoding it declares.
+    #[error("The file is not valid {0}")]
+    InvalidEncoding(&'static str),
+
+    /// A selector expression could not be parsed.
+    #[error("Invalid selector at position {position}: {message}")]
+    InvalidSelector { position: usize, message: String },
+
+    /// The pages of a split export do not make up one whole export.
+    #[error("Cannot merge the pages: {0}")]
+    InvalidPages(String),
+
     /// A parsing error occurred.
     #[error(transparent)]
     ParsingError(#[from] quick_xml::de::DeError),
 
+    /// The thread pool requested in the parse options could not be built.
+    #[error(transparent)]
+    ThreadPool(#[from] rayon::ThreadPoolBuildError),
+
+    /// The parse was stopped through its cancellation token.
+    #[error("Parsing was cancelled")]
+    Cancelled,
+
     /// An unknown error occurred.
     #[error("Unknown error")]
     Unknown,
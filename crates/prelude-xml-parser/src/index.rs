@@ -0,0 +1,122 @@
+//! Lookups by id and name over a parsed export, built once and borrowed from it.
+//!
+//! Finding a record in an export otherwise means scanning all of its records. Build an index with
+//! [`SubjectNative::index`](crate::native::subject_native::SubjectNative::index),
+//! [`SiteNative::index`](crate::native::site_native::SiteNative::index) or
+//! [`UserNative::index`](crate::native::user_native::UserNative::index) when looking up more than a
+//! handful. Where an export holds the same id twice, the first record with it is indexed.
+
+use std::collections::HashMap;
+
+use crate::native::{site_native::Site, subject_native::Patient, user_native::User};
+
+/// The patients of a subject export by their ids and by their site.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SubjectIndex<'a> {
+    pub by_patient_id: HashMap<&'a str, &'a Patient>,
+    pub by_unique_id: HashMap<&'a str, &'a Patient>,
+    /// Every patient of each site, by the site's name, in document order.
+    pub by_site: HashMap<&'a str, Vec<&'a Patient>>,
+}
+
+impl<'a> SubjectIndex<'a> {
+    pub(crate) fn new(patients: &'a [Patient]) -> Self {
+        let mut index = SubjectIndex::default();
+
+        for patient in patients {
+            index
+                .by_patient_id
+                .entry(&patient.patient_id)
+                .or_insert(patient);
+            index
+                .by_unique_id
+                .entry(&patient.unique_id)
+                .or_insert(patient);
+            index
+                .by_site
+                .entry(&patient.site_name)
+                .or_default()
+                .push(patient);
+        }
+
+        index
+    }
+}
+
+/// The sites of a site export by their name and id.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SiteIndex<'a> {
+    pub by_name: HashMap<&'a str, &'a Site>,
+    pub by_unique_id: HashMap<&'a str, &'a Site>,
+}
+
+impl<'a> SiteIndex<'a> {
+    pub(crate) fn new(sites: &'a [Site]) -> Self {
+        let mut index = SiteIndex::default();
+
+        for site in sites {
+            index.by_name.entry(&site.name).or_insert(site);
+            index.by_unique_id.entry(&site.unique_id).or_insert(site);
+        }
+
+        index
+    }
+}
+
+/// The users of a user export by their id.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UserIndex<'a> {
+    pub by_unique_id: HashMap<&'a str, &'a User>,
+}
+
+impl<'a> UserIndex<'a> {
+    pub(crate) fn new(users: &'a [User]) -> Self {
+        let mut index = UserIndex::default();
+
+        for user in users {
+            index.by_unique_id.entry(&user.unique_id).or_insert(user);
+        }
+
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::parse_subject_native_file;
+
+    #[test]
+    fn indexes_every_patient() {
+        let native =
+            parse_subject_native_file(Path::new("tests/assets/subject_native.xml")).unwrap();
+        let index = native.index();
+
+        assert_eq!(index.by_patient_id.len(), native.patients.len());
+        for patient in &native.patients {
+            assert_eq!(index.by_patient_id[&*patient.patient_id], patient);
+            assert_eq!(index.by_unique_id[&*patient.unique_id], patient);
+        }
+        assert_eq!(
+            index.by_site.values().map(Vec::len).sum::<usize>(),
+            native.patients.len()
+        );
+    }
+
+    #[test]
+    fn first_duplicate_wins() {
+        let mut native =
+            parse_subject_native_file(Path::new("tests/assets/subject_native.xml")).unwrap();
+        let mut duplicate = native.patients[1].clone();
+        duplicate.patient_id = native.patients[0].patient_id.clone();
+        native.patients.push(duplicate);
+
+        let index = native.index();
+
+        assert!(std::ptr::eq(
+            index.by_patient_id[&*native.patients[0].patient_id],
+            &native.patients[0]
+        ));
+    }
+}
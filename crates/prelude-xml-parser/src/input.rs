@@ -0,0 +1,45 @@
+use std::{
+    borrow::Cow,
+    fs::{self, File},
+    path::Path,
+};
+
+use memmap2::Mmap;
+
+use crate::{encoding::decode, errors::Error, options::FileInput};
+
+/// The contents of a native XML file, either mapped into memory or read onto the heap.
+///
+/// Chunking and record parsing only ever borrow the document, so mapping it lets the page cache
+/// back the input instead of holding a second copy of the whole file.
+pub(crate) enum XmlFile {
+    Mapped(Mmap),
+    Read(Vec<u8>),
+}
+
+impl XmlFile {
+    pub(crate) fn open(xml_path: &Path, input: FileInput) -> Result<Self, Error> {
+        match input {
+            FileInput::Mmap => {
+                let file = File::open(xml_path)?;
+
+                // SAFETY: the map is only read, and only while the file is being parsed. Another
+                // process truncating or rewriting the file meanwhile is outside what we can guard
+                // against; `FileInput::Read` exists for inputs where that is a concern.
+                let map = unsafe { Mmap::map(&file)? };
+                Ok(Self::Mapped(map))
+            }
+            FileInput::Read => Ok(Self::Read(fs::read(xml_path)?)),
+        }
+    }
+
+    /// The document as text, transcoded if it is not UTF-8.
+    pub(crate) fn text(&self) -> Result<Cow<'_, str>, Error> {
+        let bytes = match self {
+            Self::Mapped(map) => &map[..],
+            Self::Read(bytes) => &bytes[..],
+        };
+
+        decode(bytes)
+    }
+}
@@ -1,21 +1,44 @@
+mod chunks;
+pub mod encoding;
 pub mod errors;
+pub mod index;
+mod input;
 pub mod native;
+pub mod options;
+pub mod parser;
+pub mod selector;
+pub mod study;
+pub mod visit;
+
+pub use crate::parser::NativeParser;
+
+use std::{
+    borrow::Cow,
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+};
 
-use std::{fs::read_to_string, path::Path, sync::Arc};
-
-use rayon::prelude::*;
+use rayon::{prelude::*, ThreadPoolBuilder};
 
 use crate::{
+    chunks::extract_chunks,
     errors::Error,
+    input::XmlFile,
     native::{
-        common::{
-            Category, Comment, Entry, Export, Field, File, LockState, Query, Reason, State, Value,
+        borrowed::{
+            CategoryRef, CommentRef, EntryRef, FieldRef, FileRef, FormRef, LockStateRef,
+            PatientRef, QueryRef, ReasonRef, RecordRef, SiteNativeRef, SiteRef, StateRef,
+            SubjectNativeRef, UserNativeRef, UserRef, ValueRef,
+        },
+        common::Export,
+        deserializers::{
+            push_cdata, push_general_ref, push_text, take_trimmed, Interner, SharedInterner,
         },
-        deserializers::{decode_error, push_general_ref, take_trimmed, Interner},
         site_native::{Site, SiteNative},
-        subject_native::{Form, Patient, SubjectNative},
+        subject_native::{Patient, SubjectNative},
         user_native::{User, UserNative},
     },
+    options::{CancellationToken, History, Parallelism, ParseOptions, Progress, ProgressCallback},
 };
 use quick_xml::{events::Event, Reader};
 
@@ -34,10 +57,18 @@ use quick_xml::{events::Event, Reader};
 /// assert!(native.sites.len() >= 1, "Vector length is less than 1");
 /// ```
 pub fn parse_site_native_file(xml_path: &Path) -> Result<SiteNative, Error> {
+    parse_site_native_file_with_options(xml_path, &ParseOptions::default())
+}
+
+/// Parses a Prelude native site XML file into a `SiteNative` struct using the given options.
+pub fn parse_site_native_file_with_options(
+    xml_path: &Path,
+    options: &ParseOptions,
+) -> Result<SiteNative, Error> {
     check_valid_xml_file(xml_path)?;
 
-    let xml_file = read_to_string(xml_path)?;
-    let native = parse_site_native_string(&xml_file)?;
+    let xml_file = XmlFile::open(xml_path, options.file_input)?;
+    let native = parse_site_native_string_with_options(&xml_file.text()?, options)?;
 
     Ok(native)
 }
@@ -459,13 +490,18 @@ pub fn parse_site_native_file(xml_path: &Path) -> Result<SiteNative, Error> {
 /// let result = parse_site_native_string(xml).unwrap();
 /// assert_eq!(result, expected);
 pub fn parse_site_native_string(xml_str: &str) -> Result<SiteNative, Error> {
+    parse_site_native_string_with_options(xml_str, &ParseOptions::default())
+}
+
+/// Parse a string of Prelude native site XML into a `SiteNative` struct using the given options.
+pub fn parse_site_native_string_with_options(
+    xml_str: &str,
+    options: &ParseOptions,
+) -> Result<SiteNative, Error> {
     let chunks = extract_site_chunks(xml_str);
-    let sites = chunks
-        .into_par_iter()
-        .map(parse_site_xml)
-        .collect::<Result<Vec<_>, _>>()?;
+    let sites = parse_records(xml_str, chunks, options, parse_site_xml)?;
     Ok(SiteNative {
-        export: parse_export(xml_str)?,
+        export: parse_export(xml_str, options)?,
         sites,
     })
 }
@@ -485,18 +521,36 @@ pub fn parse_site_native_string(xml_str: &str) -> Result<SiteNative, Error> {
 /// assert!(native.patients.len() >= 1, "Vector length is less than 1");
 /// ```
 pub fn parse_subject_native_file(xml_path: &Path) -> Result<SubjectNative, Error> {
+    parse_subject_native_file_with_options(xml_path, &ParseOptions::default())
+}
+
+/// Parses a Prelude native subject XML file into a `SubjectNative` struct using the given options.
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+///
+/// use prelude_xml_parser::options::{History, ParseOptions};
+/// use prelude_xml_parser::parse_subject_native_file_with_options;
+///
+/// let file_path = Path::new("tests/assets/subject_native.xml");
+/// let mut options = ParseOptions::default();
+/// options.history = History::CurrentOnly;
+/// let native = parse_subject_native_file_with_options(&file_path, &options).unwrap();
+///
+/// assert!(native.patients.len() >= 1, "Vector length is less than 1");
+/// ```
+pub fn parse_subject_native_file_with_options(
+    xml_path: &Path,
+    options: &ParseOptions,
+) -> Result<SubjectNative, Error> {
     check_valid_xml_file(xml_path)?;
 
-    let xml_str = read_to_string(xml_path)?;
-    let chunks = extract_patient_chunks(&xml_str);
-    let patients = chunks
-        .into_par_iter()
-        .map(parse_patient_xml)
-        .collect::<Result<Vec<_>, _>>()?;
-    Ok(SubjectNative {
-        export: parse_export(&xml_str)?,
-        patients,
-    })
+    let xml_file = XmlFile::open(xml_path, options.file_input)?;
+    let native = parse_subject_native_string_with_options(&xml_file.text()?, options)?;
+
+    Ok(native)
 }
 
 /// Parse a string of Prelude native subject XML into a `SubjectNative` struct.
@@ -711,23 +765,126 @@ pub fn parse_subject_native_file(xml_path: &Path) -> Result<SubjectNative, Error
 /// assert_eq!(result, expected);
 /// ```
 pub fn parse_subject_native_string(xml_str: &str) -> Result<SubjectNative, Error> {
+    parse_subject_native_string_with_options(xml_str, &ParseOptions::default())
+}
+
+/// Parse a string of Prelude native subject XML into a `SubjectNative` struct using the given
+/// options.
+pub fn parse_subject_native_string_with_options(
+    xml_str: &str,
+    options: &ParseOptions,
+) -> Result<SubjectNative, Error> {
     let chunks = extract_patient_chunks(xml_str);
-    let patients = chunks
-        .into_par_iter()
-        .map(parse_patient_xml)
-        .collect::<Result<Vec<_>, _>>()?;
+    let patients = parse_records(xml_str, chunks, options, parse_patient_xml)?;
     Ok(SubjectNative {
-        export: parse_export(xml_str)?,
+        export: parse_export(xml_str, options)?,
         patients,
     })
 }
 
+/// Parse every record chunk, spreading the work as `options.parallelism` asks.
+///
+/// Results keep the order of the chunks whichever way the work is spread. Cancellation is checked
+/// before each record and progress reported after it.
+fn parse_records<'a, T, F>(
+    xml: &'a str,
+    chunks: Vec<&'a str>,
+    options: &ParseOptions,
+    parse: F,
+) -> Result<Vec<T>, Error>
+where
+    T: Send,
+    F: Fn(&'a str, &ParseOptions, &mut Interner) -> Result<T, Error> + Sync,
+{
+    let progress = ProgressTracker::new(options, xml.len(), chunks.len());
+    let parse_one = |interner: &mut Interner, chunk: &&'a str| {
+        if options
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(Error::Cancelled);
+        }
+
+        let record = parse(chunk, options, interner)?;
+        progress.record_parsed(chunk.len());
+        Ok(record)
+    };
+    let parse_parallel = || {
+        let shared = Arc::new(SharedInterner::default());
+        chunks
+            .par_iter()
+            .map_init(|| Interner::shared(shared.clone()), parse_one)
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    let records = match &options.parallelism {
+        Parallelism::Global => parse_parallel(),
+        Parallelism::Sequential | Parallelism::Threads(1) => {
+            let mut interner = Interner::default();
+            chunks
+                .iter()
+                .map(|chunk| parse_one(&mut interner, chunk))
+                .collect::<Result<Vec<_>, _>>()
+        }
+        Parallelism::Threads(threads) => ThreadPoolBuilder::new()
+            .num_threads(*threads)
+            .build()?
+            .install(parse_parallel),
+        Parallelism::Pool(pool) => pool.install(parse_parallel),
+    }?;
+
+    progress.finish();
+    Ok(records)
+}
+
+/// Keeps the running [`Progress`] of one parse and passes it to the callback, if there is one.
+///
+/// The lock serialises the calls so the callback never sees the counts go backwards.
+struct ProgressTracker<'a> {
+    callback: Option<&'a ProgressCallback>,
+    progress: Mutex<Progress>,
+}
+
+impl<'a> ProgressTracker<'a> {
+    fn new(options: &'a ParseOptions, total_bytes: usize, total_records: usize) -> Self {
+        Self {
+            callback: options.progress.as_ref(),
+            progress: Mutex::new(Progress {
+                total_records,
+                total_bytes,
+                ..Progress::default()
+            }),
+        }
+    }
+
+    fn record_parsed(&self, bytes: usize) {
+        self.update(|progress| {
+            progress.records += 1;
+            progress.bytes += bytes;
+        });
+    }
+
+    fn finish(&self) {
+        self.update(|progress| progress.bytes = progress.total_bytes);
+    }
+
+    fn update(&self, change: impl FnOnce(&mut Progress)) {
+        let Some(callback) = self.callback else {
+            return;
+        };
+        let mut progress = self.progress.lock().unwrap_or_else(PoisonError::into_inner);
+        change(&mut progress);
+        callback.call(*progress);
+    }
+}
+
 /// Read the attributes of the document's root element.
 ///
 /// The record parsers work on textual chunks and never see the root, so it is read separately.
 /// Scanning stops at the first element, so this costs almost nothing.
-fn parse_export(xml: &str) -> Result<Option<Export>, Error> {
-    if std::env::var("SKIP_EXPORT").is_ok() {
+fn parse_export(xml: &str, options: &ParseOptions) -> Result<Option<Export>, Error> {
+    if options.skip_export {
         return Ok(None);
     }
     let mut reader = Reader::from_str(xml);
@@ -748,77 +905,98 @@ fn parse_export(xml: &str) -> Result<Option<Export>, Error> {
     }
 }
 
-/// Split the document into one slice per top-level record.
+/// Whether a just-closed entry should be kept, given the entries already kept for its owner.
 ///
-/// The scan is textual, so the character after the tag name has to be checked: searching for
-/// `"<patient "` alone misses `<patient>` when the element carries no attributes, which silently
-/// yields no records at all.
-fn extract_chunks<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
-    let open = format!("<{tag}");
-    let close = format!("</{tag}>");
-
-    let mut chunks = Vec::new();
-    let mut pos = 0;
-
-    while let Some(rel) = xml[pos..].find(&open) {
-        let start = pos + rel;
-        let after_name = start + open.len();
-
-        // Anything else means we matched a longer tag that merely starts with this name.
-        let ends_name = xml[after_name..]
-            .chars()
-            .next()
-            .is_some_and(|c| c.is_whitespace() || c == '>');
-
-        if !ends_name {
-            pos = after_name;
-            continue;
-        }
-
-        let Some(rel_end) = xml[start..].find(&close) else {
-            break;
-        };
-
-        let end = start + rel_end + close.len();
-        chunks.push(&xml[start..end]);
-        pos = end;
+/// Entries are listed newest first, so with [`History::CurrentOnly`] only the first one survives.
+fn keeps_entry<T>(options: &ParseOptions, kept: &[T]) -> bool {
+    match options.history {
+        History::Full => true,
+        History::CurrentOnly => kept.is_empty(),
     }
+}
+
+/// Parse a string of Prelude native subject XML into a `SubjectNativeRef` that borrows from it.
+///
+/// # Example
+///
+/// ```
+/// use std::fs::read_to_string;
+///
+/// use prelude_xml_parser::{parse_subject_native_borrowed, parse_subject_native_string};
+///
+/// let xml = read_to_string("tests/assets/subject_native.xml").unwrap();
+/// let native = parse_subject_native_borrowed(&xml).unwrap();
+///
+/// assert_eq!(native.patients[0].patient_id, "ABC-001");
+/// assert_eq!(native.into_owned(), parse_subject_native_string(&xml).unwrap());
+/// ```
+pub fn parse_subject_native_borrowed(xml_str: &str) -> Result<SubjectNativeRef<'_>, Error> {
+    parse_subject_native_borrowed_with_options(xml_str, &ParseOptions::default())
+}
 
-    chunks
+/// Parse a string of Prelude native subject XML into a `SubjectNativeRef` that borrows from it,
+/// using the given options.
+pub fn parse_subject_native_borrowed_with_options<'a>(
+    xml_str: &'a str,
+    options: &ParseOptions,
+) -> Result<SubjectNativeRef<'a>, Error> {
+    let chunks = extract_patient_chunks(xml_str);
+    let patients = parse_records(xml_str, chunks, options, |xml, options, _| {
+        parse_record::<PatientRef>(xml, options)
+    })?;
+    Ok(SubjectNativeRef {
+        export: parse_export(xml_str, options)?,
+        patients,
+    })
 }
 
 fn extract_patient_chunks(xml: &str) -> Vec<&str> {
     extract_chunks(xml, "patient")
 }
 
+fn parse_patient_xml(
+    patient_xml: &str,
+    options: &ParseOptions,
+    interner: &mut Interner,
+) -> Result<Patient, Error> {
+    parse_record::<PatientRef>(patient_xml, options)
+        .map(|patient| patient.into_owned_with(interner))
+}
+
+/// Parse one record chunk into its borrowed form.
+///
+/// Patients, sites and users all hold their data in the same `form` elements, so one state
+/// machine serves all three exports; only the record's own element differs.
 #[allow(clippy::drain_collect)]
-fn parse_patient_xml(patient_xml: &str) -> Result<Patient, Error> {
-    let mut interner = Interner::default();
-    let mut xml_reader = Reader::from_str(patient_xml);
+fn parse_record<'a, R: RecordRef<'a>>(
+    record_xml: &'a str,
+    options: &ParseOptions,
+) -> Result<R, Error> {
+    let mut xml_reader = Reader::from_str(record_xml);
     xml_reader.config_mut().trim_text(false);
 
-    let mut current_patient: Option<Patient> = None;
-    let mut current_forms: Vec<Form> = Vec::new();
-    let mut current_form: Option<Form> = None;
-    let mut current_states: Vec<State> = Vec::new();
-    let mut current_lock_states: Vec<LockState> = Vec::new();
-    let mut current_categories: Vec<Category> = Vec::new();
-    let mut current_category: Option<Category> = None;
-    let mut current_fields: Vec<Field> = Vec::new();
-    let mut current_field: Option<Field> = None;
-    let mut current_entries: Vec<Entry> = Vec::new();
-    let mut current_entry: Option<Entry> = None;
-    let mut current_comments: Vec<Comment> = Vec::new();
-    let mut current_comment: Option<Comment> = None;
-    let mut current_files: Vec<File> = Vec::new();
-    let mut current_file: Option<File> = None;
-    let mut current_queries: Vec<Query> = Vec::new();
-    let mut current_query: Option<Query> = None;
-    let mut current_download_history: Vec<Comment> = Vec::new();
-    let mut current_download_entry: Option<Comment> = None;
-    let mut current_value: Option<Value> = None;
-    let mut current_reason: Option<Reason> = None;
-    let mut text_content = String::new();
+    let mut current_record: Option<R> = None;
+    let mut current_forms: Vec<FormRef<'a>> = Vec::new();
+    let mut current_form: Option<FormRef<'a>> = None;
+    let mut current_states: Vec<StateRef<'a>> = Vec::new();
+    let mut current_lock_states: Vec<LockStateRef<'a>> = Vec::new();
+    let mut current_categories: Vec<CategoryRef<'a>> = Vec::new();
+    let mut current_category: Option<CategoryRef<'a>> = None;
+    let mut current_fields: Vec<FieldRef<'a>> = Vec::new();
+    let mut current_field: Option<FieldRef<'a>> = None;
+    let mut current_entries: Vec<EntryRef<'a>> = Vec::new();
+    let mut current_entry: Option<EntryRef<'a>> = None;
+    let mut current_comments: Vec<CommentRef<'a>> = Vec::new();
+    let mut current_comment: Option<CommentRef<'a>> = None;
+    let mut current_files: Vec<FileRef<'a>> = Vec::new();
+    let mut current_file: Option<FileRef<'a>> = None;
+    let mut current_queries: Vec<QueryRef<'a>> = Vec::new();
+    let mut current_query: Option<QueryRef<'a>> = None;
+    let mut current_download_history: Vec<CommentRef<'a>> = Vec::new();
+    let mut current_download_entry: Option<CommentRef<'a>> = None;
+    let mut current_value: Option<ValueRef<'a>> = None;
+    let mut current_reason: Option<ReasonRef<'a>> = None;
+    let mut text_content: Cow<'a, str> = Cow::Borrowed("");
     let mut in_form = false;
     let mut in_category = false;
     let mut in_field = false;
@@ -843,31 +1021,31 @@ fn parse_patient_xml(patient_xml: &str) -> Result<Patient, Error> {
                 let name_bytes = e.local_name();
                 if let Ok(name) = std::str::from_utf8(name_bytes.as_ref()) {
                     match name {
-                        "patient" => {
-                            current_patient = Some(Patient::from_attributes(e)?);
+                        _ if name == R::TAG => {
+                            current_record = Some(R::from_attributes(record_xml, e)?);
                             current_forms.clear();
                         }
-                        "form" if current_patient.is_some() => {
-                            current_form = Some(Form::from_attributes(e)?);
+                        "form" if current_record.is_some() => {
+                            current_form = Some(FormRef::from_attributes(record_xml, e)?);
                             in_form = true;
                             current_states.clear();
                             current_lock_states.clear();
                             current_categories.clear();
                         }
                         "category" if in_form => {
-                            current_category = Some(Category::from_attributes(e, &mut interner)?);
+                            current_category = Some(CategoryRef::from_attributes(record_xml, e)?);
                             in_category = true;
                             current_fields.clear();
                         }
                         "field" if in_category => {
-                            current_field = Some(Field::from_attributes(e, &mut interner)?);
+                            current_field = Some(FieldRef::from_attributes(record_xml, e)?);
                             in_field = true;
                             current_entries.clear();
                             current_comments.clear();
                             current_queries.clear();
                         }
                         "file" if in_category => {
-                            current_file = Some(File::from_attributes(e, &mut interner)?);
+                            current_file = Some(FileRef::from_attributes(record_xml, e)?);
                             in_file = true;
                             current_entries.clear();
                             current_comments.clear();
@@ -875,43 +1053,48 @@ fn parse_patient_xml(patient_xml: &str) -> Result<Patient, Error> {
                             current_download_history.clear();
                         }
                         "entry" if in_field || in_file => {
-                            current_entry = Some(Entry::from_attributes(e, &mut interner)?);
+                            current_entry = Some(EntryRef::from_attributes(record_xml, e)?);
                             in_entry = true;
                         }
                         "comment" if in_field || in_file => {
-                            current_comment = Some(Comment::from_attributes(e)?);
+                            current_comment = Some(CommentRef::from_attributes(record_xml, e)?);
                             in_comment = true;
                         }
                         "query" if in_field || in_file => {
-                            current_query = Some(Query::from_attributes(e, &mut interner)?);
+                            current_query = Some(QueryRef::from_attributes(record_xml, e)?);
                             in_query = true;
                         }
                         "downloadHistory" if in_file => {
-                            current_download_entry = Some(Comment::from_attributes(e)?);
+                            current_download_entry =
+                                Some(CommentRef::from_attributes(record_xml, e)?);
                             in_download_history = true;
                         }
                         "answer" if in_query => {
-                            current_value = Some(Value::from_attributes(e, &mut interner)?);
+                            current_value = Some(ValueRef::from_attributes(record_xml, e)?);
                             in_value = true;
-                            text_content.clear();
+                            text_content = Cow::Borrowed("");
                         }
                         "value" if in_entry || in_comment || in_query || in_download_history => {
-                            current_value = Some(Value::from_attributes(e, &mut interner)?);
+                            current_value = Some(ValueRef::from_attributes(record_xml, e)?);
                             in_value = true;
-                            text_content.clear();
+                            text_content = Cow::Borrowed("");
                         }
                         "reason" if in_entry => {
-                            current_reason = Some(Reason::from_attributes(e, &mut interner)?);
+                            current_reason = Some(ReasonRef::from_attributes(record_xml, e)?);
                             in_reason = true;
-                            text_content.clear();
+                            text_content = Cow::Borrowed("");
                         }
                         _ => {}
                     }
                 }
             }
 
-            Ok(Event::Text(e)) if (in_value || in_reason) => {
-                text_content.push_str(&e.xml10_content().map_err(decode_error)?);
+            Ok(Event::Text(ref e)) if (in_value || in_reason) => {
+                push_text(&mut text_content, record_xml, e)?;
+            }
+
+            Ok(Event::CData(ref e)) if (in_value || in_reason) => {
+                push_cdata(&mut text_content, record_xml, e)?;
             }
 
             Ok(Event::GeneralRef(ref e)) if (in_value || in_reason) => {
@@ -922,83 +1105,44 @@ fn parse_patient_xml(patient_xml: &str) -> Result<Patient, Error> {
                 let name_bytes = e.local_name();
                 if let Ok(name) = std::str::from_utf8(name_bytes.as_ref()) {
                     match name {
-                        "patient" => {
-                            if let Some(mut patient) = current_patient.take() {
-                                if !current_forms.is_empty() {
-                                    patient.set_forms(current_forms.drain(..).collect());
-                                }
-                                current_patient = Some(patient);
+                        _ if name == R::TAG => {
+                            if let Some(record) = current_record.as_mut() {
+                                record.set_forms(current_forms.drain(..).collect());
                             }
                         }
                         "form" if in_form => {
                             if let Some(mut form) = current_form.take() {
-                                if !current_states.is_empty() {
-                                    form.states =
-                                        Some(Arc::new(current_states.drain(..).collect()));
-                                }
-                                if !current_lock_states.is_empty() {
-                                    form.lock_states =
-                                        Some(Arc::new(current_lock_states.drain(..).collect()));
-                                }
-                                if !current_categories.is_empty() {
-                                    form.categories =
-                                        Some(Arc::new(current_categories.drain(..).collect()));
-                                }
+                                form.states = current_states.drain(..).collect();
+                                form.lock_states = current_lock_states.drain(..).collect();
+                                form.categories = current_categories.drain(..).collect();
                                 current_forms.push(form);
                             }
                             in_form = false;
                         }
                         "category" if in_category => {
                             if let Some(mut category) = current_category.take() {
-                                if !current_fields.is_empty() {
-                                    category.fields =
-                                        Some(Arc::new(current_fields.drain(..).collect()));
-                                }
-                                if !current_files.is_empty() {
-                                    category.files =
-                                        Some(Arc::new(current_files.drain(..).collect()));
-                                }
+                                category.fields = current_fields.drain(..).collect();
+                                category.files = current_files.drain(..).collect();
                                 current_categories.push(category);
                             }
                             in_category = false;
                         }
                         "field" if in_field => {
                             if let Some(mut field) = current_field.take() {
-                                if !current_entries.is_empty() {
-                                    field.entries =
-                                        Some(Arc::new(current_entries.drain(..).collect()));
-                                }
-                                if !current_comments.is_empty() {
-                                    field.comments =
-                                        Some(Arc::new(current_comments.drain(..).collect()));
-                                }
-                                if !current_queries.is_empty() {
-                                    field.queries =
-                                        Some(Arc::new(current_queries.drain(..).collect()));
-                                }
+                                field.entries = current_entries.drain(..).collect();
+                                field.comments = current_comments.drain(..).collect();
+                                field.queries = current_queries.drain(..).collect();
                                 current_fields.push(field);
                             }
                             in_field = false;
                         }
                         "file" if in_file => {
                             if let Some(mut file) = current_file.take() {
-                                if !current_entries.is_empty() {
-                                    file.entries =
-                                        Some(Arc::new(current_entries.drain(..).collect()));
-                                }
-                                if !current_comments.is_empty() {
-                                    file.comments =
-                                        Some(Arc::new(current_comments.drain(..).collect()));
-                                }
-                                if !current_queries.is_empty() {
-                                    file.queries =
-                                        Some(Arc::new(current_queries.drain(..).collect()));
-                                }
-                                if !current_download_history.is_empty() {
-                                    file.download_history = Some(Arc::new(
-                                        current_download_history.drain(..).collect(),
-                                    ));
-                                }
+                                file.entries = current_entries.drain(..).collect();
+                                file.comments = current_comments.drain(..).collect();
+                                file.queries = current_queries.drain(..).collect();
+                                file.download_history =
+                                    current_download_history.drain(..).collect();
                                 current_files.push(file);
                             }
                             in_file = false;
@@ -1017,7 +1161,9 @@ fn parse_patient_xml(patient_xml: &str) -> Result<Patient, Error> {
                         }
                         "entry" if in_entry => {
                             if let Some(entry) = current_entry.take() {
-                                current_entries.push(entry);
+                                if keeps_entry(options, &current_entries) {
+                                    current_entries.push(entry);
+                                }
                             }
                             in_entry = false;
                         }
@@ -1071,29 +1217,28 @@ fn parse_patient_xml(patient_xml: &str) -> Result<Patient, Error> {
                 if let Ok(name) = std::str::from_utf8(name_bytes.as_ref()) {
                     match name {
                         "state" if in_form => {
-                            let state = State::from_attributes(e, &mut interner)?;
-                            current_states.push(state);
+                            current_states.push(StateRef::from_attributes(record_xml, e)?);
                         }
                         "lockState" if in_form => {
-                            current_lock_states.push(LockState::from_attributes(e)?);
+                            current_lock_states.push(LockStateRef::from_attributes(record_xml, e)?);
                         }
                         "category" if in_form => {
-                            current_categories.push(Category::from_attributes(e, &mut interner)?);
+                            current_categories.push(CategoryRef::from_attributes(record_xml, e)?);
                         }
                         "field" if in_category => {
-                            current_fields.push(Field::from_attributes(e, &mut interner)?);
+                            current_fields.push(FieldRef::from_attributes(record_xml, e)?);
                         }
                         "file" if in_category => {
-                            current_files.push(File::from_attributes(e, &mut interner)?);
+                            current_files.push(FileRef::from_attributes(record_xml, e)?);
                         }
                         "value" if in_entry => {
-                            let value = Value::from_attributes(e, &mut interner)?;
+                            let value = ValueRef::from_attributes(record_xml, e)?;
                             if let Some(ref mut entry) = current_entry {
                                 entry.value = Some(value);
                             }
                         }
                         "reason" if in_entry => {
-                            let reason = Reason::from_attributes(e, &mut interner)?;
+                            let reason = ReasonRef::from_attributes(record_xml, e)?;
                             if let Some(ref mut entry) = current_entry {
                                 entry.reason = Some(reason);
                             }
@@ -1107,10 +1252,46 @@ fn parse_patient_xml(patient_xml: &str) -> Result<Patient, Error> {
         }
     }
 
-    current_patient.ok_or_else(|| {
-        Error::ParsingError(quick_xml::de::DeError::Custom(
-            "No patient found in chunk".to_string(),
-        ))
+    current_record.ok_or_else(|| {
+        Error::ParsingError(quick_xml::de::DeError::Custom(format!(
+            "No {} found in chunk",
+            R::TAG
+        )))
+    })
+}
+
+/// Parse a string of Prelude native site XML into a `SiteNativeRef` that borrows from it.
+///
+/// # Example
+///
+/// ```
+/// use std::fs::read_to_string;
+///
+/// use prelude_xml_parser::{parse_site_native_borrowed, parse_site_native_string};
+///
+/// let xml = read_to_string("tests/assets/site_native.xml").unwrap();
+/// let native = parse_site_native_borrowed(&xml).unwrap();
+///
+/// assert_eq!(native.sites[0].name, "Some Site");
+/// assert_eq!(native.into_owned(), parse_site_native_string(&xml).unwrap());
+/// ```
+pub fn parse_site_native_borrowed(xml_str: &str) -> Result<SiteNativeRef<'_>, Error> {
+    parse_site_native_borrowed_with_options(xml_str, &ParseOptions::default())
+}
+
+/// Parse a string of Prelude native site XML into a `SiteNativeRef` that borrows from it,
+/// using the given options.
+pub fn parse_site_native_borrowed_with_options<'a>(
+    xml_str: &'a str,
+    options: &ParseOptions,
+) -> Result<SiteNativeRef<'a>, Error> {
+    let chunks = extract_site_chunks(xml_str);
+    let sites = parse_records(xml_str, chunks, options, |xml, options, _| {
+        parse_record::<SiteRef>(xml, options)
+    })?;
+    Ok(SiteNativeRef {
+        export: parse_export(xml_str, options)?,
+        sites,
     })
 }
 
@@ -1118,352 +1299,44 @@ fn extract_site_chunks(xml: &str) -> Vec<&str> {
     extract_chunks(xml, "site")
 }
 
-#[allow(clippy::drain_collect)]
-fn parse_site_xml(site_xml: &str) -> Result<Site, Error> {
-    let mut interner = Interner::default();
-    let mut xml_reader = Reader::from_str(site_xml);
-    xml_reader.config_mut().trim_text(false);
-
-    let mut current_site: Option<Site> = None;
-    let mut current_forms: Vec<Form> = Vec::new();
-    let mut current_form: Option<Form> = None;
-    let mut current_states: Vec<State> = Vec::new();
-    let mut current_lock_states: Vec<LockState> = Vec::new();
-    let mut current_categories: Vec<Category> = Vec::new();
-    let mut current_category: Option<Category> = None;
-    let mut current_fields: Vec<Field> = Vec::new();
-    let mut current_field: Option<Field> = None;
-    let mut current_entries: Vec<Entry> = Vec::new();
-    let mut current_entry: Option<Entry> = None;
-    let mut current_comments: Vec<Comment> = Vec::new();
-    let mut current_comment: Option<Comment> = None;
-    let mut current_files: Vec<File> = Vec::new();
-    let mut current_file: Option<File> = None;
-    let mut current_queries: Vec<Query> = Vec::new();
-    let mut current_query: Option<Query> = None;
-    let mut current_download_history: Vec<Comment> = Vec::new();
-    let mut current_download_entry: Option<Comment> = None;
-    let mut current_value: Option<Value> = None;
-    let mut current_reason: Option<Reason> = None;
-    let mut text_content = String::new();
-    let mut in_form = false;
-    let mut in_category = false;
-    let mut in_field = false;
-    let mut in_entry = false;
-    let mut in_comment = false;
-    let mut in_file = false;
-    let mut in_query = false;
-    let mut in_download_history = false;
-    let mut in_value = false;
-    let mut in_reason = false;
+fn parse_site_xml(
+    site_xml: &str,
+    options: &ParseOptions,
+    interner: &mut Interner,
+) -> Result<Site, Error> {
+    parse_record::<SiteRef>(site_xml, options).map(|site| site.into_owned_with(interner))
+}
 
-    loop {
-        match xml_reader.read_event() {
-            Err(e) => {
-                return Err(Error::ParsingError(quick_xml::de::DeError::Custom(
-                    format!("XML reading error: {}", e),
-                )))
-            }
-            Ok(Event::Eof) => break,
+/// Parses a Prelude native user XML file into a `UserNative` struct.
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+///
+/// use prelude_xml_parser::parse_user_native_file;
+///
+/// let file_path = Path::new("tests/assets/user_native.xml");
+/// let native = parse_user_native_file(&file_path).unwrap();
+///
+/// assert!(native.users.len() >= 1, "Vector length is less than 1");
+/// ```
+pub fn parse_user_native_file(xml_path: &Path) -> Result<UserNative, Error> {
+    parse_user_native_file_with_options(xml_path, &ParseOptions::default())
+}
 
-            Ok(Event::Start(ref e)) => {
-                let name_bytes = e.local_name();
-                if let Ok(name) = std::str::from_utf8(name_bytes.as_ref()) {
-                    match name {
-                        "site" => {
-                            current_site = Some(Site::from_attributes(e)?);
-                            current_forms.clear();
-                        }
-                        "form" if current_site.is_some() => {
-                            current_form = Some(Form::from_attributes(e)?);
-                            in_form = true;
-                            current_states.clear();
-                            current_lock_states.clear();
-                            current_categories.clear();
-                        }
-                        "category" if in_form => {
-                            current_category = Some(Category::from_attributes(e, &mut interner)?);
-                            in_category = true;
-                            current_fields.clear();
-                        }
-                        "field" if in_category => {
-                            current_field = Some(Field::from_attributes(e, &mut interner)?);
-                            in_field = true;
-                            current_entries.clear();
-                            current_comments.clear();
-                            current_queries.clear();
-                        }
-                        "file" if in_category => {
-                            current_file = Some(File::from_attributes(e, &mut interner)?);
-                            in_file = true;
-                            current_entries.clear();
-                            current_comments.clear();
-                            current_queries.clear();
-                            current_download_history.clear();
-                        }
-                        "entry" if in_field || in_file => {
-                            current_entry = Some(Entry::from_attributes(e, &mut interner)?);
-                            in_entry = true;
-                        }
-                        "comment" if in_field || in_file => {
-                            current_comment = Some(Comment::from_attributes(e)?);
-                            in_comment = true;
-                        }
-                        "query" if in_field || in_file => {
-                            current_query = Some(Query::from_attributes(e, &mut interner)?);
-                            in_query = true;
-                        }
-                        "downloadHistory" if in_file => {
-                            current_download_entry = Some(Comment::from_attributes(e)?);
-                            in_download_history = true;
-                        }
-                        "answer" if in_query => {
-                            current_value = Some(Value::from_attributes(e, &mut interner)?);
-                            in_value = true;
-                            text_content.clear();
-                        }
-                        "value" if in_entry || in_comment || in_query || in_download_history => {
-                            current_value = Some(Value::from_attributes(e, &mut interner)?);
-                            in_value = true;
-                            text_content.clear();
-                        }
-                        "reason" if in_entry => {
-                            current_reason = Some(Reason::from_attributes(e, &mut interner)?);
-                            in_reason = true;
-                            text_content.clear();
-                        }
-                        _ => {}
-                    }
-                }
-            }
+/// Parses a Prelude native user XML file into a `UserNative` struct using the given options.
+pub fn parse_user_native_file_with_options(
+    xml_path: &Path,
+    options: &ParseOptions,
+) -> Result<UserNative, Error> {
+    check_valid_xml_file(xml_path)?;
 
-            Ok(Event::Text(e)) if (in_value || in_reason) => {
-                text_content.push_str(&e.xml10_content().map_err(decode_error)?);
-            }
+    let xml_file = XmlFile::open(xml_path, options.file_input)?;
+    let native = parse_user_native_string_with_options(&xml_file.text()?, options)?;
 
-            Ok(Event::GeneralRef(ref e)) if (in_value || in_reason) => {
-                push_general_ref(&mut text_content, e)?;
-            }
-
-            Ok(Event::End(ref e)) => {
-                let name_bytes = e.local_name();
-                if let Ok(name) = std::str::from_utf8(name_bytes.as_ref()) {
-                    match name {
-                        "site" => {
-                            if let Some(mut site) = current_site.take() {
-                                if !current_forms.is_empty() {
-                                    site.set_forms(current_forms.drain(..).collect());
-                                }
-                                current_site = Some(site);
-                            }
-                        }
-                        "form" if in_form => {
-                            if let Some(mut form) = current_form.take() {
-                                if !current_states.is_empty() {
-                                    form.states =
-                                        Some(Arc::new(current_states.drain(..).collect()));
-                                }
-                                if !current_lock_states.is_empty() {
-                                    form.lock_states =
-                                        Some(Arc::new(current_lock_states.drain(..).collect()));
-                                }
-                                if !current_categories.is_empty() {
-                                    form.categories =
-                                        Some(Arc::new(current_categories.drain(..).collect()));
-                                }
-                                current_forms.push(form);
-                            }
-                            in_form = false;
-                        }
-                        "category" if in_category => {
-                            if let Some(mut category) = current_category.take() {
-                                if !current_fields.is_empty() {
-                                    category.fields =
-                                        Some(Arc::new(current_fields.drain(..).collect()));
-                                }
-                                if !current_files.is_empty() {
-                                    category.files =
-                                        Some(Arc::new(current_files.drain(..).collect()));
-                                }
-                                current_categories.push(category);
-                            }
-                            in_category = false;
-                        }
-                        "field" if in_field => {
-                            if let Some(mut field) = current_field.take() {
-                                if !current_entries.is_empty() {
-                                    field.entries =
-                                        Some(Arc::new(current_entries.drain(..).collect()));
-                                }
-                                if !current_comments.is_empty() {
-                                    field.comments =
-                                        Some(Arc::new(current_comments.drain(..).collect()));
-                                }
-                                if !current_queries.is_empty() {
-                                    field.queries =
-                                        Some(Arc::new(current_queries.drain(..).collect()));
-                                }
-                                current_fields.push(field);
-                            }
-                            in_field = false;
-                        }
-                        "file" if in_file => {
-                            if let Some(mut file) = current_file.take() {
-                                if !current_entries.is_empty() {
-                                    file.entries =
-                                        Some(Arc::new(current_entries.drain(..).collect()));
-                                }
-                                if !current_comments.is_empty() {
-                                    file.comments =
-                                        Some(Arc::new(current_comments.drain(..).collect()));
-                                }
-                                if !current_queries.is_empty() {
-                                    file.queries =
-                                        Some(Arc::new(current_queries.drain(..).collect()));
-                                }
-                                if !current_download_history.is_empty() {
-                                    file.download_history = Some(Arc::new(
-                                        current_download_history.drain(..).collect(),
-                                    ));
-                                }
-                                current_files.push(file);
-                            }
-                            in_file = false;
-                        }
-                        "query" if in_query => {
-                            if let Some(query) = current_query.take() {
-                                current_queries.push(query);
-                            }
-                            in_query = false;
-                        }
-                        "downloadHistory" if in_download_history => {
-                            if let Some(download) = current_download_entry.take() {
-                                current_download_history.push(download);
-                            }
-                            in_download_history = false;
-                        }
-                        "entry" if in_entry => {
-                            if let Some(entry) = current_entry.take() {
-                                current_entries.push(entry);
-                            }
-                            in_entry = false;
-                        }
-                        "comment" if in_comment => {
-                            if let Some(comment) = current_comment.take() {
-                                current_comments.push(comment);
-                            }
-                            in_comment = false;
-                        }
-                        "answer" if in_value => {
-                            if let Some(mut value) = current_value.take() {
-                                value.value = take_trimmed(&mut text_content);
-                                if let Some(ref mut query) = current_query {
-                                    query.answer = Some(value);
-                                }
-                            }
-                            in_value = false;
-                        }
-                        "value" if in_value => {
-                            if let Some(mut value) = current_value.take() {
-                                value.value = take_trimmed(&mut text_content);
-                                // innermost owner wins; these never nest within one another
-                                if let Some(ref mut download) = current_download_entry {
-                                    download.value = Some(value);
-                                } else if let Some(ref mut query) = current_query {
-                                    query.value = Some(value);
-                                } else if let Some(ref mut entry) = current_entry {
-                                    entry.value = Some(value);
-                                } else if let Some(ref mut comment) = current_comment {
-                                    comment.value = Some(value);
-                                }
-                            }
-                            in_value = false;
-                        }
-                        "reason" if in_reason => {
-                            if let Some(mut reason) = current_reason.take() {
-                                reason.value = take_trimmed(&mut text_content);
-                                if let Some(ref mut entry) = current_entry {
-                                    entry.reason = Some(reason);
-                                }
-                            }
-                            in_reason = false;
-                        }
-                        _ => {}
-                    }
-                }
-            }
-
-            Ok(Event::Empty(ref e)) => {
-                let name_bytes = e.local_name();
-                if let Ok(name) = std::str::from_utf8(name_bytes.as_ref()) {
-                    match name {
-                        "state" if in_form => {
-                            let state = State::from_attributes(e, &mut interner)?;
-                            current_states.push(state);
-                        }
-                        "lockState" if in_form => {
-                            current_lock_states.push(LockState::from_attributes(e)?);
-                        }
-                        "category" if in_form => {
-                            current_categories.push(Category::from_attributes(e, &mut interner)?);
-                        }
-                        "field" if in_category => {
-                            let field = Field::from_attributes(e, &mut interner)?;
-                            current_fields.push(field);
-                        }
-                        "file" if in_category => {
-                            current_files.push(File::from_attributes(e, &mut interner)?);
-                        }
-                        "value" if in_entry => {
-                            let value = Value::from_attributes(e, &mut interner)?;
-                            if let Some(ref mut entry) = current_entry {
-                                entry.value = Some(value);
-                            }
-                        }
-                        "reason" if in_entry => {
-                            let reason = Reason::from_attributes(e, &mut interner)?;
-                            if let Some(ref mut entry) = current_entry {
-                                entry.reason = Some(reason);
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
-
-            _ => {}
-        }
-    }
-
-    current_site.ok_or_else(|| {
-        Error::ParsingError(quick_xml::de::DeError::Custom(
-            "No site found in chunk".to_string(),
-        ))
-    })
-}
-
-/// Parses a Prelude native user XML file into a `UserNative` struct.
-///
-/// # Example
-///
-/// ```
-/// use std::path::Path;
-///
-/// use prelude_xml_parser::parse_user_native_file;
-///
-/// let file_path = Path::new("tests/assets/user_native.xml");
-/// let native = parse_user_native_file(&file_path).unwrap();
-///
-/// assert!(native.users.len() >= 1, "Vector length is less than 1");
-/// ```
-pub fn parse_user_native_file(xml_path: &Path) -> Result<UserNative, Error> {
-    check_valid_xml_file(xml_path)?;
-
-    let xml_file = read_to_string(xml_path)?;
-    let native = parse_user_native_string(&xml_file)?;
-
-    Ok(native)
-}
+    Ok(native)
+}
 
 /// Parse a string of Prelude native user XML into a `UserNative` struct.
 ///
@@ -1655,331 +1528,67 @@ pub fn parse_user_native_file(xml_path: &Path) -> Result<UserNative, Error> {
 /// assert_eq!(result, expected);
 /// ```
 pub fn parse_user_native_string(xml_str: &str) -> Result<UserNative, Error> {
+    parse_user_native_string_with_options(xml_str, &ParseOptions::default())
+}
+
+/// Parse a string of Prelude native user XML into a `UserNative` struct using the given options.
+pub fn parse_user_native_string_with_options(
+    xml_str: &str,
+    options: &ParseOptions,
+) -> Result<UserNative, Error> {
     let chunks = extract_user_chunks(xml_str);
-    let users = chunks
-        .into_par_iter()
-        .map(parse_user_xml)
-        .collect::<Result<Vec<_>, _>>()?;
+    let users = parse_records(xml_str, chunks, options, parse_user_xml)?;
     Ok(UserNative {
-        export: parse_export(xml_str)?,
+        export: parse_export(xml_str, options)?,
         users,
     })
 }
 
-fn extract_user_chunks(xml: &str) -> Vec<&str> {
-    extract_chunks(xml, "user")
+/// Parse a string of Prelude native user XML into a `UserNativeRef` that borrows from it.
+///
+/// # Example
+///
+/// ```
+/// use std::fs::read_to_string;
+///
+/// use prelude_xml_parser::{parse_user_native_borrowed, parse_user_native_string};
+///
+/// let xml = read_to_string("tests/assets/user_native.xml").unwrap();
+/// let native = parse_user_native_borrowed(&xml).unwrap();
+///
+/// assert_eq!(native.users[0].unique_id, "1691421275437");
+/// assert_eq!(native.into_owned(), parse_user_native_string(&xml).unwrap());
+/// ```
+pub fn parse_user_native_borrowed(xml_str: &str) -> Result<UserNativeRef<'_>, Error> {
+    parse_user_native_borrowed_with_options(xml_str, &ParseOptions::default())
 }
 
-#[allow(clippy::drain_collect)]
-fn parse_user_xml(user_xml: &str) -> Result<User, Error> {
-    let mut interner = Interner::default();
-    let mut xml_reader = Reader::from_str(user_xml);
-    xml_reader.config_mut().trim_text(false);
-
-    let mut current_user: Option<User> = None;
-    let mut current_forms: Vec<Form> = Vec::new();
-    let mut current_form: Option<Form> = None;
-    let mut current_states: Vec<State> = Vec::new();
-    let mut current_lock_states: Vec<LockState> = Vec::new();
-    let mut current_categories: Vec<Category> = Vec::new();
-    let mut current_category: Option<Category> = None;
-    let mut current_fields: Vec<Field> = Vec::new();
-    let mut current_field: Option<Field> = None;
-    let mut current_entries: Vec<Entry> = Vec::new();
-    let mut current_entry: Option<Entry> = None;
-    let mut current_comments: Vec<Comment> = Vec::new();
-    let mut current_comment: Option<Comment> = None;
-    let mut current_files: Vec<File> = Vec::new();
-    let mut current_file: Option<File> = None;
-    let mut current_queries: Vec<Query> = Vec::new();
-    let mut current_query: Option<Query> = None;
-    let mut current_download_history: Vec<Comment> = Vec::new();
-    let mut current_download_entry: Option<Comment> = None;
-    let mut current_value: Option<Value> = None;
-    let mut current_reason: Option<Reason> = None;
-    let mut text_content = String::new();
-    let mut in_form = false;
-    let mut in_category = false;
-    let mut in_field = false;
-    let mut in_entry = false;
-    let mut in_comment = false;
-    let mut in_file = false;
-    let mut in_query = false;
-    let mut in_download_history = false;
-    let mut in_value = false;
-    let mut in_reason = false;
-
-    loop {
-        match xml_reader.read_event() {
-            Err(e) => {
-                return Err(Error::ParsingError(quick_xml::de::DeError::Custom(
-                    format!("XML reading error: {}", e),
-                )))
-            }
-            Ok(Event::Eof) => break,
-
-            Ok(Event::Start(ref e)) => {
-                let name_bytes = e.local_name();
-                if let Ok(name) = std::str::from_utf8(name_bytes.as_ref()) {
-                    match name {
-                        "user" => {
-                            current_user = Some(User::from_attributes(e)?);
-                        }
-                        "form" => {
-                            current_form = Some(Form::from_attributes(e)?);
-                            in_form = true;
-                        }
-                        "category" if in_form => {
-                            current_category = Some(Category::from_attributes(e, &mut interner)?);
-                            in_category = true;
-                        }
-                        "field" if in_category => {
-                            current_field = Some(Field::from_attributes(e, &mut interner)?);
-                            in_field = true;
-                        }
-                        "file" if in_category => {
-                            current_file = Some(File::from_attributes(e, &mut interner)?);
-                            in_file = true;
-                        }
-                        "entry" if in_field || in_file => {
-                            current_entry = Some(Entry::from_attributes(e, &mut interner)?);
-                            in_entry = true;
-                        }
-                        "comment" if in_field || in_file => {
-                            current_comment = Some(Comment::from_attributes(e)?);
-                            in_comment = true;
-                        }
-                        "query" if in_field || in_file => {
-                            current_query = Some(Query::from_attributes(e, &mut interner)?);
-                            in_query = true;
-                        }
-                        "downloadHistory" if in_file => {
-                            current_download_entry = Some(Comment::from_attributes(e)?);
-                            in_download_history = true;
-                        }
-                        "answer" if in_query => {
-                            current_value = Some(Value::from_attributes(e, &mut interner)?);
-                            in_value = true;
-                            text_content.clear();
-                        }
-                        "value" if in_entry || in_comment || in_query || in_download_history => {
-                            current_value = Some(Value::from_attributes(e, &mut interner)?);
-                            in_value = true;
-                            text_content.clear();
-                        }
-                        "reason" if in_entry => {
-                            current_reason = Some(Reason::from_attributes(e, &mut interner)?);
-                            in_reason = true;
-                            text_content.clear();
-                        }
-                        _ => {}
-                    }
-                }
-            }
-
-            Ok(Event::Text(e)) if (in_value || in_reason) => {
-                text_content.push_str(&e.xml10_content().map_err(decode_error)?);
-            }
-
-            Ok(Event::GeneralRef(ref e)) if (in_value || in_reason) => {
-                push_general_ref(&mut text_content, e)?;
-            }
-
-            Ok(Event::End(ref e)) => {
-                let name_bytes = e.local_name();
-                if let Ok(name) = std::str::from_utf8(name_bytes.as_ref()) {
-                    match name {
-                        "user" => {
-                            if let Some(mut user) = current_user.take() {
-                                if !current_forms.is_empty() {
-                                    user.set_forms(current_forms.drain(..).collect());
-                                }
-                                current_user = Some(user);
-                            }
-                        }
-                        "form" if in_form => {
-                            if let Some(mut form) = current_form.take() {
-                                if !current_states.is_empty() {
-                                    form.states =
-                                        Some(Arc::new(current_states.drain(..).collect()));
-                                }
-                                if !current_lock_states.is_empty() {
-                                    form.lock_states =
-                                        Some(Arc::new(current_lock_states.drain(..).collect()));
-                                }
-                                if !current_categories.is_empty() {
-                                    form.categories =
-                                        Some(Arc::new(current_categories.drain(..).collect()));
-                                }
-                                current_forms.push(form);
-                            }
-                            in_form = false;
-                        }
-                        "category" if in_category => {
-                            if let Some(mut category) = current_category.take() {
-                                if !current_fields.is_empty() {
-                                    category.fields =
-                                        Some(Arc::new(current_fields.drain(..).collect()));
-                                }
-                                if !current_files.is_empty() {
-                                    category.files =
-                                        Some(Arc::new(current_files.drain(..).collect()));
-                                }
-                                current_categories.push(category);
-                            }
-                            in_category = false;
-                        }
-                        "field" if in_field => {
-                            if let Some(mut field) = current_field.take() {
-                                if !current_entries.is_empty() {
-                                    field.entries =
-                                        Some(Arc::new(current_entries.drain(..).collect()));
-                                }
-                                if !current_comments.is_empty() {
-                                    field.comments =
-                                        Some(Arc::new(current_comments.drain(..).collect()));
-                                }
-                                if !current_queries.is_empty() {
-                                    field.queries =
-                                        Some(Arc::new(current_queries.drain(..).collect()));
-                                }
-                                current_fields.push(field);
-                            }
-                            in_field = false;
-                        }
-                        "file" if in_file => {
-                            if let Some(mut file) = current_file.take() {
-                                if !current_entries.is_empty() {
-                                    file.entries =
-                                        Some(Arc::new(current_entries.drain(..).collect()));
-                                }
-                                if !current_comments.is_empty() {
-                                    file.comments =
-                                        Some(Arc::new(current_comments.drain(..).collect()));
-                                }
-                                if !current_queries.is_empty() {
-                                    file.queries =
-                                        Some(Arc::new(current_queries.drain(..).collect()));
-                                }
-                                if !current_download_history.is_empty() {
-                                    file.download_history = Some(Arc::new(
-                                        current_download_history.drain(..).collect(),
-                                    ));
-                                }
-                                current_files.push(file);
-                            }
-                            in_file = false;
-                        }
-                        "query" if in_query => {
-                            if let Some(query) = current_query.take() {
-                                current_queries.push(query);
-                            }
-                            in_query = false;
-                        }
-                        "downloadHistory" if in_download_history => {
-                            if let Some(download) = current_download_entry.take() {
-                                current_download_history.push(download);
-                            }
-                            in_download_history = false;
-                        }
-                        "entry" if in_entry => {
-                            if let Some(entry) = current_entry.take() {
-                                current_entries.push(entry);
-                            }
-                            in_entry = false;
-                        }
-                        "comment" if in_comment => {
-                            if let Some(comment) = current_comment.take() {
-                                current_comments.push(comment);
-                            }
-                            in_comment = false;
-                        }
-                        "answer" if in_value => {
-                            if let Some(mut value) = current_value.take() {
-                                value.value = take_trimmed(&mut text_content);
-                                if let Some(ref mut query) = current_query {
-                                    query.answer = Some(value);
-                                }
-                            }
-                            in_value = false;
-                        }
-                        "value" if in_value => {
-                            if let Some(mut value) = current_value.take() {
-                                value.value = take_trimmed(&mut text_content);
-                                // innermost owner wins; these never nest within one another
-                                if let Some(ref mut download) = current_download_entry {
-                                    download.value = Some(value);
-                                } else if let Some(ref mut query) = current_query {
-                                    query.value = Some(value);
-                                } else if let Some(ref mut entry) = current_entry {
-                                    entry.value = Some(value);
-                                } else if let Some(ref mut comment) = current_comment {
-                                    comment.value = Some(value);
-                                }
-                            }
-                            in_value = false;
-                        }
-                        "reason" if in_reason => {
-                            if let Some(mut reason) = current_reason.take() {
-                                reason.value = take_trimmed(&mut text_content);
-                                if let Some(ref mut entry) = current_entry {
-                                    entry.reason = Some(reason);
-                                }
-                            }
-                            in_reason = false;
-                        }
-                        _ => {}
-                    }
-                }
-            }
-
-            Ok(Event::Empty(ref e)) => {
-                let name_bytes = e.local_name();
-                if let Ok(name) = std::str::from_utf8(name_bytes.as_ref()) {
-                    match name {
-                        "state" if in_form => {
-                            let state = State::from_attributes(e, &mut interner)?;
-                            current_states.push(state);
-                        }
-                        "category" if in_form => {
-                            current_categories.push(Category::from_attributes(e, &mut interner)?);
-                        }
-                        "field" if in_category => {
-                            let field = Field::from_attributes(e, &mut interner)?;
-                            current_fields.push(field);
-                        }
-                        "file" if in_category => {
-                            current_files.push(File::from_attributes(e, &mut interner)?);
-                        }
-                        "lockState" if in_form => {
-                            current_lock_states.push(LockState::from_attributes(e)?);
-                        }
-                        "value" if in_entry => {
-                            let value = Value::from_attributes(e, &mut interner)?;
-                            if let Some(ref mut entry) = current_entry {
-                                entry.value = Some(value);
-                            }
-                        }
-                        "reason" if in_entry => {
-                            let reason = Reason::from_attributes(e, &mut interner)?;
-                            if let Some(ref mut entry) = current_entry {
-                                entry.reason = Some(reason);
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
+/// Parse a string of Prelude native user XML into a `UserNativeRef` that borrows from it,
+/// using the given options.
+pub fn parse_user_native_borrowed_with_options<'a>(
+    xml_str: &'a str,
+    options: &ParseOptions,
+) -> Result<UserNativeRef<'a>, Error> {
+    let chunks = extract_user_chunks(xml_str);
+    let users = parse_records(xml_str, chunks, options, |xml, options, _| {
+        parse_record::<UserRef>(xml, options)
+    })?;
+    Ok(UserNativeRef {
+        export: parse_export(xml_str, options)?,
+        users,
+    })
+}
 
-            _ => {}
-        }
-    }
+fn extract_user_chunks(xml: &str) -> Vec<&str> {
+    extract_chunks(xml, "user")
+}
 
-    current_user.ok_or_else(|| {
-        Error::ParsingError(quick_xml::de::DeError::Custom(
-            "No user element found".to_string(),
-        ))
-    })
+fn parse_user_xml(
+    user_xml: &str,
+    options: &ParseOptions,
+    interner: &mut Interner,
+) -> Result<User, Error> {
+    parse_record::<UserRef>(user_xml, options).map(|user| user.into_owned_with(interner))
 }
 
 fn check_valid_xml_file(xml_path: &Path) -> Result<(), Error> {
@@ -2001,6 +1610,8 @@ fn check_valid_xml_file(xml_path: &Path) -> Result<(), Error> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::options::FileInput;
+    use std::{fs::read_to_string, io::Write, sync::Arc};
     use tempfile::{tempdir, Builder};
 
     #[test]
@@ -2045,6 +1656,86 @@ mod tests {
         assert!(matches!(result, Err(Error::InvalidFileType(_))));
     }
 
+    #[test]
+    fn test_file_inputs_give_same_result() {
+        let file_path = Path::new("tests/assets/subject_native.xml");
+        let mapped: SubjectNative = NativeParser::new()
+            .file_input(FileInput::Mmap)
+            .parse_file(file_path)
+            .unwrap();
+        let read: SubjectNative = NativeParser::new()
+            .file_input(FileInput::Read)
+            .parse_file(file_path)
+            .unwrap();
+
+        assert_eq!(mapped, read);
+        assert_eq!(mapped, parse_subject_native_file(file_path).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_utf8_file_error() {
+        let mut file = Builder::new().suffix(".xml").tempfile().unwrap();
+        file.write_all(
+            b"<export_from_vision_EDC><site name=\"Caf\xe9\"></site></export_from_vision_EDC>",
+        )
+        .unwrap();
+
+        for file_input in [FileInput::Mmap, FileInput::Read] {
+            let result: Result<SiteNative, _> = NativeParser::new()
+                .file_input(file_input)
+                .parse_file(file.path());
+            assert!(
+                matches!(result, Err(Error::InvalidUtf8(_))),
+                "{file_input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_declared_encodings_give_same_result() {
+        let xml = read_to_string("tests/assets/user_native.xml")
+            .unwrap()
+            .replace("Paul Sanders", "Paul Sändérs");
+        let expected = parse_user_native_string(&xml).unwrap();
+        assert_eq!(
+            expected.users[0].forms.as_ref().unwrap()[0]
+                .who_last_modified_name
+                .as_deref(),
+            Some("Paul Sändérs")
+        );
+
+        let windows_1252 = xml.replace("encoding=\"UTF-8\"", "encoding=\"ISO-8859-1\"");
+        let (windows_1252, _, _) = encoding_rs::WINDOWS_1252.encode(&windows_1252);
+        let utf16 = xml.replace("encoding=\"UTF-8\"", "encoding=\"UTF-16\"");
+        let utf16: Vec<u8> = "\u{FEFF}"
+            .encode_utf16()
+            .chain(utf16.encode_utf16())
+            .flat_map(u16::to_le_bytes)
+            .collect();
+
+        for bytes in [&windows_1252[..], &utf16[..]] {
+            let mut file = Builder::new().suffix(".xml").tempfile().unwrap();
+            file.write_all(bytes).unwrap();
+
+            for file_input in [FileInput::Mmap, FileInput::Read] {
+                let result: UserNative = NativeParser::new()
+                    .file_input(file_input)
+                    .parse_file(file.path())
+                    .unwrap();
+                assert_eq!(result, expected, "{file_input:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_file_has_no_records() {
+        let file = Builder::new().suffix(".xml").tempfile().unwrap();
+        let result = parse_user_native_file(file.path()).unwrap();
+
+        assert_eq!(result.export, None);
+        assert!(result.users.is_empty());
+    }
+
     #[test]
     fn test_user_file_not_found_error() {
         let dir = tempdir().unwrap().path().to_path_buf();
@@ -2066,6 +1757,223 @@ mod tests {
         assert!(matches!(result, Err(Error::InvalidFileType(_))));
     }
 
+    #[test]
+    fn test_skip_export_option() {
+        let file_path = Path::new("tests/assets/site_native_small.xml");
+
+        let with_export: SiteNative = NativeParser::new().parse_file(file_path).unwrap();
+        let without_export: SiteNative = NativeParser::new()
+            .skip_export(true)
+            .parse_file(file_path)
+            .unwrap();
+
+        assert!(with_export.export.is_some());
+        assert!(without_export.export.is_none());
+        assert_eq!(with_export.sites, without_export.sites);
+    }
+
+    #[test]
+    fn test_parallelism_options_give_same_result() {
+        let file_path = Path::new("tests/assets/subject_native.xml");
+        let expected = parse_subject_native_file(file_path).unwrap();
+        let pool = Arc::new(ThreadPoolBuilder::new().num_threads(2).build().unwrap());
+
+        for parser in [
+            NativeParser::new().single_threaded(),
+            NativeParser::new().threads(1),
+            NativeParser::new().threads(3),
+            NativeParser::new().thread_pool(pool.clone()),
+        ] {
+            let result: SubjectNative = parser.parse_file(file_path).unwrap();
+            assert_eq!(result, expected, "{:?}", parser.options().parallelism);
+        }
+    }
+
+    #[test]
+    fn test_interned_strings_are_shared_across_records() {
+        let patient = r#"
+  <patient patientId="ABC-001" uniqueId="1" creator="Paul" siteName="Some Site" siteUniqueId="2">
+    <form name="demographics" formTitle="Demographics" formGroup="Day 0" formState="In-Work">
+      <category name="Demographics" type="normal" highestIndex="0">
+        <field name="breed" type="text" dataType="string" errorCode="valid" keepHistory="true">
+          <entry id="1">
+            <value by="Paul" byUniqueId="" role="PM" when="">Labrador</value>
+          </entry>
+        </field>
+      </category>
+    </form>
+  </patient>"#;
+        let xml = format!(
+            "<export_from_vision_EDC>{}\n</export_from_vision_EDC>",
+            patient.repeat(64)
+        );
+
+        for parser in [
+            NativeParser::new(),
+            NativeParser::new().single_threaded(),
+            NativeParser::new().threads(3),
+        ] {
+            let native: SubjectNative = parser.parse_str(&xml).unwrap();
+            let form = |i: usize| &native.patients[i].forms.as_ref().unwrap()[0];
+            let field = |i: usize| {
+                &form(i).categories.as_ref().unwrap()[0]
+                    .fields
+                    .as_ref()
+                    .unwrap()[0]
+            };
+            let value = |i: usize| {
+                field(i).entries.as_ref().unwrap()[0]
+                    .value
+                    .as_ref()
+                    .unwrap()
+            };
+
+            assert_eq!(native.patients.len(), 64);
+            for i in 1..native.patients.len() {
+                assert!(Arc::ptr_eq(&form(i).name, &form(0).name));
+                assert!(Arc::ptr_eq(&form(i).form_title, &form(0).form_title));
+                assert!(Arc::ptr_eq(
+                    form(i).form_group.as_ref().unwrap(),
+                    form(0).form_group.as_ref().unwrap()
+                ));
+                assert!(Arc::ptr_eq(&field(i).name, &field(0).name));
+                assert!(Arc::ptr_eq(&field(i).field_type, &field(0).field_type));
+                assert!(Arc::ptr_eq(&value(i).by, &value(0).by));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sequential_parsing_stays_on_calling_thread() {
+        let xml = read_to_string("tests/assets/user_native.xml").unwrap();
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let threads = Arc::new(Mutex::new(Vec::new()));
+        let seen = threads.clone();
+
+        // Inside a pool the calling thread is a rayon worker, so a sequential parse must not
+        // hop to any other thread. Progress is reported from whichever thread parsed a record.
+        let (result, caller): (UserNative, _) = pool.install(|| {
+            let result = NativeParser::new()
+                .single_threaded()
+                .progress(move |_| seen.lock().unwrap().push(std::thread::current().id()))
+                .parse_str(&xml)
+                .unwrap();
+            (result, std::thread::current().id())
+        });
+
+        let threads = threads.lock().unwrap();
+        assert!(!threads.is_empty());
+        assert!(threads.iter().all(|thread| *thread == caller));
+        assert_eq!(result, parse_user_native_string(&xml).unwrap());
+    }
+
+    #[test]
+    fn test_progress_reports_every_record() {
+        let xml = read_to_string("tests/assets/subject_native.xml").unwrap();
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let seen = reports.clone();
+
+        let result: SubjectNative = NativeParser::new()
+            .progress(move |progress| seen.lock().unwrap().push(progress))
+            .parse_str(&xml)
+            .unwrap();
+
+        let reports = reports.lock().unwrap();
+        let records = result.patients.len();
+        assert_eq!(reports.len(), records + 1);
+        assert!(reports
+            .windows(2)
+            .all(|pair| pair[0].records <= pair[1].records && pair[0].bytes <= pair[1].bytes));
+        assert_eq!(
+            reports.last(),
+            Some(&Progress {
+                records,
+                total_records: records,
+                bytes: xml.len(),
+                total_bytes: xml.len(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_cancellation_stops_parsing() {
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let reports = Arc::new(Mutex::new(0));
+        let seen = reports.clone();
+
+        let result = NativeParser::new()
+            .single_threaded()
+            .cancellation(token)
+            .progress(move |_| {
+                *seen.lock().unwrap() += 1;
+                cancel.cancel();
+            })
+            .parse_file::<SiteNative>(Path::new("tests/assets/site_native.xml"));
+
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert_eq!(*reports.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_borrowed_matches_owned() {
+        let subject = read_to_string("tests/assets/subject_native.xml").unwrap();
+        let site = read_to_string("tests/assets/site_native.xml").unwrap();
+        let user = read_to_string("tests/assets/user_native.xml").unwrap();
+        let parser = NativeParser::new().history(History::CurrentOnly);
+
+        let borrowed: SubjectNativeRef = parser.parse_borrowed(&subject).unwrap();
+        let owned: SubjectNative = parser.parse_str(&subject).unwrap();
+        assert_eq!(borrowed.into_owned(), owned);
+
+        let borrowed: SiteNativeRef = parser.parse_borrowed(&site).unwrap();
+        let owned: SiteNative = parser.parse_str(&site).unwrap();
+        assert_eq!(borrowed.into_owned(), owned);
+
+        let borrowed: UserNativeRef = parser.parse_borrowed(&user).unwrap();
+        let owned: UserNative = parser.parse_str(&user).unwrap();
+        assert_eq!(borrowed.into_owned(), owned);
+    }
+
+    #[test]
+    fn test_borrowed_copies_only_escaped_text() {
+        let xml = r#"<export_from_vision_EDC>
+  <patient patientId="ABC-001" uniqueId="1" creator="Paul" siteName="Site &amp; Co" siteUniqueId="2">
+    <form name="demographics" formTitle="Demographics" formState="In-Work">
+      <category name="Demographics" type="normal" highestIndex="0">
+        <field name="breed" type="text" dataType="string" errorCode="valid" keepHistory="true">
+          <entry id="1">
+            <value by="Paul" byUniqueId="" role="PM" when="">Labrador</value>
+          </entry>
+          <entry id="2">
+            <value by="Paul" byUniqueId="" role="PM" when="">Cat &lt;3</value>
+          </entry>
+        </field>
+      </category>
+    </form>
+  </patient>
+</export_from_vision_EDC>"#;
+
+        let native = parse_subject_native_borrowed(xml).unwrap();
+        let patient = &native.patients[0];
+        assert!(matches!(patient.patient_id, Cow::Borrowed("ABC-001")));
+        assert!(matches!(&patient.site_name, Cow::Owned(name) if name == "Site & Co"));
+
+        let field = &patient.forms[0].categories[0].fields[0];
+        let values: Vec<_> = field
+            .entries
+            .iter()
+            .map(|entry| &entry.value.as_ref().unwrap().value)
+            .collect();
+        assert!(matches!(values[0], Cow::Borrowed("Labrador")));
+        assert!(matches!(values[1], Cow::Owned(value) if value == "Cat <3"));
+        assert_eq!(field.entries[0].value.as_ref().unwrap().by_unique_id, None);
+        assert!(field.comments.is_empty());
+
+        let owned = native.into_owned();
+        assert_eq!(owned.patients[0].site_name, "Site & Co");
+    }
+
     #[test]
     fn test_forms_parsing_regression() {
         let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -2099,8 +2007,8 @@ mod tests {
         assert_eq!(forms.len(), 2, "Patient should have exactly 2 forms");
 
         let form1 = &forms[0];
-        assert_eq!(form1.name, "test.form.1");
-        assert_eq!(form1.form_title, "Test Form 1");
+        assert_eq!(&*form1.name, "test.form.1");
+        assert_eq!(&*form1.form_title, "Test Form 1");
         assert_eq!(form1.form_index, 1);
         assert_eq!(form1.form_state, "In-Work");
 
@@ -2135,8 +2043,8 @@ mod tests {
         assert_eq!(&*value1.role, "Tester");
 
         let form2 = &forms[1];
-        assert_eq!(form2.name, "test.form.2");
-        assert_eq!(form2.form_title, "Test Form 2");
+        assert_eq!(&*form2.name, "test.form.2");
+        assert_eq!(&*form2.form_title, "Test Form 2");
         assert_eq!(form2.form_index, 2);
         assert_eq!(form2.form_state, "Complete");
 
@@ -2227,6 +2135,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_record_close_tags_in_markup_regression() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<export_from_vision_EDC date="30-May-2024 10:35 -0500" createdBy="Test User" role="Project Manager">
+  <!-- an export of <patient> records, each ending in </patient> -->
+  <patient patientId="TEST-001" uniqueId="1" creator="Test User" siteName="Site > One" siteUniqueId="2">
+    <?audit skipped </patient>?>
+    <form name="notes" formTitle="Notes" formState="In-Work">
+      <category name="Notes" type="normal" highestIndex="0">
+        <field name="note" type="text" dataType="string" errorCode="valid" keepHistory="true">
+          <entry id="1">
+            <value by="Test User" byUniqueId="" role="Tester" when=""><![CDATA[ends with </patient>]]></value>
+          </entry>
+        </field>
+      </category>
+    </form>
+    <!-- </patient> -->
+  </patient>
+  <patient patientId="TEST-002" uniqueId="3" creator="Test User" siteName="Site Two" siteUniqueId="4">
+  </patient>
+</export_from_vision_EDC>"#;
+
+        let native = parse_subject_native_string(xml).unwrap();
+        assert_eq!(native.patients.len(), 2);
+        assert_eq!(native.patients[0].site_name, "Site > One");
+        assert_eq!(native.patients[1].patient_id, "TEST-002");
+
+        let form = &native.patients[0].forms.as_ref().unwrap()[0];
+        let field = &form.categories.as_ref().unwrap()[0]
+            .fields
+            .as_ref()
+            .unwrap()[0];
+        let value = field.entries.as_ref().unwrap()[0].value.as_ref().unwrap();
+        assert_eq!(value.value, "ends with </patient>");
+
+        let borrowed = parse_subject_native_borrowed(xml).unwrap();
+        assert_eq!(borrowed.into_owned(), native);
+    }
+
     #[test]
     fn test_empty_forms_handling() {
         let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -2293,8 +2240,8 @@ mod tests {
 
         for (i, form) in forms.iter().enumerate() {
             let form_num = i + 1;
-            assert_eq!(form.name, format!("test.form.{}", form_num));
-            assert_eq!(form.form_title, format!("Test Form {}", form_num));
+            assert_eq!(*form.name, format!("test.form.{}", form_num));
+            assert_eq!(*form.form_title, format!("Test Form {}", form_num));
             assert_eq!(form.form_index, form_num);
 
             let categories = form
@@ -2435,4 +2382,72 @@ mod tests {
         );
         assert_eq!(reason.value, "Test Reason");
     }
+
+    #[test]
+    fn test_current_only_history_keeps_first_entry() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<export_from_vision_EDC date="30-May-2024 10:35 -0500" createdBy="Test User" role="Project Manager" numberSubjectsProcessed="1">
+  <patient patientId="TEST-001" uniqueId="123456" whenCreated="2023-04-15 12:09:02 -0400" creator="Test User" siteName="Test Site" siteUniqueId="654321" lastLanguage="" numberOfForms="1">
+    <form name="test.form" lastModified="2023-04-15 12:09:15 -0400" whoLastModifiedName="Test User" whoLastModifiedRole="Manager" whenCreated="1681574905839" hasErrors="false" hasWarnings="false" locked="false" user="" dateTimeChanged="" formTitle="Test Form" formIndex="1" formGroup="Test" formState="In-Work">
+      <category name="Test Category" type="normal" highestIndex="0">
+        <field name="test_field" type="text" dataType="string" errorCode="valid" whenCreated="2023-04-15 12:08:26 -0400" keepHistory="true">
+          <entry id="1">
+            <value by="Test User" byUniqueId="123456" role="Manager" when="2023-04-15 12:09:02 -0400" xml:space="preserve">Current</value>
+            <reason by="Test User" byUniqueId="123456" role="Manager" when="2023-04-15 12:09:02 -0400" xml:space="preserve">Corrected</reason>
+          </entry>
+          <entry id="2">
+            <value by="Test User" byUniqueId="123456" role="Manager" when="2023-04-15 12:08:30 -0400" xml:space="preserve">Original</value>
+          </entry>
+        </field>
+        <file name="test_file" type="file" dataType="file" errorCode="valid" whenCreated="2023-04-15 12:08:26 -0400" keepHistory="true">
+          <entry id="1">
+            <value by="Test User" byUniqueId="123456" role="Manager" when="2023-04-15 12:09:02 -0400" xml:space="preserve">new.pdf</value>
+          </entry>
+          <entry id="2">
+            <value by="Test User" byUniqueId="123456" role="Manager" when="2023-04-15 12:08:30 -0400" xml:space="preserve">old.pdf</value>
+          </entry>
+        </file>
+      </category>
+    </form>
+  </patient>
+</export_from_vision_EDC>"#;
+
+        let full = parse_subject_native_string(xml).expect("Should parse successfully");
+        let current: SubjectNative = NativeParser::new()
+            .history(History::CurrentOnly)
+            .parse_str(xml)
+            .expect("Should parse successfully");
+
+        let category = |native: &SubjectNative| {
+            native.patients[0].forms.as_ref().unwrap()[0]
+                .categories
+                .as_ref()
+                .unwrap()[0]
+                .clone()
+        };
+
+        let full_category = category(&full);
+        let full_entries = full_category.fields.as_ref().unwrap()[0]
+            .entries
+            .clone()
+            .expect("Field should have entries");
+        assert_eq!(full_entries.len(), 2);
+
+        let current_category = category(&current);
+        let field_entries = current_category.fields.as_ref().unwrap()[0]
+            .entries
+            .clone()
+            .expect("Field should have entries");
+        assert_eq!(field_entries.len(), 1);
+        assert_eq!(&*field_entries[0].entry_id, "1");
+        assert_eq!(field_entries[0].value.as_ref().unwrap().value, "Current");
+        assert_eq!(field_entries[0].reason.as_ref().unwrap().value, "Corrected");
+
+        let file_entries = current_category.files.as_ref().unwrap()[0]
+            .entries
+            .clone()
+            .expect("File should have entries");
+        assert_eq!(file_entries.len(), 1);
+        assert_eq!(file_entries[0].value.as_ref().unwrap().value, "new.pdf");
+    }
 }
@@ -0,0 +1,984 @@
+//! A borrowed view of a native export.
+//!
+//! Every type here mirrors one of the owned model types, but its text borrows from the XML it was
+//! parsed from wherever that text appears there verbatim. Only values with entity references or
+//! normalised line endings are copied, so parsing this way allocates little beyond the tree itself.
+//! Collections are plain `Vec`s, empty when the element had no children of that kind.
+//!
+//! Use these for read-only work over a document that is already in memory, and call `into_owned`
+//! on whatever has to outlive it.
+
+use std::{borrow::Cow, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use quick_xml::events::BytesStart;
+
+use crate::{
+    errors::Error,
+    native::{
+        common::{
+            Category, Comment, Entry, Export, Field, File, Form, LockState, Query, Reason, State,
+            Value,
+        },
+        deserializers::{
+            checked_datetime, optional_borrowed, optional_datetime, required_borrowed,
+            unescape_attribute, visit_borrowed_attributes, Interner,
+        },
+        site_native::{Site, SiteNative},
+        subject_native::{Patient, SubjectNative},
+        user_native::{User, UserNative},
+    },
+};
+
+/// Convert the children of an element, keeping today's convention that no children is `None`.
+fn shared<T, U>(items: Vec<T>, convert: impl FnMut(T) -> U) -> Option<Arc<Vec<U>>> {
+    if items.is_empty() {
+        None
+    } else {
+        Some(Arc::new(items.into_iter().map(convert).collect()))
+    }
+}
+
+fn owned(value: Option<Cow<'_, str>>) -> Option<String> {
+    value.map(Cow::into_owned)
+}
+
+/// Borrowed counterpart of [`Value`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueRef<'a> {
+    pub by: Cow<'a, str>,
+    pub by_unique_id: Option<Cow<'a, str>>,
+    pub role: Cow<'a, str>,
+    pub when: Option<DateTime<Utc>>,
+    pub value: Cow<'a, str>,
+}
+
+impl<'a> ValueRef<'a> {
+    pub(crate) fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut by = "";
+        let mut by_unique_id = "";
+        let mut role = "";
+        let mut when = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"by" => by = attr,
+            b"byUniqueId" => by_unique_id = attr,
+            b"role" => role = attr,
+            b"when" => when = attr,
+            _ => {}
+        })?;
+
+        Ok(ValueRef {
+            by: unescape_attribute(by),
+            by_unique_id: optional_borrowed(by_unique_id),
+            role: unescape_attribute(role),
+            when: checked_datetime(when)?,
+            value: Cow::Borrowed(""),
+        })
+    }
+
+    /// Copy into an owned [`Value`].
+    pub fn into_owned(self) -> Value {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> Value {
+        Value {
+            by: interner.intern(&self.by),
+            by_unique_id: interner.intern_optional(self.by_unique_id.as_deref()),
+            role: interner.intern(&self.role),
+            when: self.when,
+            value: self.value.into_owned(),
+        }
+    }
+}
+
+/// Borrowed counterpart of [`Reason`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReasonRef<'a> {
+    pub by: Cow<'a, str>,
+    pub by_unique_id: Option<Cow<'a, str>>,
+    pub role: Cow<'a, str>,
+    pub when: Option<DateTime<Utc>>,
+    pub value: Cow<'a, str>,
+}
+
+impl<'a> ReasonRef<'a> {
+    pub(crate) fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let ValueRef {
+            by,
+            by_unique_id,
+            role,
+            when,
+            value,
+        } = ValueRef::from_attributes(xml, e)?;
+
+        Ok(ReasonRef {
+            by,
+            by_unique_id,
+            role,
+            when,
+            value,
+        })
+    }
+
+    /// Copy into an owned [`Reason`].
+    pub fn into_owned(self) -> Reason {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> Reason {
+        Reason {
+            by: interner.intern(&self.by),
+            by_unique_id: interner.intern_optional(self.by_unique_id.as_deref()),
+            role: interner.intern(&self.role),
+            when: self.when,
+            value: self.value.into_owned(),
+        }
+    }
+}
+
+/// Borrowed counterpart of [`Entry`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntryRef<'a> {
+    pub entry_id: Cow<'a, str>,
+    pub reviewed_by: Option<Cow<'a, str>>,
+    pub reviewed_by_unique_id: Option<Cow<'a, str>>,
+    pub reviewed_by_when: Option<DateTime<Utc>>,
+    pub value: Option<ValueRef<'a>>,
+    pub reason: Option<ReasonRef<'a>>,
+}
+
+impl<'a> EntryRef<'a> {
+    pub(crate) fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut id: Option<&str> = None;
+        let mut entry_id: Option<&str> = None;
+        let mut reviewed_by = "";
+        let mut reviewed_by_unique_id = "";
+        let mut reviewed_by_when = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"id" => id = Some(attr),
+            b"entryId" => entry_id = Some(attr),
+            b"reviewedBy" => reviewed_by = attr,
+            b"reviewedByUniqueId" => reviewed_by_unique_id = attr,
+            b"reviewedByWhen" => reviewed_by_when = attr,
+            _ => {}
+        })?;
+
+        Ok(EntryRef {
+            entry_id: unescape_attribute(id.or(entry_id).unwrap_or_default()),
+            reviewed_by: optional_borrowed(reviewed_by),
+            reviewed_by_unique_id: optional_borrowed(reviewed_by_unique_id),
+            reviewed_by_when: optional_datetime(reviewed_by_when),
+            value: None,
+            reason: None,
+        })
+    }
+
+    /// Copy into an owned [`Entry`].
+    pub fn into_owned(self) -> Entry {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> Entry {
+        Entry {
+            entry_id: interner.intern(&self.entry_id),
+            reviewed_by: owned(self.reviewed_by),
+            reviewed_by_unique_id: owned(self.reviewed_by_unique_id),
+            reviewed_by_when: self.reviewed_by_when,
+            value: self.value.map(|value| value.into_owned_with(interner)),
+            reason: self.reason.map(|reason| reason.into_owned_with(interner)),
+        }
+    }
+}
+
+/// Borrowed counterpart of [`Comment`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommentRef<'a> {
+    pub comment_id: Cow<'a, str>,
+    pub reviewed_by: Option<Cow<'a, str>>,
+    pub reviewed_by_unique_id: Option<Cow<'a, str>>,
+    pub reviewed_by_when: Option<DateTime<Utc>>,
+    pub value: Option<ValueRef<'a>>,
+}
+
+impl<'a> CommentRef<'a> {
+    pub(crate) fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut comment_id = "";
+        let mut reviewed_by = "";
+        let mut reviewed_by_unique_id = "";
+        let mut reviewed_by_when = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"id" => comment_id = attr,
+            b"reviewedBy" => reviewed_by = attr,
+            b"reviewedByUniqueId" => reviewed_by_unique_id = attr,
+            b"reviewedByWhen" => reviewed_by_when = attr,
+            _ => {}
+        })?;
+
+        Ok(CommentRef {
+            comment_id: unescape_attribute(comment_id),
+            reviewed_by: optional_borrowed(reviewed_by),
+            reviewed_by_unique_id: optional_borrowed(reviewed_by_unique_id),
+            reviewed_by_when: optional_datetime(reviewed_by_when),
+            value: None,
+        })
+    }
+
+    /// Copy into an owned [`Comment`].
+    pub fn into_owned(self) -> Comment {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> Comment {
+        Comment {
+            comment_id: self.comment_id.into_owned(),
+            reviewed_by: owned(self.reviewed_by),
+            reviewed_by_unique_id: owned(self.reviewed_by_unique_id),
+            reviewed_by_when: self.reviewed_by_when,
+            value: self.value.map(|value| value.into_owned_with(interner)),
+        }
+    }
+}
+
+/// Borrowed counterpart of [`Query`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryRef<'a> {
+    pub query_id: Cow<'a, str>,
+    pub reviewed_by: Option<Cow<'a, str>>,
+    pub reviewed_by_unique_id: Option<Cow<'a, str>>,
+    pub reviewed_by_when: Option<DateTime<Utc>>,
+    pub value: Option<ValueRef<'a>>,
+    pub answer: Option<ValueRef<'a>>,
+}
+
+impl<'a> QueryRef<'a> {
+    pub(crate) fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut id: Option<&str> = None;
+        let mut query_id: Option<&str> = None;
+        let mut reviewed_by = "";
+        let mut reviewed_by_unique_id = "";
+        let mut reviewed_by_when = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"id" => id = Some(attr),
+            b"queryId" => query_id = Some(attr),
+            b"reviewedBy" => reviewed_by = attr,
+            b"reviewedByUniqueId" => reviewed_by_unique_id = attr,
+            b"reviewedByWhen" => reviewed_by_when = attr,
+            _ => {}
+        })?;
+
+        Ok(QueryRef {
+            query_id: unescape_attribute(id.or(query_id).unwrap_or_default()),
+            reviewed_by: optional_borrowed(reviewed_by),
+            reviewed_by_unique_id: optional_borrowed(reviewed_by_unique_id),
+            reviewed_by_when: optional_datetime(reviewed_by_when),
+            value: None,
+            answer: None,
+        })
+    }
+
+    /// Copy into an owned [`Query`].
+    pub fn into_owned(self) -> Query {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> Query {
+        Query {
+            query_id: interner.intern(&self.query_id),
+            reviewed_by: owned(self.reviewed_by),
+            reviewed_by_unique_id: owned(self.reviewed_by_unique_id),
+            reviewed_by_when: self.reviewed_by_when,
+            value: self.value.map(|value| value.into_owned_with(interner)),
+            answer: self.answer.map(|answer| answer.into_owned_with(interner)),
+        }
+    }
+}
+
+/// Borrowed counterpart of [`File`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileRef<'a> {
+    pub name: Cow<'a, str>,
+    pub file_type: Cow<'a, str>,
+    pub data_type: Option<Cow<'a, str>>,
+    pub error_code: Cow<'a, str>,
+    pub when_created: Option<DateTime<Utc>>,
+    pub keep_history: bool,
+    pub file_location: Option<Cow<'a, str>>,
+    pub size: Option<usize>,
+    pub entries: Vec<EntryRef<'a>>,
+    pub comments: Vec<CommentRef<'a>>,
+    pub queries: Vec<QueryRef<'a>>,
+    pub download_history: Vec<CommentRef<'a>>,
+}
+
+impl<'a> FileRef<'a> {
+    pub(crate) fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut name = "";
+        let mut file_type = "";
+        let mut data_type = "";
+        let mut error_code = "";
+        let mut when_created = "";
+        let mut keep_history = "";
+        let mut file_location = "";
+        let mut size = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"name" => name = attr,
+            b"type" => file_type = attr,
+            b"dataType" => data_type = attr,
+            b"errorCode" => error_code = attr,
+            b"whenCreated" => when_created = attr,
+            b"keepHistory" => keep_history = attr,
+            b"fileLocation" => file_location = attr,
+            b"size" => size = attr,
+            _ => {}
+        })?;
+
+        Ok(FileRef {
+            name: unescape_attribute(name),
+            file_type: unescape_attribute(file_type),
+            data_type: optional_borrowed(data_type),
+            error_code: unescape_attribute(error_code),
+            when_created: checked_datetime(when_created)?,
+            keep_history: keep_history == "true",
+            file_location: optional_borrowed(file_location),
+            size: size.parse().ok(),
+            entries: Vec::new(),
+            comments: Vec::new(),
+            queries: Vec::new(),
+            download_history: Vec::new(),
+        })
+    }
+
+    /// Copy into an owned [`File`].
+    pub fn into_owned(self) -> File {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> File {
+        File {
+            name: interner.intern(&self.name),
+            file_type: interner.intern(&self.file_type),
+            data_type: interner.intern_optional(self.data_type.as_deref()),
+            error_code: interner.intern(&self.error_code),
+            when_created: self.when_created,
+            keep_history: self.keep_history,
+            file_location: owned(self.file_location),
+            size: self.size,
+            entries: shared(self.entries, |entry| entry.into_owned_with(interner)),
+            comments: shared(self.comments, |comment| comment.into_owned_with(interner)),
+            queries: shared(self.queries, |query| query.into_owned_with(interner)),
+            download_history: shared(self.download_history, |download| {
+                download.into_owned_with(interner)
+            }),
+        }
+    }
+}
+
+/// Borrowed counterpart of [`Field`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldRef<'a> {
+    pub name: Cow<'a, str>,
+    pub field_type: Cow<'a, str>,
+    pub data_type: Option<Cow<'a, str>>,
+    pub error_code: Cow<'a, str>,
+    pub when_created: Option<DateTime<Utc>>,
+    pub keep_history: bool,
+    pub entries: Vec<EntryRef<'a>>,
+    pub comments: Vec<CommentRef<'a>>,
+    pub queries: Vec<QueryRef<'a>>,
+}
+
+impl<'a> FieldRef<'a> {
+    pub(crate) fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut name = "";
+        let mut field_type = "";
+        let mut data_type = "";
+        let mut error_code = "";
+        let mut when_created = "";
+        let mut keep_history = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"name" => name = attr,
+            b"type" => field_type = attr,
+            b"dataType" => data_type = attr,
+            b"errorCode" => error_code = attr,
+            b"whenCreated" => when_created = attr,
+            b"keepHistory" => keep_history = attr,
+            _ => {}
+        })?;
+
+        Ok(FieldRef {
+            name: unescape_attribute(name),
+            field_type: unescape_attribute(field_type),
+            data_type: optional_borrowed(data_type),
+            error_code: unescape_attribute(error_code),
+            when_created: checked_datetime(when_created)?,
+            keep_history: keep_history == "true",
+            entries: Vec::new(),
+            comments: Vec::new(),
+            queries: Vec::new(),
+        })
+    }
+
+    /// Copy into an owned [`Field`].
+    pub fn into_owned(self) -> Field {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> Field {
+        Field {
+            name: interner.intern(&self.name),
+            field_type: interner.intern(&self.field_type),
+            data_type: interner.intern_optional(self.data_type.as_deref()),
+            error_code: interner.intern(&self.error_code),
+            when_created: self.when_created,
+            keep_history: self.keep_history,
+            entries: shared(self.entries, |entry| entry.into_owned_with(interner)),
+            comments: shared(self.comments, |comment| comment.into_owned_with(interner)),
+            queries: shared(self.queries, |query| query.into_owned_with(interner)),
+        }
+    }
+}
+
+/// Borrowed counterpart of [`Category`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CategoryRef<'a> {
+    pub name: Cow<'a, str>,
+    pub category_type: Cow<'a, str>,
+    pub highest_index: usize,
+    pub obfuscated: bool,
+    pub over_ride_highest_index: bool,
+    pub fields: Vec<FieldRef<'a>>,
+    pub files: Vec<FileRef<'a>>,
+}
+
+impl<'a> CategoryRef<'a> {
+    pub(crate) fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut name = "";
+        let mut category_type = "";
+        let mut highest_index = "";
+        let mut obfuscated = "";
+        let mut over_ride_highest_index = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"name" => name = attr,
+            b"type" => category_type = attr,
+            b"highestIndex" => highest_index = attr,
+            b"obfuscated" => obfuscated = attr,
+            b"overRideHighestIndex" => over_ride_highest_index = attr,
+            _ => {}
+        })?;
+
+        Ok(CategoryRef {
+            name: unescape_attribute(name),
+            category_type: unescape_attribute(category_type),
+            highest_index: highest_index.parse().unwrap_or(0),
+            obfuscated: obfuscated == "true",
+            over_ride_highest_index: over_ride_highest_index == "true",
+            fields: Vec::new(),
+            files: Vec::new(),
+        })
+    }
+
+    /// Copy into an owned [`Category`].
+    pub fn into_owned(self) -> Category {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> Category {
+        Category {
+            name: interner.intern(&self.name),
+            category_type: interner.intern(&self.category_type),
+            highest_index: self.highest_index,
+            obfuscated: self.obfuscated,
+            over_ride_highest_index: self.over_ride_highest_index,
+            fields: shared(self.fields, |field| field.into_owned_with(interner)),
+            files: shared(self.files, |file| file.into_owned_with(interner)),
+        }
+    }
+}
+
+/// Borrowed counterpart of [`State`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateRef<'a> {
+    pub value: Cow<'a, str>,
+    pub signer: Cow<'a, str>,
+    pub signer_unique_id: Cow<'a, str>,
+    pub date_signed: Option<DateTime<Utc>>,
+}
+
+impl<'a> StateRef<'a> {
+    pub(crate) fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut value = "";
+        let mut signer = "";
+        let mut signer_unique_id = "";
+        let mut date_signed = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"value" => value = attr,
+            b"signer" => signer = attr,
+            b"signerUniqueId" => signer_unique_id = attr,
+            b"dateSigned" => date_signed = attr,
+            _ => {}
+        })?;
+
+        Ok(StateRef {
+            value: unescape_attribute(value),
+            signer: unescape_attribute(signer),
+            signer_unique_id: unescape_attribute(signer_unique_id),
+            date_signed: optional_datetime(date_signed),
+        })
+    }
+
+    /// Copy into an owned [`State`].
+    pub fn into_owned(self) -> State {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> State {
+        State {
+            value: interner.intern(&self.value),
+            signer: interner.intern(&self.signer),
+            signer_unique_id: interner.intern(&self.signer_unique_id),
+            date_signed: self.date_signed,
+        }
+    }
+}
+
+/// Borrowed counterpart of [`LockState`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LockStateRef<'a> {
+    pub locked: bool,
+    pub user: Option<Cow<'a, str>>,
+    pub user_unique_id: Option<Cow<'a, str>>,
+    pub date_time_changed: Option<DateTime<Utc>>,
+}
+
+impl<'a> LockStateRef<'a> {
+    pub(crate) fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut locked = "";
+        let mut user = "";
+        let mut user_unique_id = "";
+        let mut date_time_changed = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"locked" => locked = attr,
+            b"user" => user = attr,
+            b"userUniqueId" => user_unique_id = attr,
+            b"dateTimeChanged" => date_time_changed = attr,
+            _ => {}
+        })?;
+
+        Ok(LockStateRef {
+            locked: locked == "true",
+            user: optional_borrowed(user),
+            user_unique_id: optional_borrowed(user_unique_id),
+            date_time_changed: optional_datetime(date_time_changed),
+        })
+    }
+
+    /// Copy into an owned [`LockState`].
+    pub fn into_owned(self) -> LockState {
+        LockState {
+            locked: self.locked,
+            user: owned(self.user),
+            user_unique_id: owned(self.user_unique_id),
+            date_time_changed: self.date_time_changed,
+        }
+    }
+}
+
+/// Borrowed counterpart of [`Form`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormRef<'a> {
+    pub name: Cow<'a, str>,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub who_last_modified_name: Option<Cow<'a, str>>,
+    pub who_last_modified_role: Option<Cow<'a, str>>,
+    pub when_created: usize,
+    pub has_errors: bool,
+    pub has_warnings: bool,
+    pub locked: bool,
+    pub user: Option<Cow<'a, str>>,
+    pub date_time_changed: Option<DateTime<Utc>>,
+    pub form_title: Cow<'a, str>,
+    pub form_index: usize,
+    pub form_group: Option<Cow<'a, str>>,
+    pub form_state: Cow<'a, str>,
+    pub states: Vec<StateRef<'a>>,
+    pub lock_states: Vec<LockStateRef<'a>>,
+    pub categories: Vec<CategoryRef<'a>>,
+}
+
+impl<'a> FormRef<'a> {
+    pub(crate) fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut name = "";
+        let mut last_modified = "";
+        let mut who_last_modified_name = "";
+        let mut who_last_modified_role = "";
+        let mut when_created = "";
+        let mut has_errors = "";
+        let mut has_warnings = "";
+        let mut locked = "";
+        let mut user = "";
+        let mut date_time_changed = "";
+        let mut form_title = "";
+        let mut form_index = "";
+        let mut form_group = "";
+        let mut form_state = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"name" => name = attr,
+            b"lastModified" => last_modified = attr,
+            b"whoLastModifiedName" => who_last_modified_name = attr,
+            b"whoLastModifiedRole" => who_last_modified_role = attr,
+            b"whenCreated" => when_created = attr,
+            b"hasErrors" => has_errors = attr,
+            b"hasWarnings" => has_warnings = attr,
+            b"locked" => locked = attr,
+            b"user" => user = attr,
+            b"dateTimeChanged" => date_time_changed = attr,
+            b"formTitle" => form_title = attr,
+            b"formIndex" => form_index = attr,
+            b"formGroup" => form_group = attr,
+            b"formState" => form_state = attr,
+            _ => {}
+        })?;
+
+        Ok(FormRef {
+            name: unescape_attribute(name),
+            last_modified: optional_datetime(last_modified),
+            who_last_modified_name: optional_borrowed(who_last_modified_name),
+            who_last_modified_role: optional_borrowed(who_last_modified_role),
+            when_created: when_created.parse().unwrap_or(0),
+            has_errors: has_errors == "true",
+            has_warnings: has_warnings == "true",
+            locked: locked == "true",
+            user: optional_borrowed(user),
+            date_time_changed: optional_datetime(date_time_changed),
+            form_title: unescape_attribute(form_title),
+            form_index: form_index.parse().unwrap_or(0),
+            form_group: optional_borrowed(form_group),
+            form_state: unescape_attribute(form_state),
+            states: Vec::new(),
+            lock_states: Vec::new(),
+            categories: Vec::new(),
+        })
+    }
+
+    /// Copy into an owned [`Form`].
+    pub fn into_owned(self) -> Form {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> Form {
+        Form {
+            name: interner.intern(&self.name),
+            last_modified: self.last_modified,
+            who_last_modified_name: owned(self.who_last_modified_name),
+            who_last_modified_role: owned(self.who_last_modified_role),
+            when_created: self.when_created,
+            has_errors: self.has_errors,
+            has_warnings: self.has_warnings,
+            locked: self.locked,
+            user: owned(self.user),
+            date_time_changed: self.date_time_changed,
+            form_title: interner.intern(&self.form_title),
+            form_index: self.form_index,
+            form_group: interner.intern_optional(self.form_group.as_deref()),
+            form_state: self.form_state.into_owned(),
+            states: shared(self.states, |state| state.into_owned_with(interner)),
+            lock_states: shared(self.lock_states, LockStateRef::into_owned),
+            categories: shared(self.categories, |category| {
+                category.into_owned_with(interner)
+            }),
+        }
+    }
+}
+
+/// A top-level record of a native export: a patient, site or user and the forms beneath it.
+///
+/// The record parser is shared by all three exports, which differ only in the root element of a
+/// record and its attributes.
+pub(crate) trait RecordRef<'a>: Sized {
+    /// The name of the element that holds one record.
+    const TAG: &'static str;
+
+    fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error>;
+
+    fn set_forms(&mut self, forms: Vec<FormRef<'a>>);
+}
+
+/// Borrowed counterpart of [`Patient`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatientRef<'a> {
+    pub patient_id: Cow<'a, str>,
+    pub unique_id: Cow<'a, str>,
+    pub when_created: Option<DateTime<Utc>>,
+    pub password_change_date: Option<DateTime<Utc>>,
+    pub creator: Cow<'a, str>,
+    pub site_name: Cow<'a, str>,
+    pub site_unique_id: Cow<'a, str>,
+    pub last_language: Option<Cow<'a, str>>,
+    pub number_of_forms: usize,
+    pub forms: Vec<FormRef<'a>>,
+}
+
+impl<'a> PatientRef<'a> {
+    /// Copy into an owned [`Patient`].
+    pub fn into_owned(self) -> Patient {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> Patient {
+        Patient {
+            patient_id: self.patient_id.into_owned(),
+            unique_id: self.unique_id.into_owned(),
+            when_created: self.when_created,
+            password_change_date: self.password_change_date,
+            creator: self.creator.into_owned(),
+            site_name: self.site_name.into_owned(),
+            site_unique_id: self.site_unique_id.into_owned(),
+            last_language: owned(self.last_language),
+            number_of_forms: self.number_of_forms,
+            forms: shared(self.forms, |form| form.into_owned_with(interner)),
+        }
+    }
+}
+
+impl<'a> RecordRef<'a> for PatientRef<'a> {
+    const TAG: &'static str = "patient";
+
+    fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut patient_id: Option<&str> = None;
+        let mut unique_id: Option<&str> = None;
+        let mut when_created = "";
+        let mut password_change_date = "";
+        let mut creator: Option<&str> = None;
+        let mut site_name: Option<&str> = None;
+        let mut site_unique_id: Option<&str> = None;
+        let mut last_language = "";
+        let mut number_of_forms = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"patientId" => patient_id = Some(attr),
+            b"uniqueId" => unique_id = Some(attr),
+            b"whenCreated" => when_created = attr,
+            b"passwordChangeDate" => password_change_date = attr,
+            b"creator" => creator = Some(attr),
+            b"siteName" => site_name = Some(attr),
+            b"siteUniqueId" => site_unique_id = Some(attr),
+            b"lastLanguage" => last_language = attr,
+            b"numberOfForms" => number_of_forms = attr,
+            _ => {}
+        })?;
+
+        Ok(PatientRef {
+            patient_id: required_borrowed(patient_id, "patientId")?,
+            unique_id: required_borrowed(unique_id, "uniqueId")?,
+            when_created: checked_datetime(when_created)?,
+            password_change_date: checked_datetime(password_change_date)?,
+            creator: required_borrowed(creator, "creator")?,
+            site_name: required_borrowed(site_name, "siteName")?,
+            site_unique_id: required_borrowed(site_unique_id, "siteUniqueId")?,
+            last_language: optional_borrowed(last_language),
+            number_of_forms: number_of_forms.parse().unwrap_or(0),
+            forms: Vec::new(),
+        })
+    }
+
+    fn set_forms(&mut self, forms: Vec<FormRef<'a>>) {
+        self.forms = forms;
+    }
+}
+
+/// Borrowed counterpart of [`Site`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SiteRef<'a> {
+    pub name: Cow<'a, str>,
+    pub unique_id: Cow<'a, str>,
+    pub number_of_patients: usize,
+    pub count_of_randomized_patients: usize,
+    pub when_created: Option<DateTime<Utc>>,
+    pub creator: Cow<'a, str>,
+    pub number_of_forms: usize,
+    pub forms: Vec<FormRef<'a>>,
+}
+
+impl<'a> SiteRef<'a> {
+    /// Copy into an owned [`Site`].
+    pub fn into_owned(self) -> Site {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> Site {
+        Site {
+            name: self.name.into_owned(),
+            unique_id: self.unique_id.into_owned(),
+            number_of_patients: self.number_of_patients,
+            count_of_randomized_patients: self.count_of_randomized_patients,
+            when_created: self.when_created,
+            creator: self.creator.into_owned(),
+            number_of_forms: self.number_of_forms,
+            forms: shared(self.forms, |form| form.into_owned_with(interner)),
+        }
+    }
+}
+
+impl<'a> RecordRef<'a> for SiteRef<'a> {
+    const TAG: &'static str = "site";
+
+    fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut name: Option<&str> = None;
+        let mut unique_id: Option<&str> = None;
+        let mut number_of_patients = "";
+        let mut count_of_randomized_patients = "";
+        let mut when_created = "";
+        let mut creator: Option<&str> = None;
+        let mut number_of_forms = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"name" => name = Some(attr),
+            b"uniqueId" => unique_id = Some(attr),
+            b"numberOfPatients" => number_of_patients = attr,
+            b"countOfRandomizedPatients" => count_of_randomized_patients = attr,
+            b"whenCreated" => when_created = attr,
+            b"creator" => creator = Some(attr),
+            b"numberOfForms" => number_of_forms = attr,
+            _ => {}
+        })?;
+
+        Ok(SiteRef {
+            name: required_borrowed(name, "name")?,
+            unique_id: required_borrowed(unique_id, "uniqueId")?,
+            number_of_patients: number_of_patients.parse().unwrap_or(0),
+            count_of_randomized_patients: count_of_randomized_patients.parse().unwrap_or(0),
+            when_created: checked_datetime(when_created)?,
+            creator: required_borrowed(creator, "creator")?,
+            number_of_forms: number_of_forms.parse().unwrap_or(0),
+            forms: Vec::new(),
+        })
+    }
+
+    fn set_forms(&mut self, forms: Vec<FormRef<'a>>) {
+        self.forms = forms;
+    }
+}
+
+/// Borrowed counterpart of [`User`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserRef<'a> {
+    pub unique_id: Cow<'a, str>,
+    pub last_language: Option<Cow<'a, str>>,
+    pub creator: Cow<'a, str>,
+    pub number_of_forms: usize,
+    pub forms: Vec<FormRef<'a>>,
+}
+
+impl<'a> UserRef<'a> {
+    /// Copy into an owned [`User`].
+    pub fn into_owned(self) -> User {
+        self.into_owned_with(&mut Interner::default())
+    }
+
+    pub(crate) fn into_owned_with(self, interner: &mut Interner) -> User {
+        User {
+            unique_id: self.unique_id.into_owned(),
+            last_language: owned(self.last_language),
+            creator: self.creator.into_owned(),
+            number_of_forms: self.number_of_forms,
+            forms: shared(self.forms, |form| form.into_owned_with(interner)),
+        }
+    }
+}
+
+impl<'a> RecordRef<'a> for UserRef<'a> {
+    const TAG: &'static str = "user";
+
+    fn from_attributes(xml: &'a str, e: &BytesStart<'_>) -> Result<Self, Error> {
+        let mut unique_id: Option<&str> = None;
+        let mut last_language = "";
+        let mut creator: Option<&str> = None;
+        let mut number_of_forms = "";
+
+        visit_borrowed_attributes(xml, e, |key, attr| match key {
+            b"uniqueId" => unique_id = Some(attr),
+            b"lastLanguage" => last_language = attr,
+            b"creator" => creator = Some(attr),
+            b"numberOfForms" => number_of_forms = attr,
+            _ => {}
+        })?;
+
+        Ok(UserRef {
+            unique_id: required_borrowed(unique_id, "uniqueId")?,
+            last_language: optional_borrowed(last_language),
+            creator: required_borrowed(creator, "creator")?,
+            number_of_forms: number_of_forms.parse().unwrap_or(0),
+            forms: Vec::new(),
+        })
+    }
+
+    fn set_forms(&mut self, forms: Vec<FormRef<'a>>) {
+        self.forms = forms;
+    }
+}
+
+/// Borrowed counterpart of [`SubjectNative`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubjectNativeRef<'a> {
+    pub export: Option<Export>,
+    pub patients: Vec<PatientRef<'a>>,
+}
+
+impl SubjectNativeRef<'_> {
+    /// Copy into an owned [`SubjectNative`].
+    pub fn into_owned(self) -> SubjectNative {
+        SubjectNative {
+            export: self.export,
+            patients: self
+                .patients
+                .into_iter()
+                .map(PatientRef::into_owned)
+                .collect(),
+        }
+    }
+}
+
+/// Borrowed counterpart of [`SiteNative`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SiteNativeRef<'a> {
+    pub export: Option<Export>,
+    pub sites: Vec<SiteRef<'a>>,
+}
+
+impl SiteNativeRef<'_> {
+    /// Copy into an owned [`SiteNative`].
+    pub fn into_owned(self) -> SiteNative {
+        SiteNative {
+            export: self.export,
+            sites: self.sites.into_iter().map(SiteRef::into_owned).collect(),
+        }
+    }
+}
+
+/// Borrowed counterpart of [`UserNative`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserNativeRef<'a> {
+    pub export: Option<Export>,
+    pub users: Vec<UserRef<'a>>,
+}
+
+impl UserNativeRef<'_> {
+    /// Copy into an owned [`UserNative`].
+    pub fn into_owned(self) -> UserNative {
+        UserNative {
+            export: self.export,
+            users: self.users.into_iter().map(UserRef::into_owned).collect(),
+        }
+    }
+}
@@ -1,48 +1,16 @@
-#[cfg(not(feature = "python"))]
 /// The attributes on the `export_from_vision_EDC` root element: who produced the export, when, and
 /// how much of the study it covers.
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Export {
-    #[serde(rename = "date")]
-    #[serde(alias = "@date")]
-    #[serde(alias = "date")]
-    #[serde(
-        default,
-        deserialize_with = "deserialize_empty_string_as_none_datetime"
-    )]
-    pub date: Option<DateTime<Utc>>,
-
-    #[serde(rename = "createdBy")]
-    #[serde(alias = "@createdBy")]
-    #[serde(alias = "createdBy")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub created_by: Option<String>,
-
-    #[serde(rename = "role")]
-    #[serde(alias = "@role")]
-    #[serde(alias = "role")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub role: Option<String>,
-
-    #[serde(rename = "numberSubjectsProcessed")]
-    #[serde(alias = "@numberSubjectsProcessed")]
-    #[serde(alias = "numberSubjectsProcessed")]
-    #[serde(default)]
-    pub number_subjects_processed: Option<usize>,
-
-    /// Present when the export was split, e.g. `"1 of 6"`.
-    #[serde(rename = "pageNumber")]
-    #[serde(alias = "@pageNumber")]
-    #[serde(alias = "pageNumber")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub page_number: Option<String>,
-}
-
-#[cfg(feature = "python")]
-/// The attributes on the `export_from_vision_EDC` root element: who produced the export, when, and
-/// how much of the study it covers.
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[pyclass(skip_from_py_object)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct Export {
     #[serde(rename = "date")]
     #[serde(alias = "@date")]
@@ -56,6 +24,7 @@ pub struct Export {
     #[serde(rename = "createdBy")]
     #[serde(alias = "@createdBy")]
     #[serde(alias = "createdBy")]
+    #[serde(alias = "created_by")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub created_by: Option<String>,
 
@@ -68,6 +37,7 @@ pub struct Export {
     #[serde(rename = "numberSubjectsProcessed")]
     #[serde(alias = "@numberSubjectsProcessed")]
     #[serde(alias = "numberSubjectsProcessed")]
+    #[serde(alias = "number_subjects_processed")]
     #[serde(default)]
     pub number_subjects_processed: Option<usize>,
 
@@ -75,6 +45,7 @@ pub struct Export {
     #[serde(rename = "pageNumber")]
     #[serde(alias = "@pageNumber")]
     #[serde(alias = "pageNumber")]
+    #[serde(alias = "page_number")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub page_number: Option<String>,
 }
@@ -86,23 +57,41 @@ use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "python")]
 use pyo3::{
+    exceptions::PyKeyError,
     prelude::*,
-    types::{PyDateTime, PyDict},
+    types::{PyBytes, PyDateTime, PyDict},
 };
 
 use quick_xml::events::BytesStart;
 
+#[cfg(feature = "python")]
+use crate::native::{
+    list_view::{ListView, Source},
+    python::{from_dict, reduce, to_dict, unpickle, Repr, ToDict},
+};
+
 use crate::native::deserializers::{
-    attribute_string, checked_datetime, deserialize_empty_string_as_none,
-    deserialize_empty_string_as_none_arc, deserialize_empty_string_as_none_datetime,
-    optional_datetime, optional_string, visit_attributes, Interner,
+    deserialize_empty_string_as_none, deserialize_empty_string_as_none_arc,
+    deserialize_empty_string_as_none_datetime, optional_datetime, optional_string,
+    visit_attributes,
 };
 
 #[cfg(feature = "python")]
-use crate::native::deserializers::{to_py_datetime, to_py_datetime_option};
+use crate::native::deserializers::{
+    from_py_datetime_option, to_py_datetime, to_py_datetime_option,
+};
 
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct Value {
     #[serde(rename = "by")]
     #[serde(alias = "@by")]
@@ -112,6 +101,7 @@ pub struct Value {
     #[serde(rename = "byUniqueId")]
     #[serde(alias = "@byUniqueId")]
     #[serde(alias = "byUniqueId")]
+    #[serde(alias = "by_unique_id")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none_arc")]
     pub by_unique_id: Option<Arc<str>>,
     #[serde(rename = "role")]
@@ -132,39 +122,35 @@ pub struct Value {
 }
 
 #[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[pyclass(skip_from_py_object)]
-pub struct Value {
-    #[serde(rename = "by")]
-    #[serde(alias = "@by")]
-    #[serde(alias = "by")]
-    pub by: Arc<str>,
-
-    #[serde(rename = "byUniqueId")]
-    #[serde(alias = "@byUniqueId")]
-    #[serde(alias = "byUniqueId")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none_arc")]
-    pub by_unique_id: Option<Arc<str>>,
-    #[serde(rename = "role")]
-    #[serde(alias = "@role")]
-    #[serde(alias = "role")]
-    pub role: Arc<str>,
-    #[serde(rename = "when")]
-    #[serde(alias = "@when")]
-    #[serde(alias = "when")]
-    pub when: Option<DateTime<Utc>>,
-
-    #[serde(rename = "value")]
-    #[serde(alias = "$text")]
-    #[serde(alias = "#text")]
-    #[serde(alias = "value")]
-    #[serde(default)]
-    pub value: String,
-}
+to_dict!(Value {
+    by,
+    by_unique_id,
+    role,
+    when,
+    value
+});
 
 #[cfg(feature = "python")]
 #[pymethods]
 impl Value {
+    #[new]
+    #[pyo3(signature = (value, by, role, *, by_unique_id=None, when=None))]
+    fn new(
+        value: String,
+        by: &str,
+        role: &str,
+        by_unique_id: Option<&str>,
+        when: Option<&Bound<'_, PyDateTime>>,
+    ) -> PyResult<Self> {
+        Ok(Value {
+            by: Arc::from(by),
+            by_unique_id: by_unique_id.map(Arc::from),
+            role: Arc::from(role),
+            when: from_py_datetime_option(when)?,
+            value,
+        })
+    }
+
     #[getter]
     fn by(&self) -> PyResult<String> {
         Ok(self.by.to_string())
@@ -190,52 +176,48 @@ impl Value {
         Ok(self.value.clone())
     }
 
-    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("by", &*self.by)?;
-        dict.set_item("by_unique_id", self.by_unique_id.as_deref())?;
-        dict.set_item("role", &*self.role)?;
-        dict.set_item("when", to_py_datetime_option(py, &self.when)?)?;
-        dict.set_item("value", &self.value)?;
-
-        Ok(dict)
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "Value")
+            .field("value", &*self.value)?
+            .field("by", &*self.by)?
+            .field("role", &*self.role)?
+            .field("when", to_py_datetime_option(py, &self.when)?)?
+            .finish())
     }
-}
 
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Reason {
-    #[serde(rename = "by")]
-    #[serde(alias = "@by")]
-    #[serde(alias = "by")]
-    pub by: Arc<str>,
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
 
-    #[serde(rename = "byUniqueId")]
-    #[serde(alias = "@byUniqueId")]
-    #[serde(alias = "byUniqueId")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none_arc")]
-    pub by_unique_id: Option<Arc<str>>,
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
 
-    #[serde(rename = "role")]
-    #[serde(alias = "@role")]
-    #[serde(alias = "role")]
-    pub role: Arc<str>,
-    #[serde(rename = "when")]
-    #[serde(alias = "@when")]
-    #[serde(alias = "when")]
-    pub when: Option<DateTime<Utc>>,
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
 
-    #[serde(rename = "value")]
-    #[serde(alias = "$text")]
-    #[serde(alias = "#text")]
-    #[serde(alias = "value")]
-    #[serde(default)]
-    pub value: String,
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ToDict::to_dict(self, py)
+    }
 }
 
-#[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[pyclass(skip_from_py_object)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct Reason {
     #[serde(rename = "by")]
     #[serde(alias = "@by")]
@@ -245,6 +227,7 @@ pub struct Reason {
     #[serde(rename = "byUniqueId")]
     #[serde(alias = "@byUniqueId")]
     #[serde(alias = "byUniqueId")]
+    #[serde(alias = "by_unique_id")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none_arc")]
     pub by_unique_id: Option<Arc<str>>,
 
@@ -265,9 +248,36 @@ pub struct Reason {
     pub value: String,
 }
 
+#[cfg(feature = "python")]
+to_dict!(Reason {
+    by,
+    by_unique_id,
+    role,
+    when,
+    value
+});
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl Reason {
+    #[new]
+    #[pyo3(signature = (value, by, role, *, by_unique_id=None, when=None))]
+    fn new(
+        value: String,
+        by: &str,
+        role: &str,
+        by_unique_id: Option<&str>,
+        when: Option<&Bound<'_, PyDateTime>>,
+    ) -> PyResult<Self> {
+        Ok(Reason {
+            by: Arc::from(by),
+            by_unique_id: by_unique_id.map(Arc::from),
+            role: Arc::from(role),
+            when: from_py_datetime_option(when)?,
+            value,
+        })
+    }
+
     #[getter]
     fn by(&self) -> PyResult<String> {
         Ok(self.by.to_string())
@@ -293,75 +303,73 @@ impl Reason {
         Ok(self.value.clone())
     }
 
-    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("by", &*self.by)?;
-        dict.set_item("by_unique_id", self.by_unique_id.as_deref())?;
-        dict.set_item("role", &*self.role)?;
-        dict.set_item("when", to_py_datetime_option(py, &self.when)?)?;
-        dict.set_item("value", &self.value)?;
-
-        Ok(dict)
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "Reason")
+            .field("value", &*self.value)?
+            .field("by", &*self.by)?
+            .field("role", &*self.role)?
+            .field("when", to_py_datetime_option(py, &self.when)?)?
+            .finish())
     }
-}
-
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Entry {
-    #[serde(rename = "entryId")]
-    #[serde(alias = "@id")]
-    #[serde(alias = "entryId")]
-    pub entry_id: Arc<str>,
 
-    #[serde(rename = "reviewedBy")]
-    #[serde(alias = "@reviewedBy")]
-    #[serde(alias = "reviewedBy")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub reviewed_by: Option<String>,
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
 
-    #[serde(rename = "reviewedByUniqueId")]
-    #[serde(alias = "@reviewedByUniqueId")]
-    #[serde(alias = "reviewedByUniqueId")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub reviewed_by_unique_id: Option<String>,
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
 
-    #[serde(rename = "reviewedByWhen")]
-    #[serde(alias = "@reviewedByWhen")]
-    #[serde(alias = "reviewedByWhen")]
-    #[serde(
-        default,
-        deserialize_with = "deserialize_empty_string_as_none_datetime"
-    )]
-    pub reviewed_by_when: Option<DateTime<Utc>>,
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
 
-    pub value: Option<Value>,
-    pub reason: Option<Reason>,
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ToDict::to_dict(self, py)
+    }
 }
 
-#[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[pyclass(skip_from_py_object)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct Entry {
     #[serde(rename = "entryId")]
     #[serde(alias = "@id")]
     #[serde(alias = "entryId")]
+    #[serde(alias = "entry_id")]
     pub entry_id: Arc<str>,
 
     #[serde(rename = "reviewedBy")]
     #[serde(alias = "@reviewedBy")]
     #[serde(alias = "reviewedBy")]
+    #[serde(alias = "reviewed_by")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub reviewed_by: Option<String>,
 
     #[serde(rename = "reviewedByUniqueId")]
     #[serde(alias = "@reviewedByUniqueId")]
     #[serde(alias = "reviewedByUniqueId")]
+    #[serde(alias = "reviewed_by_unique_id")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub reviewed_by_unique_id: Option<String>,
 
     #[serde(rename = "reviewedByWhen")]
     #[serde(alias = "@reviewedByWhen")]
     #[serde(alias = "reviewedByWhen")]
+    #[serde(alias = "reviewed_by_when")]
     #[serde(
         default,
         deserialize_with = "deserialize_empty_string_as_none_datetime"
@@ -372,9 +380,47 @@ pub struct Entry {
     pub reason: Option<Reason>,
 }
 
+#[cfg(feature = "python")]
+to_dict!(Entry {
+    entry_id,
+    reviewed_by,
+    reviewed_by_unique_id,
+    reviewed_by_when,
+    value,
+    reason
+});
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl Entry {
+    #[new]
+    #[pyo3(signature = (
+        entry_id,
+        value=None,
+        reason=None,
+        *,
+        reviewed_by=None,
+        reviewed_by_unique_id=None,
+        reviewed_by_when=None
+    ))]
+    fn new(
+        entry_id: &str,
+        value: Option<&Bound<'_, Value>>,
+        reason: Option<&Bound<'_, Reason>>,
+        reviewed_by: Option<String>,
+        reviewed_by_unique_id: Option<String>,
+        reviewed_by_when: Option<&Bound<'_, PyDateTime>>,
+    ) -> PyResult<Self> {
+        Ok(Entry {
+            entry_id: Arc::from(entry_id),
+            reviewed_by,
+            reviewed_by_unique_id,
+            reviewed_by_when: from_py_datetime_option(reviewed_by_when)?,
+            value: value.map(|value| value.get().clone()),
+            reason: reason.map(|reason| reason.get().clone()),
+        })
+    }
+
     #[getter]
     fn entry_id(&self) -> PyResult<String> {
         Ok(self.entry_id.to_string())
@@ -405,86 +451,72 @@ impl Entry {
         Ok(self.reason.clone())
     }
 
-    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("entry_id", &*self.entry_id)?;
-        dict.set_item("reviewed_by", &self.reviewed_by)?;
-        dict.set_item("reviewed_by_unique_id", &self.reviewed_by_unique_id)?;
-        dict.set_item(
-            "reviewed_by_when",
-            to_py_datetime_option(py, &self.reviewed_by_when)?,
-        )?;
-        if let Some(value) = &self.value {
-            dict.set_item("value", value.to_dict(py)?)?;
-        } else {
-            dict.set_item("value", py.None())?;
-        }
-        if let Some(reason) = &self.reason {
-            dict.set_item("reason", reason.to_dict(py)?)?;
-        } else {
-            dict.set_item("reason", py.None())?;
-        }
-
-        Ok(dict)
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "Entry")
+            .field("entry_id", &*self.entry_id)?
+            .field("value", self.value.clone())?
+            .field("reason", self.reason.clone())?
+            .finish())
     }
-}
-
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Comment {
-    #[serde(rename = "commentId")]
-    #[serde(alias = "@id")]
-    #[serde(alias = "commentId")]
-    pub comment_id: String,
 
-    #[serde(rename = "reviewedBy")]
-    #[serde(alias = "@reviewedBy")]
-    #[serde(alias = "reviewedBy")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub reviewed_by: Option<String>,
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
 
-    #[serde(rename = "reviewedByUniqueId")]
-    #[serde(alias = "@reviewedByUniqueId")]
-    #[serde(alias = "reviewedByUniqueId")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub reviewed_by_unique_id: Option<String>,
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
 
-    #[serde(rename = "reviewedByWhen")]
-    #[serde(alias = "@reviewedByWhen")]
-    #[serde(alias = "reviewedByWhen")]
-    #[serde(
-        default,
-        deserialize_with = "deserialize_empty_string_as_none_datetime"
-    )]
-    pub reviewed_by_when: Option<DateTime<Utc>>,
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
 
-    pub value: Option<Value>,
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ToDict::to_dict(self, py)
+    }
 }
 
-#[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[pyclass(skip_from_py_object)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct Comment {
     #[serde(rename = "commentId")]
     #[serde(alias = "@id")]
     #[serde(alias = "commentId")]
+    #[serde(alias = "comment_id")]
     pub comment_id: String,
 
     #[serde(rename = "reviewedBy")]
     #[serde(alias = "@reviewedBy")]
     #[serde(alias = "reviewedBy")]
+    #[serde(alias = "reviewed_by")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub reviewed_by: Option<String>,
 
     #[serde(rename = "reviewedByUniqueId")]
     #[serde(alias = "@reviewedByUniqueId")]
     #[serde(alias = "reviewedByUniqueId")]
+    #[serde(alias = "reviewed_by_unique_id")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub reviewed_by_unique_id: Option<String>,
 
     #[serde(rename = "reviewedByWhen")]
     #[serde(alias = "@reviewedByWhen")]
     #[serde(alias = "reviewedByWhen")]
+    #[serde(alias = "reviewed_by_when")]
     #[serde(
         default,
         deserialize_with = "deserialize_empty_string_as_none_datetime"
@@ -494,9 +526,47 @@ pub struct Comment {
     pub value: Option<Value>,
 }
 
+#[cfg(feature = "python")]
+to_dict!(Query {
+    query_id,
+    reviewed_by,
+    reviewed_by_unique_id,
+    reviewed_by_when,
+    value,
+    answer
+});
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl Query {
+    #[new]
+    #[pyo3(signature = (
+        query_id,
+        value=None,
+        answer=None,
+        *,
+        reviewed_by=None,
+        reviewed_by_unique_id=None,
+        reviewed_by_when=None
+    ))]
+    fn new(
+        query_id: &str,
+        value: Option<&Bound<'_, Value>>,
+        answer: Option<&Bound<'_, Value>>,
+        reviewed_by: Option<String>,
+        reviewed_by_unique_id: Option<String>,
+        reviewed_by_when: Option<&Bound<'_, PyDateTime>>,
+    ) -> PyResult<Self> {
+        Ok(Query {
+            query_id: Arc::from(query_id),
+            reviewed_by,
+            reviewed_by_unique_id,
+            reviewed_by_when: from_py_datetime_option(reviewed_by_when)?,
+            value: value.map(|value| value.get().clone()),
+            answer: answer.map(|answer| answer.get().clone()),
+        })
+    }
+
     #[getter]
     fn query_id(&self) -> PyResult<String> {
         Ok(self.query_id.to_string())
@@ -527,30 +597,52 @@ impl Query {
         Ok(self.answer.clone())
     }
 
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "Query")
+            .field("query_id", &*self.query_id)?
+            .field("value", self.value.clone())?
+            .field("answer", self.answer.clone())?
+            .finish())
+    }
+
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
+
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
+
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
+
     pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("query_id", &*self.query_id)?;
-        dict.set_item("reviewed_by", &self.reviewed_by)?;
-        dict.set_item("reviewed_by_unique_id", &self.reviewed_by_unique_id)?;
-        dict.set_item(
-            "reviewed_by_when",
-            to_py_datetime_option(py, &self.reviewed_by_when)?,
-        )?;
-
-        match &self.value {
-            Some(value) => dict.set_item("value", value.to_dict(py)?)?,
-            None => dict.set_item("value", py.None())?,
-        }
-
-        match &self.answer {
-            Some(answer) => dict.set_item("answer", answer.to_dict(py)?)?,
-            None => dict.set_item("answer", py.None())?,
-        }
-
-        Ok(dict)
+        ToDict::to_dict(self, py)
     }
 }
 
+#[cfg(feature = "python")]
+to_dict!(File {
+    name,
+    file_type,
+    data_type,
+    error_code,
+    when_created,
+    keep_history,
+    file_location,
+    size,
+    entries,
+    comments,
+    queries,
+    download_history
+});
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl File {
@@ -595,93 +687,104 @@ impl File {
     }
 
     #[getter]
-    fn entries(&self) -> PyResult<Option<Vec<Entry>>> {
-        Ok(self.entries.as_deref().cloned())
+    fn entries(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .entries
+            .clone()
+            .map(|items| ListView::new(py, Source::Entries(items))))
     }
 
     #[getter]
-    fn comments(&self) -> PyResult<Option<Vec<Comment>>> {
-        Ok(self.comments.as_deref().cloned())
+    fn comments(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .comments
+            .clone()
+            .map(|items| ListView::new(py, Source::Comments(items))))
     }
 
     #[getter]
-    fn queries(&self) -> PyResult<Option<Vec<Query>>> {
-        Ok(self.queries.as_deref().cloned())
+    fn queries(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .queries
+            .clone()
+            .map(|items| ListView::new(py, Source::Queries(items))))
     }
 
     #[getter]
-    fn download_history(&self) -> PyResult<Option<Vec<Comment>>> {
-        Ok(self.download_history.as_deref().cloned())
+    fn download_history(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .download_history
+            .clone()
+            .map(|items| ListView::new(py, Source::Comments(items))))
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "File")
+            .field("name", &*self.name)?
+            .field("file_location", self.file_location.as_deref())?
+            .list("entries", self.entries.as_ref().map(|items| items.len()))
+            .finish())
+    }
+
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
+
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
+
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
     }
 
     pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("name", &*self.name)?;
-        dict.set_item("file_type", &*self.file_type)?;
-        dict.set_item("data_type", self.data_type.as_deref())?;
-        dict.set_item("error_code", &*self.error_code)?;
-        dict.set_item(
-            "when_created",
-            to_py_datetime_option(py, &self.when_created)?,
-        )?;
-        dict.set_item("keep_history", self.keep_history)?;
-        dict.set_item("file_location", &self.file_location)?;
-        dict.set_item("size", self.size)?;
-
-        for (key, items) in [(
-            "entries",
-            self.entries.as_deref().map(|v| {
-                v.iter()
-                    .map(|x| x.to_dict(py))
-                    .collect::<PyResult<Vec<_>>>()
-            }),
-        )] {
-            match items {
-                Some(items) => dict.set_item(key, items?)?,
-                None => dict.set_item(key, py.None())?,
-            }
-        }
-
-        match self.comments.as_deref() {
-            Some(items) => dict.set_item(
-                "comments",
-                items
-                    .iter()
-                    .map(|x| x.to_dict(py))
-                    .collect::<PyResult<Vec<_>>>()?,
-            )?,
-            None => dict.set_item("comments", py.None())?,
-        }
-
-        match self.queries.as_deref() {
-            Some(items) => dict.set_item(
-                "queries",
-                items
-                    .iter()
-                    .map(|x| x.to_dict(py))
-                    .collect::<PyResult<Vec<_>>>()?,
-            )?,
-            None => dict.set_item("queries", py.None())?,
-        }
-
-        match self.download_history.as_deref() {
-            Some(items) => dict.set_item(
-                "download_history",
-                items
-                    .iter()
-                    .map(|x| x.to_dict(py))
-                    .collect::<PyResult<Vec<_>>>()?,
-            )?,
-            None => dict.set_item("download_history", py.None())?,
-        }
-
-        Ok(dict)
+        ToDict::to_dict(self, py)
     }
 }
 
+#[cfg(feature = "python")]
+to_dict!(Comment {
+    comment_id,
+    reviewed_by,
+    reviewed_by_unique_id,
+    reviewed_by_when,
+    value
+});
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl Comment {
+    #[new]
+    #[pyo3(signature = (
+        comment_id,
+        value=None,
+        *,
+        reviewed_by=None,
+        reviewed_by_unique_id=None,
+        reviewed_by_when=None
+    ))]
+    fn new(
+        comment_id: String,
+        value: Option<&Bound<'_, Value>>,
+        reviewed_by: Option<String>,
+        reviewed_by_unique_id: Option<String>,
+        reviewed_by_when: Option<&Bound<'_, PyDateTime>>,
+    ) -> PyResult<Self> {
+        Ok(Comment {
+            comment_id,
+            reviewed_by,
+            reviewed_by_unique_id,
+            reviewed_by_when: from_py_datetime_option(reviewed_by_when)?,
+            value: value.map(|value| value.get().clone()),
+        })
+    }
+
     #[getter]
     fn comment_id(&self) -> PyResult<String> {
         Ok(self.comment_id.clone())
@@ -707,48 +810,71 @@ impl Comment {
         to_py_datetime_option(py, &self.reviewed_by_when)
     }
 
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "Comment")
+            .field("comment_id", &*self.comment_id)?
+            .field("value", self.value.clone())?
+            .finish())
+    }
+
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
+
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
+
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
+
     pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("comment_id", &self.comment_id)?;
-        dict.set_item("reviewed_by", &self.reviewed_by)?;
-        dict.set_item("reviewed_by_unique_id", &self.reviewed_by_unique_id)?;
-        dict.set_item(
-            "reviewed_by_when",
-            to_py_datetime_option(py, &self.reviewed_by_when)?,
-        )?;
-        if let Some(value) = &self.value {
-            dict.set_item("value", value.to_dict(py)?)?;
-        } else {
-            dict.set_item("value", py.None())?;
-        }
-
-        Ok(dict)
+        ToDict::to_dict(self, py)
     }
 }
 
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct Query {
     #[serde(rename = "queryId")]
     #[serde(alias = "@id")]
     #[serde(alias = "queryId")]
+    #[serde(alias = "query_id")]
     pub query_id: Arc<str>,
 
     #[serde(rename = "reviewedBy")]
     #[serde(alias = "@reviewedBy")]
     #[serde(alias = "reviewedBy")]
+    #[serde(alias = "reviewed_by")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub reviewed_by: Option<String>,
 
     #[serde(rename = "reviewedByUniqueId")]
     #[serde(alias = "@reviewedByUniqueId")]
     #[serde(alias = "reviewedByUniqueId")]
+    #[serde(alias = "reviewed_by_unique_id")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub reviewed_by_unique_id: Option<String>,
 
     #[serde(rename = "reviewedByWhen")]
     #[serde(alias = "@reviewedByWhen")]
     #[serde(alias = "reviewedByWhen")]
+    #[serde(alias = "reviewed_by_when")]
     #[serde(
         default,
         deserialize_with = "deserialize_empty_string_as_none_datetime"
@@ -759,8 +885,17 @@ pub struct Query {
     pub answer: Option<Value>,
 }
 
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct File {
     #[serde(rename = "name")]
     #[serde(alias = "@name")]
@@ -770,32 +905,38 @@ pub struct File {
     #[serde(rename = "fileType")]
     #[serde(alias = "@type")]
     #[serde(alias = "fileType")]
+    #[serde(alias = "file_type")]
     pub file_type: Arc<str>,
 
     #[serde(rename = "dataType")]
     #[serde(alias = "@dataType")]
     #[serde(alias = "dataType")]
+    #[serde(alias = "data_type")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none_arc")]
     pub data_type: Option<Arc<str>>,
 
     #[serde(rename = "errorCode")]
     #[serde(alias = "@errorCode")]
     #[serde(alias = "errorCode")]
+    #[serde(alias = "error_code")]
     pub error_code: Arc<str>,
 
     #[serde(rename = "whenCreated")]
     #[serde(alias = "@whenCreated")]
     #[serde(alias = "whenCreated")]
+    #[serde(alias = "when_created")]
     pub when_created: Option<DateTime<Utc>>,
 
     #[serde(rename = "keepHistory")]
     #[serde(alias = "@keepHistory")]
     #[serde(alias = "keepHistory")]
+    #[serde(alias = "keep_history")]
     pub keep_history: bool,
 
     #[serde(rename = "fileLocation")]
     #[serde(alias = "@fileLocation")]
     #[serde(alias = "fileLocation")]
+    #[serde(alias = "file_location")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub file_location: Option<String>,
 
@@ -816,133 +957,54 @@ pub struct File {
 
     #[serde(rename = "downloadHistory")]
     #[serde(alias = "downloadHistory")]
+    #[serde(alias = "download_history")]
     pub download_history: Option<Arc<Vec<Comment>>>,
 }
 
-#[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[pyclass(skip_from_py_object)]
-pub struct Query {
-    #[serde(rename = "queryId")]
-    #[serde(alias = "@id")]
-    #[serde(alias = "queryId")]
-    pub query_id: Arc<str>,
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
+pub struct Field {
+    #[serde(rename = "name")]
+    #[serde(alias = "@name")]
+    #[serde(alias = "name")]
+    pub name: Arc<str>,
 
-    #[serde(rename = "reviewedBy")]
-    #[serde(alias = "@reviewedBy")]
-    #[serde(alias = "reviewedBy")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub reviewed_by: Option<String>,
+    #[serde(rename = "fieldType")]
+    #[serde(alias = "@type")]
+    #[serde(alias = "fieldType")]
+    #[serde(alias = "field_type")]
+    pub field_type: Arc<str>,
 
-    #[serde(rename = "reviewedByUniqueId")]
-    #[serde(alias = "@reviewedByUniqueId")]
-    #[serde(alias = "reviewedByUniqueId")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub reviewed_by_unique_id: Option<String>,
+    #[serde(rename = "dataType")]
+    #[serde(alias = "@dataType")]
+    #[serde(alias = "dataType")]
+    #[serde(alias = "data_type")]
+    #[serde(default, deserialize_with = "deserialize_empty_string_as_none_arc")]
+    pub data_type: Option<Arc<str>>,
 
-    #[serde(rename = "reviewedByWhen")]
-    #[serde(alias = "@reviewedByWhen")]
-    #[serde(alias = "reviewedByWhen")]
-    #[serde(
-        default,
-        deserialize_with = "deserialize_empty_string_as_none_datetime"
-    )]
-    pub reviewed_by_when: Option<DateTime<Utc>>,
-
-    pub value: Option<Value>,
-    pub answer: Option<Value>,
-}
-
-#[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[pyclass(skip_from_py_object)]
-pub struct File {
-    #[serde(rename = "name")]
-    #[serde(alias = "@name")]
-    #[serde(alias = "name")]
-    pub name: Arc<str>,
-
-    #[serde(rename = "fileType")]
-    #[serde(alias = "@type")]
-    #[serde(alias = "fileType")]
-    pub file_type: Arc<str>,
-
-    #[serde(rename = "dataType")]
-    #[serde(alias = "@dataType")]
-    #[serde(alias = "dataType")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none_arc")]
-    pub data_type: Option<Arc<str>>,
-
-    #[serde(rename = "errorCode")]
-    #[serde(alias = "@errorCode")]
-    #[serde(alias = "errorCode")]
-    pub error_code: Arc<str>,
-
-    #[serde(rename = "whenCreated")]
-    #[serde(alias = "@whenCreated")]
-    #[serde(alias = "whenCreated")]
-    pub when_created: Option<DateTime<Utc>>,
-
-    #[serde(rename = "keepHistory")]
-    #[serde(alias = "@keepHistory")]
-    #[serde(alias = "keepHistory")]
-    pub keep_history: bool,
-
-    #[serde(rename = "fileLocation")]
-    #[serde(alias = "@fileLocation")]
-    #[serde(alias = "fileLocation")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub file_location: Option<String>,
-
-    #[serde(rename = "size")]
-    #[serde(alias = "@size")]
-    #[serde(alias = "size")]
-    #[serde(default)]
-    pub size: Option<usize>,
-
-    #[serde(alias = "entry")]
-    pub entries: Option<Arc<Vec<Entry>>>,
-
-    #[serde(alias = "comment")]
-    pub comments: Option<Arc<Vec<Comment>>>,
-
-    #[serde(alias = "query")]
-    pub queries: Option<Arc<Vec<Query>>>,
-
-    #[serde(rename = "downloadHistory")]
-    #[serde(alias = "downloadHistory")]
-    pub download_history: Option<Arc<Vec<Comment>>>,
-}
-
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Field {
-    #[serde(rename = "name")]
-    #[serde(alias = "@name")]
-    #[serde(alias = "name")]
-    pub name: Arc<str>,
-
-    #[serde(rename = "fieldType")]
-    #[serde(alias = "@type")]
-    #[serde(alias = "fieldType")]
-    pub field_type: Arc<str>,
-
-    #[serde(rename = "dataType")]
-    #[serde(alias = "@dataType")]
-    #[serde(alias = "dataType")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none_arc")]
-    pub data_type: Option<Arc<str>>,
     #[serde(rename = "errorCode")]
     #[serde(alias = "@errorCode")]
     #[serde(alias = "errorCode")]
+    #[serde(alias = "error_code")]
     pub error_code: Arc<str>,
     #[serde(rename = "whenCreated")]
     #[serde(alias = "@whenCreated")]
     #[serde(alias = "whenCreated")]
+    #[serde(alias = "when_created")]
     pub when_created: Option<DateTime<Utc>>,
     #[serde(rename = "keepHistory")]
     #[serde(alias = "@keepHistory")]
     #[serde(alias = "keepHistory")]
+    #[serde(alias = "keep_history")]
     pub keep_history: bool,
 
     #[serde(alias = "entry")]
@@ -956,47 +1018,17 @@ pub struct Field {
 }
 
 #[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[pyclass(skip_from_py_object)]
-pub struct Field {
-    #[serde(rename = "name")]
-    #[serde(alias = "@name")]
-    #[serde(alias = "name")]
-    pub name: Arc<str>,
-
-    #[serde(rename = "fieldType")]
-    #[serde(alias = "@type")]
-    #[serde(alias = "fieldType")]
-    pub field_type: Arc<str>,
-
-    #[serde(rename = "dataType")]
-    #[serde(alias = "@dataType")]
-    #[serde(alias = "dataType")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none_arc")]
-    pub data_type: Option<Arc<str>>,
-
-    #[serde(rename = "errorCode")]
-    #[serde(alias = "@errorCode")]
-    #[serde(alias = "errorCode")]
-    pub error_code: Arc<str>,
-    #[serde(rename = "whenCreated")]
-    #[serde(alias = "@whenCreated")]
-    #[serde(alias = "whenCreated")]
-    pub when_created: Option<DateTime<Utc>>,
-    #[serde(rename = "keepHistory")]
-    #[serde(alias = "@keepHistory")]
-    #[serde(alias = "keepHistory")]
-    pub keep_history: bool,
-
-    #[serde(alias = "entry")]
-    pub entries: Option<Arc<Vec<Entry>>>,
-
-    #[serde(alias = "comment")]
-    pub comments: Option<Arc<Vec<Comment>>>,
-
-    #[serde(alias = "query")]
-    pub queries: Option<Arc<Vec<Query>>>,
-}
+to_dict!(Field {
+    name,
+    field_type,
+    data_type,
+    error_code,
+    when_created,
+    keep_history,
+    entries,
+    comments,
+    queries
+});
 
 #[cfg(feature = "python")]
 #[pymethods]
@@ -1035,110 +1067,70 @@ impl Field {
     }
 
     #[getter]
-    fn entries(&self) -> PyResult<Option<Vec<Entry>>> {
-        Ok(self.entries.as_deref().cloned())
+    fn entries(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .entries
+            .clone()
+            .map(|items| ListView::new(py, Source::Entries(items))))
     }
 
     #[getter]
-    fn comments(&self) -> PyResult<Option<Vec<Comment>>> {
-        Ok(self.comments.as_deref().cloned())
+    fn comments(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .comments
+            .clone()
+            .map(|items| ListView::new(py, Source::Comments(items))))
     }
 
     #[getter]
-    fn queries(&self) -> PyResult<Option<Vec<Query>>> {
-        Ok(self.queries.as_deref().cloned())
+    fn queries(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .queries
+            .clone()
+            .map(|items| ListView::new(py, Source::Queries(items))))
     }
 
-    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("name", &*self.name)?;
-        dict.set_item("field_type", &*self.field_type)?;
-        dict.set_item("data_type", self.data_type.as_deref())?;
-        dict.set_item("error_code", &*self.error_code)?;
-        dict.set_item(
-            "when_created",
-            self.when_created
-                .as_ref()
-                .map(|dt| to_py_datetime(py, dt))
-                .transpose()?,
-        )?;
-        dict.set_item("keep_history", self.keep_history)?;
-
-        let mut entry_dicts = Vec::new();
-        if let Some(entries) = &self.entries {
-            for entry in entries.iter() {
-                let entry_dict = entry.to_dict(py)?;
-                entry_dicts.push(entry_dict);
-            }
-            dict.set_item("entries", entry_dicts)?;
-        } else {
-            dict.set_item("entries", py.None())?;
-        }
-
-        let mut comment_dicts = Vec::new();
-        if let Some(comments) = &self.comments {
-            for comment in comments.iter() {
-                let comment_dict = comment.to_dict(py)?;
-                comment_dicts.push(comment_dict);
-            }
-            dict.set_item("comments", comment_dicts)?;
-        } else {
-            dict.set_item("comments", py.None())?;
-        }
-
-        let mut queries_dicts = Vec::new();
-        if let Some(queries) = &self.queries {
-            for item in queries.iter() {
-                queries_dicts.push(item.to_dict(py)?);
-            }
-            dict.set_item("queries", queries_dicts)?;
-        } else {
-            dict.set_item("queries", py.None())?;
-        }
-        Ok(dict)
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "Field")
+            .field("name", &*self.name)?
+            .field("field_type", &*self.field_type)?
+            .list("entries", self.entries.as_ref().map(|items| items.len()))
+            .finish())
     }
-}
-
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Category {
-    #[serde(rename = "name")]
-    #[serde(alias = "@name")]
-    #[serde(alias = "name")]
-    pub name: Arc<str>,
-
-    #[serde(rename = "categoryType")]
-    #[serde(alias = "@type")]
-    #[serde(alias = "categoryType")]
-    pub category_type: Arc<str>,
 
-    #[serde(rename = "highestIndex")]
-    #[serde(alias = "@highestIndex")]
-    #[serde(alias = "highestIndex")]
-    pub highest_index: usize,
-
-    #[serde(rename = "obfuscated")]
-    #[serde(alias = "@obfuscated")]
-    #[serde(alias = "obfuscated")]
-    #[serde(default)]
-    pub obfuscated: bool,
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
 
-    #[serde(rename = "overRideHighestIndex")]
-    #[serde(alias = "@overRideHighestIndex")]
-    #[serde(alias = "overRideHighestIndex")]
-    #[serde(default)]
-    pub over_ride_highest_index: bool,
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
 
-    #[serde(alias = "field")]
-    pub fields: Option<Arc<Vec<Field>>>,
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
 
-    #[serde(alias = "file")]
-    pub files: Option<Arc<Vec<File>>>,
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ToDict::to_dict(self, py)
+    }
 }
 
-#[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[pyclass(skip_from_py_object)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct Category {
     #[serde(rename = "name")]
     #[serde(alias = "@name")]
@@ -1148,11 +1140,13 @@ pub struct Category {
     #[serde(rename = "categoryType")]
     #[serde(alias = "@type")]
     #[serde(alias = "categoryType")]
+    #[serde(alias = "category_type")]
     pub category_type: Arc<str>,
 
     #[serde(rename = "highestIndex")]
     #[serde(alias = "@highestIndex")]
     #[serde(alias = "highestIndex")]
+    #[serde(alias = "highest_index")]
     pub highest_index: usize,
 
     #[serde(rename = "obfuscated")]
@@ -1164,6 +1158,7 @@ pub struct Category {
     #[serde(rename = "overRideHighestIndex")]
     #[serde(alias = "@overRideHighestIndex")]
     #[serde(alias = "overRideHighestIndex")]
+    #[serde(alias = "over_ride_highest_index")]
     #[serde(default)]
     pub over_ride_highest_index: bool,
 
@@ -1174,6 +1169,17 @@ pub struct Category {
     pub files: Option<Arc<Vec<File>>>,
 }
 
+#[cfg(feature = "python")]
+to_dict!(Category {
+    name,
+    category_type,
+    highest_index,
+    obfuscated,
+    over_ride_highest_index,
+    fields,
+    files
+});
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl Category {
@@ -1193,13 +1199,19 @@ impl Category {
     }
 
     #[getter]
-    fn fields(&self) -> PyResult<Option<Vec<Field>>> {
-        Ok(self.fields.as_deref().cloned())
+    fn fields(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .fields
+            .clone()
+            .map(|items| ListView::new(py, Source::Fields(items))))
     }
 
     #[getter]
-    fn files(&self) -> PyResult<Option<Vec<File>>> {
-        Ok(self.files.as_deref().cloned())
+    fn files(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .files
+            .clone()
+            .map(|items| ListView::new(py, Source::Files(items))))
     }
 
     #[getter]
@@ -1212,97 +1224,48 @@ impl Category {
         Ok(self.over_ride_highest_index)
     }
 
-    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("name", &*self.name)?;
-        dict.set_item("category_type", &*self.category_type)?;
-        dict.set_item("highest_index", self.highest_index)?;
-        dict.set_item("obfuscated", self.obfuscated)?;
-        dict.set_item("over_ride_highest_index", self.over_ride_highest_index)?;
-
-        let mut field_dicts = Vec::new();
-        if let Some(fields) = &self.fields {
-            for field in fields.iter() {
-                let field_dict = field.to_dict(py)?;
-                field_dicts.push(field_dict);
-            }
-            dict.set_item("fields", field_dicts)?;
-        } else {
-            dict.set_item("fields", py.None())?;
-        }
-
-        let mut files_dicts = Vec::new();
-        if let Some(files) = &self.files {
-            for item in files.iter() {
-                files_dicts.push(item.to_dict(py)?);
-            }
-            dict.set_item("files", files_dicts)?;
-        } else {
-            dict.set_item("files", py.None())?;
-        }
-        Ok(dict)
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "Category")
+            .field("name", &*self.name)?
+            .field("category_type", &*self.category_type)?
+            .list("fields", self.fields.as_ref().map(|items| items.len()))
+            .list("files", self.files.as_ref().map(|items| items.len()))
+            .finish())
     }
-}
 
-impl Form {
-    pub(crate) fn from_attributes(e: &BytesStart<'_>) -> Result<Self, crate::errors::Error> {
-        let mut name = "";
-        let mut last_modified = "";
-        let mut who_last_modified_name = "";
-        let mut who_last_modified_role = "";
-        let mut when_created = "";
-        let mut has_errors = "";
-        let mut has_warnings = "";
-        let mut locked = "";
-        let mut user = "";
-        let mut date_time_changed = "";
-        let mut form_title = "";
-        let mut form_index = "";
-        let mut form_group = "";
-        let mut form_state = "";
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
 
-        visit_attributes(e, |key, attr| match key {
-            b"name" => name = attr,
-            b"lastModified" => last_modified = attr,
-            b"whoLastModifiedName" => who_last_modified_name = attr,
-            b"whoLastModifiedRole" => who_last_modified_role = attr,
-            b"whenCreated" => when_created = attr,
-            b"hasErrors" => has_errors = attr,
-            b"hasWarnings" => has_warnings = attr,
-            b"locked" => locked = attr,
-            b"user" => user = attr,
-            b"dateTimeChanged" => date_time_changed = attr,
-            b"formTitle" => form_title = attr,
-            b"formIndex" => form_index = attr,
-            b"formGroup" => form_group = attr,
-            b"formState" => form_state = attr,
-            _ => {}
-        })?;
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
 
-        Ok(Form {
-            name: attribute_string(name),
-            last_modified: optional_datetime(last_modified),
-            who_last_modified_name: optional_string(who_last_modified_name),
-            who_last_modified_role: optional_string(who_last_modified_role),
-            when_created: when_created.parse().unwrap_or(0),
-            has_errors: has_errors == "true",
-            has_warnings: has_warnings == "true",
-            locked: locked == "true",
-            user: optional_string(user),
-            date_time_changed: optional_datetime(date_time_changed),
-            form_title: attribute_string(form_title),
-            form_index: form_index.parse().unwrap_or(0),
-            form_group: optional_string(form_group),
-            form_state: attribute_string(form_state),
-            states: None,
-            lock_states: None,
-            categories: None,
-        })
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
+
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ToDict::to_dict(self, py)
     }
 }
 
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct State {
     #[serde(rename = "value")]
     #[serde(alias = "@value")]
@@ -1315,11 +1278,13 @@ pub struct State {
     #[serde(rename = "signerUniqueId")]
     #[serde(alias = "@signerUniqueId")]
     #[serde(alias = "signerUniqueId")]
+    #[serde(alias = "signer_unique_id")]
     pub signer_unique_id: Arc<str>,
 
     #[serde(rename = "dateSigned")]
     #[serde(alias = "@dateSigned")]
     #[serde(alias = "dateSigned")]
+    #[serde(alias = "date_signed")]
     #[serde(
         default,
         deserialize_with = "deserialize_empty_string_as_none_datetime"
@@ -1328,35 +1293,32 @@ pub struct State {
 }
 
 #[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[pyclass(skip_from_py_object)]
-pub struct State {
-    #[serde(rename = "value")]
-    #[serde(alias = "@value")]
-    #[serde(alias = "value")]
-    pub value: Arc<str>,
-    #[serde(rename = "signer")]
-    #[serde(alias = "@signer")]
-    #[serde(alias = "signer")]
-    pub signer: Arc<str>,
-    #[serde(rename = "signerUniqueId")]
-    #[serde(alias = "@signerUniqueId")]
-    #[serde(alias = "signerUniqueId")]
-    pub signer_unique_id: Arc<str>,
-
-    #[serde(rename = "dateSigned")]
-    #[serde(alias = "@dateSigned")]
-    #[serde(alias = "dateSigned")]
-    #[serde(
-        default,
-        deserialize_with = "deserialize_empty_string_as_none_datetime"
-    )]
-    pub date_signed: Option<DateTime<Utc>>,
-}
+to_dict!(State {
+    value,
+    signer,
+    signer_unique_id,
+    date_signed
+});
 
 #[cfg(feature = "python")]
 #[pymethods]
 impl State {
+    #[new]
+    #[pyo3(signature = (value, signer, signer_unique_id, date_signed=None))]
+    fn new(
+        value: &str,
+        signer: &str,
+        signer_unique_id: &str,
+        date_signed: Option<&Bound<'_, PyDateTime>>,
+    ) -> PyResult<Self> {
+        Ok(State {
+            value: Arc::from(value),
+            signer: Arc::from(signer),
+            signer_unique_id: Arc::from(signer_unique_id),
+            date_signed: from_py_datetime_option(date_signed)?,
+        })
+    }
+
     #[getter]
     fn value(&self) -> PyResult<String> {
         Ok(self.value.to_string())
@@ -1377,50 +1339,47 @@ impl State {
         to_py_datetime_option(py, &self.date_signed)
     }
 
-    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("value", &*self.value)?;
-        dict.set_item("signer", &*self.signer)?;
-        dict.set_item("signer_unique_id", &*self.signer_unique_id)?;
-        dict.set_item("date_signed", to_py_datetime_option(py, &self.date_signed)?)?;
-
-        Ok(dict)
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "State")
+            .field("value", &*self.value)?
+            .field("signer", &*self.signer)?
+            .field("date_signed", to_py_datetime_option(py, &self.date_signed)?)?
+            .finish())
     }
-}
 
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct LockState {
-    #[serde(rename = "locked")]
-    #[serde(alias = "@locked")]
-    #[serde(alias = "locked")]
-    pub locked: bool,
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
 
-    #[serde(rename = "user")]
-    #[serde(alias = "@user")]
-    #[serde(alias = "user")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub user: Option<String>,
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
 
-    #[serde(rename = "userUniqueId")]
-    #[serde(alias = "@userUniqueId")]
-    #[serde(alias = "userUniqueId")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub user_unique_id: Option<String>,
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
 
-    #[serde(rename = "dateTimeChanged")]
-    #[serde(alias = "@dateTimeChanged")]
-    #[serde(alias = "dateTimeChanged")]
-    #[serde(
-        default,
-        deserialize_with = "deserialize_empty_string_as_none_datetime"
-    )]
-    pub date_time_changed: Option<DateTime<Utc>>,
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ToDict::to_dict(self, py)
+    }
 }
 
-#[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[pyclass(skip_from_py_object)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct LockState {
     #[serde(rename = "locked")]
     #[serde(alias = "@locked")]
@@ -1436,12 +1395,14 @@ pub struct LockState {
     #[serde(rename = "userUniqueId")]
     #[serde(alias = "@userUniqueId")]
     #[serde(alias = "userUniqueId")]
+    #[serde(alias = "user_unique_id")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub user_unique_id: Option<String>,
 
     #[serde(rename = "dateTimeChanged")]
     #[serde(alias = "@dateTimeChanged")]
     #[serde(alias = "dateTimeChanged")]
+    #[serde(alias = "date_time_changed")]
     #[serde(
         default,
         deserialize_with = "deserialize_empty_string_as_none_datetime"
@@ -1449,9 +1410,33 @@ pub struct LockState {
     pub date_time_changed: Option<DateTime<Utc>>,
 }
 
+#[cfg(feature = "python")]
+to_dict!(LockState {
+    locked,
+    user,
+    user_unique_id,
+    date_time_changed
+});
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl LockState {
+    #[new]
+    #[pyo3(signature = (locked, *, user=None, user_unique_id=None, date_time_changed=None))]
+    fn new(
+        locked: bool,
+        user: Option<String>,
+        user_unique_id: Option<String>,
+        date_time_changed: Option<&Bound<'_, PyDateTime>>,
+    ) -> PyResult<Self> {
+        Ok(LockState {
+            locked,
+            user,
+            user_unique_id,
+            date_time_changed: from_py_datetime_option(date_time_changed)?,
+        })
+    }
+
     #[getter]
     fn locked(&self) -> PyResult<bool> {
         Ok(self.locked)
@@ -1472,123 +1457,60 @@ impl LockState {
         to_py_datetime_option(py, &self.date_time_changed)
     }
 
-    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("locked", self.locked)?;
-        dict.set_item("user", &self.user)?;
-        dict.set_item("user_unique_id", &self.user_unique_id)?;
-        dict.set_item(
-            "date_time_changed",
-            to_py_datetime_option(py, &self.date_time_changed)?,
-        )?;
-
-        Ok(dict)
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "LockState")
+            .field("locked", self.locked)?
+            .field("user", self.user.as_deref())?
+            .field(
+                "date_time_changed",
+                to_py_datetime_option(py, &self.date_time_changed)?,
+            )?
+            .finish())
     }
-}
-
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Form {
-    #[serde(rename = "name")]
-    #[serde(alias = "@name")]
-    #[serde(alias = "name")]
-    pub name: String,
-
-    #[serde(rename = "lastModified")]
-    #[serde(alias = "@lastModified")]
-    #[serde(alias = "lastModified")]
-    #[serde(
-        default,
-        deserialize_with = "deserialize_empty_string_as_none_datetime"
-    )]
-    pub last_modified: Option<DateTime<Utc>>,
-
-    #[serde(rename = "whoLastModifiedName")]
-    #[serde(alias = "@whoLastModifiedName")]
-    #[serde(alias = "whoLastModifiedName")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub who_last_modified_name: Option<String>,
-
-    #[serde(rename = "whoLastModifiedRole")]
-    #[serde(alias = "@whoLastModifiedRole")]
-    #[serde(alias = "whoLastModifiedRole")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub who_last_modified_role: Option<String>,
-
-    #[serde(rename = "whenCreated")]
-    #[serde(alias = "@whenCreated")]
-    #[serde(alias = "whenCreated")]
-    pub when_created: usize,
-    #[serde(rename = "hasErrors")]
-    #[serde(alias = "@hasErrors")]
-    #[serde(alias = "hasErrors")]
-    pub has_errors: bool,
-    #[serde(rename = "hasWarnings")]
-    #[serde(alias = "@hasWarnings")]
-    #[serde(alias = "hasWarnings")]
-    pub has_warnings: bool,
-    #[serde(rename = "locked")]
-    #[serde(alias = "@locked")]
-    #[serde(alias = "locked")]
-    pub locked: bool,
-
-    #[serde(rename = "user")]
-    #[serde(alias = "@user")]
-    #[serde(alias = "user")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub user: Option<String>,
-
-    #[serde(rename = "dateTimeChanged")]
-    #[serde(alias = "@dateTimeChanged")]
-    #[serde(alias = "dateTimeChanged")]
-    #[serde(
-        default,
-        deserialize_with = "deserialize_empty_string_as_none_datetime"
-    )]
-    pub date_time_changed: Option<DateTime<Utc>>,
-
-    #[serde(rename = "formTitle")]
-    #[serde(alias = "@formTitle")]
-    #[serde(alias = "formTitle")]
-    pub form_title: String,
-    #[serde(rename = "formIndex")]
-    #[serde(alias = "@formIndex")]
-    #[serde(alias = "formIndex")]
-    pub form_index: usize,
-
-    #[serde(rename = "formGroup")]
-    #[serde(alias = "@formGroup")]
-    #[serde(alias = "formGroup")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub form_group: Option<String>,
 
-    #[serde(rename = "formState")]
-    #[serde(alias = "@formState")]
-    #[serde(alias = "formState")]
-    pub form_state: String,
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
 
-    #[serde(alias = "state")]
-    pub states: Option<Arc<Vec<State>>>,
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
 
-    #[serde(alias = "lockState")]
-    pub lock_states: Option<Arc<Vec<LockState>>>,
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
 
-    #[serde(alias = "category")]
-    pub categories: Option<Arc<Vec<Category>>>,
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ToDict::to_dict(self, py)
+    }
 }
 
-#[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[pyclass(skip_from_py_object)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct Form {
     #[serde(rename = "name")]
     #[serde(alias = "@name")]
     #[serde(alias = "name")]
-    pub name: String,
+    pub name: Arc<str>,
 
     #[serde(rename = "lastModified")]
     #[serde(alias = "@lastModified")]
     #[serde(alias = "lastModified")]
+    #[serde(alias = "last_modified")]
     #[serde(
         default,
         deserialize_with = "deserialize_empty_string_as_none_datetime"
@@ -1598,26 +1520,31 @@ pub struct Form {
     #[serde(rename = "whoLastModifiedName")]
     #[serde(alias = "@whoLastModifiedName")]
     #[serde(alias = "whoLastModifiedName")]
+    #[serde(alias = "who_last_modified_name")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub who_last_modified_name: Option<String>,
 
     #[serde(rename = "whoLastModifiedRole")]
     #[serde(alias = "@whoLastModifiedRole")]
     #[serde(alias = "whoLastModifiedRole")]
+    #[serde(alias = "who_last_modified_role")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub who_last_modified_role: Option<String>,
 
     #[serde(rename = "whenCreated")]
     #[serde(alias = "@whenCreated")]
     #[serde(alias = "whenCreated")]
+    #[serde(alias = "when_created")]
     pub when_created: usize,
     #[serde(rename = "hasErrors")]
     #[serde(alias = "@hasErrors")]
     #[serde(alias = "hasErrors")]
+    #[serde(alias = "has_errors")]
     pub has_errors: bool,
     #[serde(rename = "hasWarnings")]
     #[serde(alias = "@hasWarnings")]
     #[serde(alias = "hasWarnings")]
+    #[serde(alias = "has_warnings")]
     pub has_warnings: bool,
     #[serde(rename = "locked")]
     #[serde(alias = "@locked")]
@@ -1633,6 +1560,7 @@ pub struct Form {
     #[serde(rename = "dateTimeChanged")]
     #[serde(alias = "@dateTimeChanged")]
     #[serde(alias = "dateTimeChanged")]
+    #[serde(alias = "date_time_changed")]
     #[serde(
         default,
         deserialize_with = "deserialize_empty_string_as_none_datetime"
@@ -1642,21 +1570,25 @@ pub struct Form {
     #[serde(rename = "formTitle")]
     #[serde(alias = "@formTitle")]
     #[serde(alias = "formTitle")]
-    pub form_title: String,
+    #[serde(alias = "form_title")]
+    pub form_title: Arc<str>,
     #[serde(rename = "formIndex")]
     #[serde(alias = "@formIndex")]
     #[serde(alias = "formIndex")]
+    #[serde(alias = "form_index")]
     pub form_index: usize,
 
     #[serde(rename = "formGroup")]
     #[serde(alias = "@formGroup")]
     #[serde(alias = "formGroup")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub form_group: Option<String>,
+    #[serde(alias = "form_group")]
+    #[serde(default, deserialize_with = "deserialize_empty_string_as_none_arc")]
+    pub form_group: Option<Arc<str>>,
 
     #[serde(rename = "formState")]
     #[serde(alias = "@formState")]
     #[serde(alias = "formState")]
+    #[serde(alias = "form_state")]
     pub form_state: String,
 
     #[serde(alias = "state")]
@@ -1669,12 +1601,33 @@ pub struct Form {
     pub categories: Option<Arc<Vec<Category>>>,
 }
 
+#[cfg(feature = "python")]
+to_dict!(Form {
+    name,
+    last_modified,
+    who_last_modified_name,
+    who_last_modified_role,
+    when_created,
+    has_errors,
+    has_warnings,
+    locked,
+    user,
+    date_time_changed,
+    form_title,
+    form_index,
+    form_group,
+    form_state,
+    states,
+    lock_states,
+    categories
+});
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl Form {
     #[getter]
     fn name(&self) -> PyResult<String> {
-        Ok(self.name.clone())
+        Ok(self.name.to_string())
     }
 
     #[getter]
@@ -1724,7 +1677,7 @@ impl Form {
 
     #[getter]
     fn form_title(&self) -> PyResult<String> {
-        Ok(self.form_title.clone())
+        Ok(self.form_title.to_string())
     }
 
     #[getter]
@@ -1734,7 +1687,7 @@ impl Form {
 
     #[getter]
     fn form_group(&self) -> PyResult<Option<String>> {
-        Ok(self.form_group.clone())
+        Ok(self.form_group.as_deref().map(str::to_string))
     }
 
     #[getter]
@@ -1743,283 +1696,93 @@ impl Form {
     }
 
     #[getter]
-    fn states(&self) -> PyResult<Option<Vec<State>>> {
-        Ok(self.states.as_deref().cloned())
+    fn states(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .states
+            .clone()
+            .map(|items| ListView::new(py, Source::States(items))))
     }
 
     #[getter]
-    fn lock_states(&self) -> PyResult<Option<Vec<LockState>>> {
-        Ok(self.lock_states.as_deref().cloned())
+    fn lock_states(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .lock_states
+            .clone()
+            .map(|items| ListView::new(py, Source::LockStates(items))))
     }
 
     #[getter]
-    fn categories(&self) -> PyResult<Option<Vec<Category>>> {
-        Ok(self.categories.as_deref().cloned())
-    }
-
-    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("name", &self.name)?;
-        dict.set_item(
-            "last_modified",
-            to_py_datetime_option(py, &self.last_modified)?,
-        )?;
-        dict.set_item("who_last_modified_name", &self.who_last_modified_name)?;
-        dict.set_item("who_last_modified_role", &self.who_last_modified_role)?;
-        dict.set_item("when_created", self.when_created)?;
-        dict.set_item("has_errors", self.has_errors)?;
-        dict.set_item("has_warnings", self.has_warnings)?;
-        dict.set_item("locked", self.locked)?;
-        dict.set_item("user", &self.user)?;
-        dict.set_item(
-            "date_time_changed",
-            to_py_datetime_option(py, &self.date_time_changed)?,
-        )?;
-        dict.set_item("form_title", &self.form_title)?;
-        dict.set_item("form_index", self.form_index)?;
-        dict.set_item("form_group", &self.form_group)?;
-        dict.set_item("form_state", &self.form_state)?;
-
-        let mut state_dicts = Vec::new();
-        if let Some(states) = &self.states {
-            for state in states.iter() {
-                let state_dict = state.to_dict(py)?;
-                state_dicts.push(state_dict);
-            }
-            dict.set_item("states", state_dicts)?;
-        } else {
-            dict.set_item("states", py.None())?;
-        }
-
-        let mut lock_state_dicts = Vec::new();
-        if let Some(lock_states) = &self.lock_states {
-            for lock_state in lock_states.iter() {
-                let lock_state_dict = lock_state.to_dict(py)?;
-                lock_state_dicts.push(lock_state_dict);
-            }
-            dict.set_item("lock_states", lock_state_dicts)?;
-        } else {
-            dict.set_item("lock_states", py.None())?;
-        }
-
-        if let Some(categories) = &self.categories {
-            let mut category_dicts = Vec::new();
-            for category in categories.iter() {
-                let category_dict = category.to_dict(py)?;
-                category_dicts.push(category_dict);
-            }
-            dict.set_item("categories", category_dicts)?;
-        } else {
-            dict.set_item("categories", py.None())?;
-        }
-
-        Ok(dict)
+    fn categories(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .categories
+            .clone()
+            .map(|items| ListView::new(py, Source::Categories(items))))
     }
-}
-
-impl State {
-    pub(crate) fn from_attributes(
-        e: &BytesStart<'_>,
-        interner: &mut Interner,
-    ) -> Result<Self, crate::errors::Error> {
-        let mut value = "";
-        let mut signer = "";
-        let mut signer_unique_id = "";
-        let mut date_signed = "";
-
-        visit_attributes(e, |key, attr| match key {
-            b"value" => value = attr,
-            b"signer" => signer = attr,
-            b"signerUniqueId" => signer_unique_id = attr,
-            b"dateSigned" => date_signed = attr,
-            _ => {}
-        })?;
 
-        Ok(State {
-            value: interner.intern(value),
-            signer: interner.intern(signer),
-            signer_unique_id: interner.intern(signer_unique_id),
-            date_signed: optional_datetime(date_signed),
-        })
+    /// Look up a field of the form by name
+    fn __getitem__(&self, name: &str) -> PyResult<Field> {
+        self.field(name)
+            .cloned()
+            .ok_or_else(|| PyErr::new::<PyKeyError, _>(name.to_string()))
     }
-}
-
-impl LockState {
-    pub(crate) fn from_attributes(e: &BytesStart<'_>) -> Result<Self, crate::errors::Error> {
-        let mut locked = "";
-        let mut user = "";
-        let mut user_unique_id = "";
-        let mut date_time_changed = "";
 
-        visit_attributes(e, |key, attr| match key {
-            b"locked" => locked = attr,
-            b"user" => user = attr,
-            b"userUniqueId" => user_unique_id = attr,
-            b"dateTimeChanged" => date_time_changed = attr,
-            _ => {}
-        })?;
-
-        Ok(LockState {
-            locked: locked == "true",
-            user: optional_string(user),
-            user_unique_id: optional_string(user_unique_id),
-            date_time_changed: optional_datetime(date_time_changed),
-        })
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "Form")
+            .field("name", &*self.name)?
+            .field("form_title", &*self.form_title)?
+            .field("form_index", self.form_index)?
+            .field("form_state", &*self.form_state)?
+            .list(
+                "categories",
+                self.categories.as_ref().map(|items| items.len()),
+            )
+            .finish())
     }
-}
 
-impl Category {
-    pub(crate) fn from_attributes(
-        e: &BytesStart<'_>,
-        interner: &mut Interner,
-    ) -> Result<Self, crate::errors::Error> {
-        let mut name = "";
-        let mut category_type = "";
-        let mut highest_index = "";
-        let mut obfuscated = "";
-        let mut over_ride_highest_index = "";
-
-        visit_attributes(e, |key, attr| match key {
-            b"name" => name = attr,
-            b"type" => category_type = attr,
-            b"highestIndex" => highest_index = attr,
-            b"obfuscated" => obfuscated = attr,
-            b"overRideHighestIndex" => over_ride_highest_index = attr,
-            _ => {}
-        })?;
-
-        Ok(Category {
-            name: interner.intern(name),
-            category_type: interner.intern(category_type),
-            highest_index: highest_index.parse().unwrap_or(0),
-            obfuscated: obfuscated == "true",
-            over_ride_highest_index: over_ride_highest_index == "true",
-            fields: None,
-            files: None,
-        })
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
     }
-}
-
-impl Field {
-    pub(crate) fn from_attributes(
-        e: &BytesStart<'_>,
-        interner: &mut Interner,
-    ) -> Result<Self, crate::errors::Error> {
-        let mut name = "";
-        let mut field_type = "";
-        let mut data_type = "";
-        let mut error_code = "";
-        let mut when_created = "";
-        let mut keep_history = "";
-
-        visit_attributes(e, |key, attr| match key {
-            b"name" => name = attr,
-            b"type" => field_type = attr,
-            b"dataType" => data_type = attr,
-            b"errorCode" => error_code = attr,
-            b"whenCreated" => when_created = attr,
-            b"keepHistory" => keep_history = attr,
-            _ => {}
-        })?;
 
-        Ok(Field {
-            name: interner.intern(name),
-            field_type: interner.intern(field_type),
-            data_type: interner.intern_optional(data_type),
-            error_code: interner.intern(error_code),
-            when_created: checked_datetime(when_created)?,
-            keep_history: keep_history == "true",
-            entries: None,
-            comments: None,
-            queries: None,
-        })
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
     }
-}
-
-impl Entry {
-    pub(crate) fn from_attributes(
-        e: &BytesStart<'_>,
-        interner: &mut Interner,
-    ) -> Result<Self, crate::errors::Error> {
-        let mut id: Option<&str> = None;
-        let mut entry_id: Option<&str> = None;
-        let mut reviewed_by = "";
-        let mut reviewed_by_unique_id = "";
-        let mut reviewed_by_when = "";
-
-        visit_attributes(e, |key, attr| match key {
-            b"id" => id = Some(attr),
-            b"entryId" => entry_id = Some(attr),
-            b"reviewedBy" => reviewed_by = attr,
-            b"reviewedByUniqueId" => reviewed_by_unique_id = attr,
-            b"reviewedByWhen" => reviewed_by_when = attr,
-            _ => {}
-        })?;
 
-        Ok(Entry {
-            entry_id: interner.intern(id.or(entry_id).unwrap_or_default()),
-            reviewed_by: optional_string(reviewed_by),
-            reviewed_by_unique_id: optional_string(reviewed_by_unique_id),
-            reviewed_by_when: optional_datetime(reviewed_by_when),
-            value: None,
-            reason: None,
-        })
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
     }
-}
-
-impl Value {
-    pub(crate) fn from_attributes(
-        e: &BytesStart<'_>,
-        interner: &mut Interner,
-    ) -> Result<Self, crate::errors::Error> {
-        let mut by = "";
-        let mut by_unique_id = "";
-        let mut role = "";
-        let mut when = "";
 
-        visit_attributes(e, |key, attr| match key {
-            b"by" => by = attr,
-            b"byUniqueId" => by_unique_id = attr,
-            b"role" => role = attr,
-            b"when" => when = attr,
-            _ => {}
-        })?;
-
-        Ok(Value {
-            by: interner.intern(by),
-            by_unique_id: interner.intern_optional(by_unique_id),
-            role: interner.intern(role),
-            when: checked_datetime(when)?,
-            value: String::new(),
-        })
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ToDict::to_dict(self, py)
     }
 }
 
-impl Reason {
-    pub(crate) fn from_attributes(
-        e: &BytesStart<'_>,
-        interner: &mut Interner,
-    ) -> Result<Self, crate::errors::Error> {
-        let mut by = "";
-        let mut by_unique_id = "";
-        let mut role = "";
-        let mut when = "";
-
-        visit_attributes(e, |key, attr| match key {
-            b"by" => by = attr,
-            b"byUniqueId" => by_unique_id = attr,
-            b"role" => role = attr,
-            b"when" => when = attr,
-            _ => {}
-        })?;
-
-        Ok(Reason {
-            by: interner.intern(by),
-            by_unique_id: interner.intern_optional(by_unique_id),
-            role: interner.intern(role),
-            when: checked_datetime(when)?,
-            value: String::new(),
-        })
+impl Form {
+    /// Find a field of the form by name, whichever category it is in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use prelude_xml_parser::parse_subject_native_file;
+    ///
+    /// let native = parse_subject_native_file(Path::new("tests/assets/subject_native_small.xml")).unwrap();
+    /// let forms = native.patients[0].forms.as_deref().unwrap();
+    ///
+    /// assert_eq!(&*forms[0].field("breed").unwrap().field_type, "combo-box");
+    /// assert!(forms[0].field("missing").is_none());
+    /// ```
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.categories
+            .iter()
+            .flat_map(|categories| categories.iter())
+            .flat_map(|category| category.fields.iter().flat_map(|fields| fields.iter()))
+            .find(|field| &*field.name == name)
     }
 }
 
@@ -2050,9 +1813,43 @@ impl Export {
     }
 }
 
+#[cfg(feature = "python")]
+to_dict!(Export {
+    date,
+    created_by,
+    role,
+    number_subjects_processed,
+    page_number
+});
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl Export {
+    #[new]
+    #[pyo3(signature = (
+        *,
+        date=None,
+        created_by=None,
+        role=None,
+        number_subjects_processed=None,
+        page_number=None
+    ))]
+    fn new(
+        date: Option<&Bound<'_, PyDateTime>>,
+        created_by: Option<String>,
+        role: Option<String>,
+        number_subjects_processed: Option<usize>,
+        page_number: Option<String>,
+    ) -> PyResult<Self> {
+        Ok(Export {
+            date: from_py_datetime_option(date)?,
+            created_by,
+            role,
+            number_subjects_processed,
+            page_number,
+        })
+    }
+
     #[getter]
     fn date<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDateTime>>> {
         to_py_datetime_option(py, &self.date)
@@ -2078,113 +1875,34 @@ impl Export {
         Ok(self.page_number.clone())
     }
 
-    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("date", to_py_datetime_option(py, &self.date)?)?;
-        dict.set_item("created_by", &self.created_by)?;
-        dict.set_item("role", &self.role)?;
-        dict.set_item("number_subjects_processed", self.number_subjects_processed)?;
-        dict.set_item("page_number", &self.page_number)?;
-
-        Ok(dict)
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "Export")
+            .field("date", to_py_datetime_option(py, &self.date)?)?
+            .field("created_by", self.created_by.as_deref())?
+            .field("role", self.role.as_deref())?
+            .field("number_subjects_processed", self.number_subjects_processed)?
+            .field("page_number", self.page_number.as_deref())?
+            .finish())
     }
-}
 
-impl Query {
-    pub(crate) fn from_attributes(
-        e: &BytesStart<'_>,
-        interner: &mut Interner,
-    ) -> Result<Self, crate::errors::Error> {
-        let mut id: Option<&str> = None;
-        let mut query_id: Option<&str> = None;
-        let mut reviewed_by = "";
-        let mut reviewed_by_unique_id = "";
-        let mut reviewed_by_when = "";
-
-        visit_attributes(e, |key, attr| match key {
-            b"id" => id = Some(attr),
-            b"queryId" => query_id = Some(attr),
-            b"reviewedBy" => reviewed_by = attr,
-            b"reviewedByUniqueId" => reviewed_by_unique_id = attr,
-            b"reviewedByWhen" => reviewed_by_when = attr,
-            _ => {}
-        })?;
-
-        Ok(Query {
-            query_id: interner.intern(id.or(query_id).unwrap_or_default()),
-            reviewed_by: optional_string(reviewed_by),
-            reviewed_by_unique_id: optional_string(reviewed_by_unique_id),
-            reviewed_by_when: optional_datetime(reviewed_by_when),
-            value: None,
-            answer: None,
-        })
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
     }
-}
-
-impl File {
-    pub(crate) fn from_attributes(
-        e: &BytesStart<'_>,
-        interner: &mut Interner,
-    ) -> Result<Self, crate::errors::Error> {
-        let mut name = "";
-        let mut file_type = "";
-        let mut data_type = "";
-        let mut error_code = "";
-        let mut when_created = "";
-        let mut keep_history = "";
-        let mut file_location = "";
-        let mut size = "";
 
-        visit_attributes(e, |key, attr| match key {
-            b"name" => name = attr,
-            b"type" => file_type = attr,
-            b"dataType" => data_type = attr,
-            b"errorCode" => error_code = attr,
-            b"whenCreated" => when_created = attr,
-            b"keepHistory" => keep_history = attr,
-            b"fileLocation" => file_location = attr,
-            b"size" => size = attr,
-            _ => {}
-        })?;
-
-        Ok(File {
-            name: interner.intern(name),
-            file_type: interner.intern(file_type),
-            data_type: interner.intern_optional(data_type),
-            error_code: interner.intern(error_code),
-            when_created: checked_datetime(when_created)?,
-            keep_history: keep_history == "true",
-            file_location: optional_string(file_location),
-            size: size.parse().ok(),
-            entries: None,
-            comments: None,
-            queries: None,
-            download_history: None,
-        })
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
     }
-}
 
-impl Comment {
-    pub(crate) fn from_attributes(e: &BytesStart<'_>) -> Result<Self, crate::errors::Error> {
-        let mut comment_id = "";
-        let mut reviewed_by = "";
-        let mut reviewed_by_unique_id = "";
-        let mut reviewed_by_when = "";
-
-        visit_attributes(e, |key, attr| match key {
-            b"id" => comment_id = attr,
-            b"reviewedBy" => reviewed_by = attr,
-            b"reviewedByUniqueId" => reviewed_by_unique_id = attr,
-            b"reviewedByWhen" => reviewed_by_when = attr,
-            _ => {}
-        })?;
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
 
-        Ok(Comment {
-            comment_id: attribute_string(comment_id),
-            reviewed_by: optional_string(reviewed_by),
-            reviewed_by_unique_id: optional_string(reviewed_by_unique_id),
-            reviewed_by_when: optional_datetime(reviewed_by_when),
-            value: None,
-        })
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ToDict::to_dict(self, py)
     }
 }
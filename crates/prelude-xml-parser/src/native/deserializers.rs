@@ -1,4 +1,9 @@
-use std::{borrow::Cow, collections::HashSet, str::from_utf8, sync::Arc};
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    str::from_utf8,
+    sync::{Arc, Mutex, PoisonError},
+};
 
 #[cfg(feature = "python")]
 use chrono::{Datelike, Timelike};
@@ -6,45 +11,83 @@ use chrono::{Datelike, Timelike};
 use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
 
 #[cfg(feature = "python")]
-use pyo3::{prelude::*, types::PyDateTime};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyDateAccess, PyDateTime, PyTimeAccess, PyTzInfo, PyTzInfoAccess},
+};
 
 use quick_xml::{
     escape::resolve_predefined_entity,
-    events::{BytesRef, BytesStart},
+    events::{BytesCData, BytesRef, BytesStart, BytesText},
 };
 use serde::{Deserialize, Deserializer};
 
-/// Deduplicates the heavily repeated attribute values within a single record.
+/// Deduplicates the heavily repeated attribute values of a document.
 ///
-/// Values such as a field's `type`, `dataType` and `errorCode` take only a handful of distinct
-/// values across hundreds of thousands of elements, so handing out a shared `Arc<str>` replaces
-/// one allocation per occurrence with one per distinct value. One interner is used per parsed
-/// record so that no synchronisation is needed between threads.
+/// Values such as a field's `type`, `dataType` and `errorCode`, form names and titles, and the
+/// users who made entries take only a handful of distinct values across hundreds of thousands of
+/// elements, so handing out a shared `Arc<str>` replaces one allocation per occurrence with one
+/// per distinct value. An interner is only used from one thread at a time, so its lookups need no
+/// locking. A parallel parse makes one for each piece of work rayon splits the records into,
+/// which can be several per thread. Backing them with a [`SharedInterner`] means values an
+/// interner has not seen yet are taken from the one set kept for the whole document, so every
+/// record ends up sharing the same strings.
 #[derive(Default)]
 pub(crate) struct Interner {
     seen: HashSet<Arc<str>>,
+    shared: Option<Arc<SharedInterner>>,
 }
 
 impl Interner {
-    pub(crate) fn intern(&mut self, value: &str) -> Arc<str> {
-        let value = unescape_attribute(value);
+    /// An interner that falls back to `shared` for values it has not seen yet.
+    pub(crate) fn shared(shared: Arc<SharedInterner>) -> Self {
+        Self {
+            seen: HashSet::new(),
+            shared: Some(shared),
+        }
+    }
 
-        if let Some(existing) = self.seen.get(value.as_ref()) {
+    /// Share `value`, which must already have had its entity references resolved.
+    pub(crate) fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(existing) = self.seen.get(value) {
             return existing.clone();
         }
 
-        let shared: Arc<str> = Arc::from(value.as_ref());
+        let shared = match &self.shared {
+            Some(shared) => shared.intern(value),
+            None => Arc::from(value),
+        };
         self.seen.insert(shared.clone());
 
         shared
     }
 
-    pub(crate) fn intern_optional(&mut self, value: &str) -> Option<Arc<str>> {
-        if value.is_empty() {
-            None
-        } else {
-            Some(self.intern(value))
+    pub(crate) fn intern_optional(&mut self, value: Option<&str>) -> Option<Arc<str>> {
+        value.map(|value| self.intern(value))
+    }
+}
+
+/// The strings interned across all the threads parsing one document.
+///
+/// Only consulted when a thread's own [`Interner`] misses, which after the first few records is
+/// rare, so the lock is seldom contended.
+#[derive(Default)]
+pub(crate) struct SharedInterner {
+    seen: Mutex<HashSet<Arc<str>>>,
+}
+
+impl SharedInterner {
+    fn intern(&self, value: &str) -> Arc<str> {
+        let mut seen = self.seen.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(existing) = seen.get(value) {
+            return existing.clone();
         }
+
+        let shared: Arc<str> = Arc::from(value);
+        seen.insert(shared.clone());
+
+        shared
     }
 }
 
@@ -80,9 +123,10 @@ pub(crate) fn decode_error(e: impl std::fmt::Display) -> crate::errors::Error {
 /// the surrounding [`Event::Text`](quick_xml::events::Event::Text) events and these. A reference
 /// that cannot be resolved is kept verbatim rather than dropped, so no input is silently lost.
 pub(crate) fn push_general_ref(
-    text: &mut String,
+    text: &mut Cow<'_, str>,
     reference: &BytesRef<'_>,
 ) -> Result<(), crate::errors::Error> {
+    let text = text.to_mut();
     if let Some(character) = reference.resolve_char_ref().map_err(decode_error)? {
         text.push(character);
         return Ok(());
@@ -102,19 +146,54 @@ pub(crate) fn push_general_ref(
     Ok(())
 }
 
+/// Append a [`BytesText`] event to the text being accumulated.
+///
+/// Text without line endings to normalise arrives as a slice of the document, and when it is the
+/// only fragment, which it nearly always is, it is kept borrowed rather than copied.
+pub(crate) fn push_text<'a>(
+    text: &mut Cow<'a, str>,
+    xml: &'a str,
+    event: &BytesText<'_>,
+) -> Result<(), crate::errors::Error> {
+    let content = event.xml10_content().map_err(decode_error)?;
+    push_content(text, xml, &content);
+    Ok(())
+}
+
+/// Append a [`BytesCData`] event to the text being accumulated.
+///
+/// The section's content is taken verbatim, markup and all, exactly as for [`push_text`].
+pub(crate) fn push_cdata<'a>(
+    text: &mut Cow<'a, str>,
+    xml: &'a str,
+    event: &BytesCData<'_>,
+) -> Result<(), crate::errors::Error> {
+    let content = event.xml10_content().map_err(decode_error)?;
+    push_content(text, xml, &content);
+    Ok(())
+}
+
+fn push_content<'a>(text: &mut Cow<'a, str>, xml: &'a str, content: &str) {
+    if text.is_empty() {
+        if let Some(fragment) = reborrow(xml, content) {
+            *text = Cow::Borrowed(fragment);
+            return;
+        }
+    }
+
+    text.to_mut().push_str(content);
+}
+
 /// Take the accumulated text, trimmed, leaving the buffer empty for the next element.
 ///
 /// Trimming happens once over the fully reassembled text rather than per fragment, so whitespace
 /// that sits next to a reference survives.
-pub(crate) fn take_trimmed(text: &mut String) -> String {
-    if text.trim().len() == text.len() {
-        return std::mem::take(text);
+pub(crate) fn take_trimmed<'a>(text: &mut Cow<'a, str>) -> Cow<'a, str> {
+    match std::mem::take(text) {
+        Cow::Borrowed(text) => Cow::Borrowed(text.trim()),
+        Cow::Owned(text) if text.trim().len() == text.len() => Cow::Owned(text),
+        Cow::Owned(text) => Cow::Owned(text.trim().to_string()),
     }
-
-    let trimmed = text.trim().to_string();
-    text.clear();
-
-    trimmed
 }
 
 /// Walk an element's attributes once, handing each raw key and its borrowed value to `visit`.
@@ -153,6 +232,62 @@ pub(crate) fn visit_attributes<'a>(
     Ok(())
 }
 
+/// Like [`visit_attributes`], but hands out values that borrow from the document rather than from
+/// the element, so they can outlive the event they were read from.
+pub(crate) fn visit_borrowed_attributes<'a>(
+    xml: &'a str,
+    e: &BytesStart<'_>,
+    mut visit: impl FnMut(&[u8], &'a str),
+) -> Result<(), crate::errors::Error> {
+    let mut outside = false;
+    visit_attributes(e, |key, value| match reborrow(xml, value) {
+        Some(value) => visit(key, value),
+        None => outside = true,
+    })?;
+
+    if outside {
+        return Err(crate::errors::Error::ParsingError(
+            quick_xml::de::DeError::Custom(
+                "Attribute value was not borrowed from the document".to_string(),
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Find `part` within `xml` and return it with the lifetime of the whole document.
+///
+/// quick-xml hands out slices of its input tied to the lifetime of the event rather than of the
+/// input, even though they point into it. Locating the slice by address recovers the longer
+/// lifetime without copying; `None` means `part` does not lie within `xml`.
+pub(crate) fn reborrow<'a>(xml: &'a str, part: &str) -> Option<&'a str> {
+    let start = (part.as_ptr() as usize).checked_sub(xml.as_ptr() as usize)?;
+    xml.get(start..start.checked_add(part.len())?)
+}
+
+/// Resolve an attribute's entity references, treating an empty string as absent.
+pub(crate) fn optional_borrowed(value: &str) -> Option<Cow<'_, str>> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(unescape_attribute(value))
+    }
+}
+
+/// Take an attribute that must be present, reporting its absence as an error.
+pub(crate) fn required_borrowed<'a>(
+    value: Option<&'a str>,
+    name: &str,
+) -> Result<Cow<'a, str>, crate::errors::Error> {
+    match value {
+        Some(value) => Ok(unescape_attribute(value)),
+        None => Err(crate::errors::Error::ParsingError(
+            quick_xml::de::DeError::Custom(format!("Missing {}", name)),
+        )),
+    }
+}
+
 /// Parse an attribute that carries a datetime, treating an empty string as absent and an
 /// unparsable value as absent.
 pub(crate) fn optional_datetime(s: &str) -> Option<DateTime<Utc>> {
@@ -173,19 +308,6 @@ pub(crate) fn checked_datetime(s: &str) -> Result<Option<DateTime<Utc>>, crate::
     }
 }
 
-/// Take an attribute that must be present, reporting its absence as an error.
-pub(crate) fn required_attribute(
-    value: Option<&str>,
-    name: &str,
-) -> Result<String, crate::errors::Error> {
-    match value {
-        Some(value) => Ok(attribute_string(value)),
-        None => Err(crate::errors::Error::ParsingError(
-            quick_xml::de::DeError::Custom(format!("Missing {}", name)),
-        )),
-    }
-}
-
 /// Convert an attribute into an owned `String`, treating an empty string as absent.
 pub(crate) fn optional_string(s: &str) -> Option<String> {
     if s.is_empty() {
@@ -242,7 +364,18 @@ fn parse_prelude_datetime(s: &str) -> Option<DateTime<Utc>> {
 
 /// Parse a Prelude datetime attribute, trying the common fixed-shape fast path before falling back
 /// to the general chrono format parsers.
-pub(crate) fn parse_datetime(s: &str) -> Result<DateTime<Utc>, crate::errors::Error> {
+///
+/// # Example
+///
+/// ```
+/// use prelude_xml_parser::native::parse_datetime;
+///
+/// let when = parse_datetime("2023-04-15 12:09:02 -0400").unwrap();
+///
+/// assert_eq!(when.to_rfc3339(), "2023-04-15T16:09:02+00:00");
+/// assert!(parse_datetime("15/04/2023").is_err());
+/// ```
+pub fn parse_datetime(s: &str) -> Result<DateTime<Utc>, crate::errors::Error> {
     if let Some(dt) = parse_prelude_datetime(s) {
         Ok(dt)
     } else if let Ok(dt) = DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S %z") {
@@ -357,13 +490,50 @@ pub fn to_py_datetime_option<'py>(
     }
 }
 
+/// The reverse of [`to_py_datetime`]: a naive datetime is taken to be in UTC, as the ones the
+/// models give are, and an aware one is converted to UTC.
+#[cfg(feature = "python")]
+pub fn from_py_datetime(date_time: &Bound<'_, PyDateTime>) -> PyResult<DateTime<Utc>> {
+    let date_time = if date_time.get_tzinfo().is_some() {
+        let utc = PyTzInfo::utc(date_time.py())?;
+        date_time
+            .call_method1("astimezone", (utc,))?
+            .cast_into::<PyDateTime>()?
+    } else {
+        date_time.clone()
+    };
+
+    NaiveDate::from_ymd_opt(
+        date_time.get_year(),
+        date_time.get_month().into(),
+        date_time.get_day().into(),
+    )
+    .and_then(|date| {
+        date.and_hms_micro_opt(
+            date_time.get_hour().into(),
+            date_time.get_minute().into(),
+            date_time.get_second().into(),
+            date_time.get_microsecond(),
+        )
+    })
+    .map(|date_time| date_time.and_utc())
+    .ok_or_else(|| PyValueError::new_err(format!("{date_time} is not a valid datetime")))
+}
+
+#[cfg(feature = "python")]
+pub fn from_py_datetime_option(
+    date_time: Option<&Bound<'_, PyDateTime>>,
+) -> PyResult<Option<DateTime<Utc>>> {
+    date_time.map(from_py_datetime).transpose()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn resolves_amp() {
-        let mut text = String::new();
+        let mut text = Cow::Borrowed("");
         push_general_ref(&mut text, &BytesRef::new("amp")).expect("reference should resolve");
 
         assert_eq!(text, "&");
@@ -371,7 +541,7 @@ mod tests {
 
     #[test]
     fn resolves_lt() {
-        let mut text = String::new();
+        let mut text = Cow::Borrowed("");
         push_general_ref(&mut text, &BytesRef::new("lt")).expect("reference should resolve");
 
         assert_eq!(text, "<");
@@ -379,7 +549,7 @@ mod tests {
 
     #[test]
     fn resolves_gt() {
-        let mut text = String::new();
+        let mut text = Cow::Borrowed("");
         push_general_ref(&mut text, &BytesRef::new("gt")).expect("reference should resolve");
 
         assert_eq!(text, ">");
@@ -387,7 +557,7 @@ mod tests {
 
     #[test]
     fn resolves_quot() {
-        let mut text = String::new();
+        let mut text = Cow::Borrowed("");
         push_general_ref(&mut text, &BytesRef::new("quot")).expect("reference should resolve");
 
         assert_eq!(text, "\"");
@@ -395,7 +565,7 @@ mod tests {
 
     #[test]
     fn resolves_apos() {
-        let mut text = String::new();
+        let mut text = Cow::Borrowed("");
         push_general_ref(&mut text, &BytesRef::new("apos")).expect("reference should resolve");
 
         assert_eq!(text, "'");
@@ -403,7 +573,7 @@ mod tests {
 
     #[test]
     fn resolves_decimal_character_reference() {
-        let mut text = String::new();
+        let mut text = Cow::Borrowed("");
         push_general_ref(&mut text, &BytesRef::new("#65")).expect("reference should resolve");
 
         assert_eq!(text, "A");
@@ -411,7 +581,7 @@ mod tests {
 
     #[test]
     fn resolves_hex_character_reference() {
-        let mut text = String::new();
+        let mut text = Cow::Borrowed("");
         push_general_ref(&mut text, &BytesRef::new("#x42")).expect("reference should resolve");
 
         assert_eq!(text, "B");
@@ -419,7 +589,7 @@ mod tests {
 
     #[test]
     fn keeps_unresolvable_reference_verbatim() {
-        let mut text = String::new();
+        let mut text = Cow::Borrowed("");
         push_general_ref(&mut text, &BytesRef::new("unknown")).expect("reference should resolve");
 
         assert_eq!(text, "&unknown;");
@@ -427,9 +597,39 @@ mod tests {
 
     #[test]
     fn appends_to_existing_text() {
-        let mut text = String::from("Tom ");
+        let mut text = Cow::Borrowed("Tom ");
         push_general_ref(&mut text, &BytesRef::new("amp")).expect("reference should resolve");
 
         assert_eq!(text, "Tom &");
     }
+
+    #[test]
+    fn borrows_single_text_fragment() {
+        let xml = "<value> Labrador </value>";
+        let mut text = Cow::Borrowed("");
+        push_text(&mut text, xml, &BytesText::from_escaped(&xml[7..17])).unwrap();
+
+        let trimmed = take_trimmed(&mut text);
+        assert!(matches!(trimmed, Cow::Borrowed("Labrador")));
+        assert_eq!(text, "");
+    }
+
+    #[test]
+    fn copies_text_from_outside_the_document() {
+        let xml = "<value>Labrador</value>";
+        let mut text = Cow::Borrowed("");
+        push_text(&mut text, xml, &BytesText::new("Poodle")).unwrap();
+
+        assert!(matches!(text, Cow::Owned(_)));
+        assert_eq!(text, "Poodle");
+    }
+
+    #[test]
+    fn reborrow_rejects_foreign_slices() {
+        let xml = "<field name=\"breed\"/>";
+        let other = String::from("breed");
+
+        assert_eq!(reborrow(xml, &xml[13..18]), Some("breed"));
+        assert_eq!(reborrow(xml, &other), None);
+    }
 }
@@ -0,0 +1,212 @@
+//! Read-only Python sequences over the lists in a parsed export.
+//!
+//! Returning a list from a getter copies every item into it, every time the attribute is read.
+//! A [`ListView`] instead shares the list it was made from, and only copies the item that is
+//! indexed or iterated to. Slicing a view gives another view of the same list.
+
+use std::sync::Arc;
+
+use pyo3::{
+    exceptions::PyIndexError,
+    prelude::*,
+    types::{PyList, PySlice},
+};
+
+use crate::native::{
+    common::{Category, Comment, Entry, Field, File, Form, LockState, Query, State},
+    site_native::SiteNative,
+    subject_native::SubjectNative,
+    user_native::UserNative,
+};
+
+/// The list a view is over.
+///
+/// The records of an export are held in the export itself rather than in a shared list, so views
+/// of them hold the export.
+pub(crate) enum Source {
+    Patients(Py<SubjectNative>),
+    Sites(Py<SiteNative>),
+    Users(Py<UserNative>),
+    Forms(Arc<Vec<Form>>),
+    States(Arc<Vec<State>>),
+    LockStates(Arc<Vec<LockState>>),
+    Categories(Arc<Vec<Category>>),
+    Fields(Arc<Vec<Field>>),
+    Files(Arc<Vec<File>>),
+    Entries(Arc<Vec<Entry>>),
+    Comments(Arc<Vec<Comment>>),
+    Queries(Arc<Vec<Query>>),
+}
+
+impl Source {
+    fn clone_ref(&self, py: Python<'_>) -> Self {
+        match self {
+            Source::Patients(native) => Source::Patients(native.clone_ref(py)),
+            Source::Sites(native) => Source::Sites(native.clone_ref(py)),
+            Source::Users(native) => Source::Users(native.clone_ref(py)),
+            Source::Forms(items) => Source::Forms(items.clone()),
+            Source::States(items) => Source::States(items.clone()),
+            Source::LockStates(items) => Source::LockStates(items.clone()),
+            Source::Categories(items) => Source::Categories(items.clone()),
+            Source::Fields(items) => Source::Fields(items.clone()),
+            Source::Files(items) => Source::Files(items.clone()),
+            Source::Entries(items) => Source::Entries(items.clone()),
+            Source::Comments(items) => Source::Comments(items.clone()),
+            Source::Queries(items) => Source::Queries(items.clone()),
+        }
+    }
+
+    fn len(&self, py: Python<'_>) -> usize {
+        match self {
+            Source::Patients(native) => native.borrow(py).patients.len(),
+            Source::Sites(native) => native.borrow(py).sites.len(),
+            Source::Users(native) => native.borrow(py).users.len(),
+            Source::Forms(items) => items.len(),
+            Source::States(items) => items.len(),
+            Source::LockStates(items) => items.len(),
+            Source::Categories(items) => items.len(),
+            Source::Fields(items) => items.len(),
+            Source::Files(items) => items.len(),
+            Source::Entries(items) => items.len(),
+            Source::Comments(items) => items.len(),
+            Source::Queries(items) => items.len(),
+        }
+    }
+
+    /// A Python copy of the item at `index`, which has to be in bounds.
+    fn get<'py>(&self, py: Python<'py>, index: usize) -> PyResult<Bound<'py, PyAny>> {
+        fn item<'py, T>(py: Python<'py>, item: T) -> PyResult<Bound<'py, PyAny>>
+        where
+            T: Into<PyClassInitializer<T>> + pyo3::PyClass,
+        {
+            Ok(Bound::new(py, item)?.into_any())
+        }
+
+        match self {
+            Source::Patients(native) => item(py, native.borrow(py).patients[index].clone()),
+            Source::Sites(native) => item(py, native.borrow(py).sites[index].clone()),
+            Source::Users(native) => item(py, native.borrow(py).users[index].clone()),
+            Source::Forms(items) => item(py, items[index].clone()),
+            Source::States(items) => item(py, items[index].clone()),
+            Source::LockStates(items) => item(py, items[index].clone()),
+            Source::Categories(items) => item(py, items[index].clone()),
+            Source::Fields(items) => item(py, items[index].clone()),
+            Source::Files(items) => item(py, items[index].clone()),
+            Source::Entries(items) => item(py, items[index].clone()),
+            Source::Comments(items) => item(py, items[index].clone()),
+            Source::Queries(items) => item(py, items[index].clone()),
+        }
+    }
+}
+
+/// A read-only sequence over a list in an export, copying only the items that are accessed.
+#[pyclass(module = "prelude_parser._prelude_parser", frozen, sequence)]
+pub struct ListView {
+    source: Source,
+    /// The position in the source of the view's first item.
+    start: isize,
+    step: isize,
+    len: usize,
+}
+
+impl ListView {
+    pub(crate) fn new(py: Python<'_>, source: Source) -> Self {
+        let len = source.len(py);
+
+        ListView {
+            source,
+            start: 0,
+            step: 1,
+            len,
+        }
+    }
+
+    fn position(&self, index: usize) -> usize {
+        (self.start + index as isize * self.step) as usize
+    }
+}
+
+#[derive(FromPyObject)]
+enum Index<'py> {
+    Position(isize),
+    Slice(Bound<'py, PySlice>),
+}
+
+#[pymethods]
+impl ListView {
+    fn __len__(&self) -> usize {
+        self.len
+    }
+
+    fn __getitem__<'py>(&self, py: Python<'py>, index: Index<'py>) -> PyResult<Bound<'py, PyAny>> {
+        match index {
+            Index::Position(position) => {
+                let index = if position < 0 {
+                    position + self.len as isize
+                } else {
+                    position
+                };
+                if index < 0 || index >= self.len as isize {
+                    return Err(PyIndexError::new_err("list index out of range"));
+                }
+
+                self.source.get(py, self.position(index as usize))
+            }
+            Index::Slice(slice) => {
+                let indices = slice.indices(self.len as isize)?;
+                let view = ListView {
+                    source: self.source.clone_ref(py),
+                    start: self.start + indices.start * self.step,
+                    step: self.step * indices.step,
+                    len: indices.slicelength,
+                };
+
+                Ok(Bound::new(py, view)?.into_any())
+            }
+        }
+    }
+
+    fn __iter__(slf: Bound<'_, Self>) -> ListViewIterator {
+        ListViewIterator {
+            view: slf.unbind(),
+            next: 0,
+        }
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!("ListView({})", self.to_list(py)?.repr()?))
+    }
+
+    /// Copy the items into a list
+    fn to_list<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let items = (0..self.len)
+            .map(|index| self.source.get(py, self.position(index)))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        PyList::new(py, items)
+    }
+}
+
+#[pyclass(module = "prelude_parser._prelude_parser")]
+pub struct ListViewIterator {
+    view: Py<ListView>,
+    next: usize,
+}
+
+#[pymethods]
+impl ListViewIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let view = self.view.get();
+        if self.next >= view.len {
+            return Ok(None);
+        }
+
+        let item = view.source.get(py, view.position(self.next))?;
+        self.next += 1;
+        Ok(Some(item))
+    }
+}
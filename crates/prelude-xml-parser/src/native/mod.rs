@@ -1,5 +1,12 @@
+pub mod borrowed;
 pub mod common;
 pub(crate) mod deserializers;
+#[cfg(feature = "python")]
+pub mod list_view;
+#[cfg(feature = "python")]
+mod python;
 pub mod site_native;
 pub mod subject_native;
 pub mod user_native;
+
+pub use deserializers::parse_datetime;
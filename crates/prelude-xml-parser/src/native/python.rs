@@ -0,0 +1,381 @@
+//! What the Python classes of the native models share: their `__repr__`s, their `to_dict`s,
+//! pickling, loading from JSON and dicts, and the positions the exports look their records up by.
+//!
+//! A model is pickled as its JSON, the same the `to_json` methods give, and unpickled by the
+//! class's `_unpickle`, so pickling goes through the serde implementations the models already
+//! have. `from_dict` goes the same way, turning the dict into JSON first.
+
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use pyo3::{
+    exceptions::PyValueError,
+    intern,
+    prelude::*,
+    types::{PyBool, PyBytes, PyDateTime, PyDict, PyFloat, PyInt, PyString},
+    IntoPyObjectExt, PyClass,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::native::{
+    deserializers::{from_py_datetime, to_py_datetime},
+    site_native::Site,
+    subject_native::Patient,
+    user_native::User,
+};
+
+/// Builds a `__repr__` in the style of a dataclass's, e.g. `Site(name='Some Site', forms=<2 items>)`.
+pub(crate) struct Repr<'py> {
+    py: Python<'py>,
+    repr: String,
+    empty: bool,
+}
+
+impl<'py> Repr<'py> {
+    pub(crate) fn new(py: Python<'py>, class: &str) -> Self {
+        Repr {
+            py,
+            repr: format!("{class}("),
+            empty: true,
+        }
+    }
+
+    fn name(&mut self, name: &str) {
+        if !self.empty {
+            self.repr.push_str(", ");
+        }
+        self.empty = false;
+        self.repr.push_str(name);
+        self.repr.push('=');
+    }
+
+    /// Add a field, written the way Python writes its value.
+    pub(crate) fn field<T>(mut self, name: &str, value: T) -> PyResult<Self>
+    where
+        T: IntoPyObject<'py>,
+    {
+        self.name(name);
+        let value = value.into_bound_py_any(self.py)?.repr()?;
+        self.repr.push_str(value.to_str()?);
+
+        Ok(self)
+    }
+
+    /// Add a list as the number of items in it, as printing them would bury the rest.
+    pub(crate) fn list(mut self, name: &str, len: Option<usize>) -> Self {
+        self.name(name);
+        match len {
+            Some(1) => self.repr.push_str("<1 item>"),
+            Some(len) => self.repr.push_str(&format!("<{len} items>")),
+            None => self.repr.push_str("None"),
+        }
+
+        self
+    }
+
+    pub(crate) fn finish(mut self) -> String {
+        self.repr.push(')');
+        self.repr
+    }
+}
+
+/// The `__reduce__` of a model: its class's `_unpickle`, and its JSON to call it with.
+pub(crate) fn reduce<'py, T>(
+    slf: &Bound<'py, T>,
+) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))>
+where
+    T: PyClass + Serialize,
+{
+    let state = serde_json::to_vec(&*slf.borrow())
+        .map_err(|e| PyValueError::new_err(format!("Error pickling: {e}")))?;
+
+    Ok((
+        slf.as_any().get_type().getattr("_unpickle")?,
+        (PyBytes::new(slf.py(), &state),),
+    ))
+}
+
+/// Rebuild a model from the JSON [`reduce`] pickled it as.
+pub(crate) fn unpickle<T>(state: &[u8]) -> PyResult<T>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_slice(state)
+        .map_err(|e| PyValueError::new_err(format!("Error unpickling: {e}")))
+}
+
+/// Build a model from the JSON its `to_json` gives.
+pub(crate) fn from_json<T>(json: &str) -> PyResult<T>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_str(json)
+        .map_err(|e| PyValueError::new_err(format!("Error loading JSON: {e}")))
+}
+
+/// Build a model from the dict its `to_dict` gives.
+///
+/// Nested models in the dict can be dicts or the model objects themselves, and lists can be any
+/// iterable, such as a [`ListView`](crate::native::list_view::ListView).
+pub(crate) fn from_dict<T>(dict: &Bound<'_, PyDict>) -> PyResult<T>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_value(to_json_value(dict.as_any())?)
+        .map_err(|e| PyValueError::new_err(format!("Error loading dict: {e}")))
+}
+
+fn to_json_value(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if value.is_none() {
+        return Ok(Value::Null);
+    }
+    if let Ok(dict) = value.cast::<PyDict>() {
+        let mut object = Map::new();
+        for (key, item) in dict {
+            object.insert(key.extract()?, to_json_value(&item)?);
+        }
+        return Ok(Value::Object(object));
+    }
+    if let Ok(text) = value.cast::<PyString>() {
+        return Ok(Value::String(text.to_str()?.to_string()));
+    }
+    // `bool` is a subclass of `int`, so has to be checked first.
+    if let Ok(flag) = value.cast::<PyBool>() {
+        return Ok(Value::Bool(flag.is_true()));
+    }
+    if let Ok(number) = value.cast::<PyInt>() {
+        return Ok(match number.extract::<i64>() {
+            Ok(number) => number.into(),
+            Err(_) => number.extract::<u64>()?.into(),
+        });
+    }
+    if let Ok(number) = value.cast::<PyFloat>() {
+        return Number::from_f64(number.value())
+            .map(Value::Number)
+            .ok_or_else(|| PyValueError::new_err(format!("{number} can not be loaded")));
+    }
+    if let Ok(date_time) = value.cast::<PyDateTime>() {
+        let date_time = from_py_datetime(date_time)?;
+        return Ok(Value::String(
+            date_time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        ));
+    }
+    if value.hasattr("to_dict")? {
+        return to_json_value(&value.call_method0("to_dict")?);
+    }
+
+    value
+        .try_iter()?
+        .map(|item| to_json_value(&item?))
+        .collect::<PyResult<Vec<_>>>()
+        .map(Value::Array)
+}
+
+/// A model with a `to_dict`, made by [`to_dict`].
+pub(crate) trait ToDict {
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>>;
+}
+
+/// A field as a model's `to_dict` gives it, where nested models are dicts too.
+pub(crate) trait ToDictValue {
+    fn to_dict_value<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>>;
+}
+
+impl ToDictValue for Arc<str> {
+    fn to_dict_value<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        (**self).into_bound_py_any(py)
+    }
+}
+
+impl ToDictValue for String {
+    fn to_dict_value<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.as_str().into_bound_py_any(py)
+    }
+}
+
+impl ToDictValue for bool {
+    fn to_dict_value<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.into_bound_py_any(py)
+    }
+}
+
+impl ToDictValue for usize {
+    fn to_dict_value<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.into_bound_py_any(py)
+    }
+}
+
+impl ToDictValue for DateTime<Utc> {
+    fn to_dict_value<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(to_py_datetime(py, self)?.into_any())
+    }
+}
+
+impl<T: ToDictValue> ToDictValue for Option<T> {
+    fn to_dict_value<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        match self {
+            Some(value) => value.to_dict_value(py),
+            None => Ok(py.None().into_bound(py)),
+        }
+    }
+}
+
+impl<T: ToDict> ToDictValue for T {
+    fn to_dict_value<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(self.to_dict(py)?.into_any())
+    }
+}
+
+impl<T: ToDict> ToDictValue for Vec<T> {
+    fn to_dict_value<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.iter()
+            .map(|item| item.to_dict(py))
+            .collect::<PyResult<Vec<_>>>()?
+            .into_bound_py_any(py)
+    }
+}
+
+impl<T: ToDict> ToDictValue for Arc<Vec<T>> {
+    fn to_dict_value<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        (**self).to_dict_value(py)
+    }
+}
+
+/// Implements [`ToDict`] for a model from the names of its fields, each a key of the dict.
+///
+/// The model is taken apart by the names given, so a field left out is a compile error rather
+/// than a key missing from the dict.
+macro_rules! to_dict {
+    ($model:ident { $($field:ident),* $(,)? }) => {
+        impl $crate::native::python::ToDict for $model {
+            fn to_dict<'py>(
+                &self,
+                py: ::pyo3::Python<'py>,
+            ) -> ::pyo3::PyResult<::pyo3::Bound<'py, ::pyo3::types::PyDict>> {
+                let $model { $($field),* } = self;
+                let dict = ::pyo3::types::PyDict::new(py);
+                $(
+                    dict.set_item(
+                        stringify!($field),
+                        $crate::native::python::ToDictValue::to_dict_value($field, py)?,
+                    )?;
+                )*
+
+                Ok(dict)
+            }
+        }
+    };
+}
+
+pub(crate) use to_dict;
+
+/// Gets the positions of an export's records, building them the first time they are needed.
+///
+/// Python cannot hold an index that borrows from an export, so the exports keep the positions of
+/// their records instead, in the `__dict__` of their Python object. That keeps them out of the
+/// models' values: they are not compared, hashed, pickled or copied to Rust. Exports cannot be
+/// changed from Python, so the positions never go stale.
+pub(crate) fn positions<'py, T>(
+    slf: &Bound<'py, PyAny>,
+    build: impl FnOnce() -> T,
+) -> PyResult<Bound<'py, T>>
+where
+    T: PyClass + Into<PyClassInitializer<T>>,
+{
+    let py = slf.py();
+    let dict = slf
+        .getattr(intern!(py, "__dict__"))?
+        .cast_into::<PyDict>()?;
+    let key = intern!(py, "_positions");
+
+    if let Some(positions) = dict.get_item(key)? {
+        return Ok(positions.cast_into::<T>()?);
+    }
+
+    let positions = Bound::new(py, build())?;
+    dict.set_item(key, &positions)?;
+    Ok(positions)
+}
+
+/// The positions of a subject export's patients, as [`SubjectIndex`](crate::index::SubjectIndex) has the patients.
+#[derive(Debug, Default)]
+#[pyclass(frozen)]
+pub(crate) struct SubjectPositions {
+    pub(crate) by_patient_id: HashMap<String, usize>,
+    pub(crate) by_unique_id: HashMap<String, usize>,
+    pub(crate) by_site: HashMap<String, Vec<usize>>,
+}
+
+impl SubjectPositions {
+    pub(crate) fn new(patients: &[Patient]) -> Self {
+        let mut positions = SubjectPositions::default();
+
+        for (position, patient) in patients.iter().enumerate() {
+            positions
+                .by_patient_id
+                .entry(patient.patient_id.clone())
+                .or_insert(position);
+            positions
+                .by_unique_id
+                .entry(patient.unique_id.clone())
+                .or_insert(position);
+            positions
+                .by_site
+                .entry(patient.site_name.clone())
+                .or_default()
+                .push(position);
+        }
+
+        positions
+    }
+}
+
+/// The positions of a site export's sites, as [`SiteIndex`](crate::index::SiteIndex) has the sites.
+#[derive(Debug, Default)]
+#[pyclass(frozen)]
+pub(crate) struct SitePositions {
+    pub(crate) by_name: HashMap<String, usize>,
+    pub(crate) by_unique_id: HashMap<String, usize>,
+}
+
+impl SitePositions {
+    pub(crate) fn new(sites: &[Site]) -> Self {
+        let mut positions = SitePositions::default();
+
+        for (position, site) in sites.iter().enumerate() {
+            positions
+                .by_name
+                .entry(site.name.clone())
+                .or_insert(position);
+            positions
+                .by_unique_id
+                .entry(site.unique_id.clone())
+                .or_insert(position);
+        }
+
+        positions
+    }
+}
+
+/// The positions of a user export's users, as [`UserIndex`](crate::index::UserIndex) has the users.
+#[derive(Debug, Default)]
+#[pyclass(frozen)]
+pub(crate) struct UserPositions {
+    pub(crate) by_unique_id: HashMap<String, usize>,
+}
+
+impl UserPositions {
+    pub(crate) fn new(users: &[User]) -> Self {
+        let mut positions = UserPositions::default();
+
+        for (position, user) in users.iter().enumerate() {
+            positions
+                .by_unique_id
+                .entry(user.unique_id.clone())
+                .or_insert(position);
+        }
+
+        positions
+    }
+}
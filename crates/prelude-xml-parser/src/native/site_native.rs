@@ -4,107 +4,40 @@ use chrono::{DateTime, Utc};
 
 #[cfg(feature = "python")]
 use pyo3::{
-    exceptions::PyValueError,
+    exceptions::{PyKeyError, PyValueError},
     prelude::*,
-    types::{PyDateTime, PyDict},
+    types::{PyBytes, PyDateTime, PyDict},
 };
 
 use serde::{Deserialize, Serialize};
 
+use crate::index::SiteIndex;
+
 pub use crate::native::common::{
     Category, Comment, Entry, Export, Field, Form, Reason, State, Value,
 };
 
-use quick_xml::events::BytesStart;
-
-use crate::native::deserializers::{checked_datetime, required_attribute, visit_attributes};
-
 #[cfg(feature = "python")]
-use crate::native::deserializers::to_py_datetime;
-
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[serde(rename_all = "camelCase")]
-pub struct Site {
-    #[serde(alias = "@name")]
-    #[serde(alias = "name")]
-    pub name: String,
-    #[serde(rename = "uniqueId")]
-    #[serde(alias = "@uniqueId")]
-    #[serde(alias = "uniqueId")]
-    pub unique_id: String,
-    #[serde(rename = "numberOfPatients")]
-    #[serde(alias = "@numberOfPatients")]
-    #[serde(alias = "numberOfPatients")]
-    pub number_of_patients: usize,
-    #[serde(rename = "countOfRandomizedPatients")]
-    #[serde(alias = "@countOfRandomizedPatients")]
-    #[serde(alias = "countOfRandomizedPatients")]
-    pub count_of_randomized_patients: usize,
-    #[serde(rename = "whenCreated")]
-    #[serde(alias = "@whenCreated")]
-    #[serde(alias = "whenCreated")]
-    pub when_created: Option<DateTime<Utc>>,
-    #[serde(alias = "@creator")]
-    #[serde(alias = "creator")]
-    pub creator: String,
-    #[serde(rename = "numberOfForms")]
-    #[serde(alias = "@numberOfForms")]
-    #[serde(alias = "numberOfForms")]
-    pub number_of_forms: usize,
-
-    #[serde(rename = "form")]
-    #[serde(alias = "form")]
-    pub forms: Option<Arc<Vec<Form>>>,
-}
-
-#[cfg(not(feature = "python"))]
-impl Site {
-    pub(crate) fn from_attributes(e: &BytesStart<'_>) -> Result<Self, crate::errors::Error> {
-        let mut name: Option<&str> = None;
-        let mut unique_id: Option<&str> = None;
-        let mut number_of_patients = "";
-        let mut count_of_randomized_patients = "";
-        let mut when_created = "";
-        let mut creator: Option<&str> = None;
-        let mut number_of_forms = "";
-
-        visit_attributes(e, |key, attr| match key {
-            b"name" => name = Some(attr),
-            b"uniqueId" => unique_id = Some(attr),
-            b"numberOfPatients" => number_of_patients = attr,
-            b"countOfRandomizedPatients" => count_of_randomized_patients = attr,
-            b"whenCreated" => when_created = attr,
-            b"creator" => creator = Some(attr),
-            b"numberOfForms" => number_of_forms = attr,
-            _ => {}
-        })?;
-
-        Ok(Site {
-            name: required_attribute(name, "name")?,
-            unique_id: required_attribute(unique_id, "uniqueId")?,
-            number_of_patients: number_of_patients.parse().unwrap_or(0),
-            count_of_randomized_patients: count_of_randomized_patients.parse().unwrap_or(0),
-            when_created: checked_datetime(when_created)?,
-            creator: required_attribute(creator, "creator")?,
-            number_of_forms: number_of_forms.parse().unwrap_or(0),
-            forms: None,
-        })
-    }
-
-    pub(crate) fn set_forms(&mut self, forms: Vec<Form>) {
-        self.forms = if forms.is_empty() {
-            None
-        } else {
-            Some(Arc::new(forms))
-        };
-    }
-}
+use crate::native::{
+    deserializers::to_py_datetime,
+    list_view::{ListView, Source},
+    python::{
+        from_dict, from_json, positions, reduce, to_dict, unpickle, Repr, SitePositions, ToDict,
+    },
+};
 
-#[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
-#[pyclass(skip_from_py_object)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct Site {
     #[serde(alias = "@name")]
     #[serde(alias = "name")]
@@ -112,18 +45,22 @@ pub struct Site {
     #[serde(rename = "uniqueId")]
     #[serde(alias = "@uniqueId")]
     #[serde(alias = "uniqueId")]
+    #[serde(alias = "unique_id")]
     pub unique_id: String,
     #[serde(rename = "numberOfPatients")]
     #[serde(alias = "@numberOfPatients")]
     #[serde(alias = "numberOfPatients")]
+    #[serde(alias = "number_of_patients")]
     pub number_of_patients: usize,
     #[serde(rename = "countOfRandomizedPatients")]
     #[serde(alias = "@countOfRandomizedPatients")]
     #[serde(alias = "countOfRandomizedPatients")]
+    #[serde(alias = "count_of_randomized_patients")]
     pub count_of_randomized_patients: usize,
     #[serde(rename = "whenCreated")]
     #[serde(alias = "@whenCreated")]
     #[serde(alias = "whenCreated")]
+    #[serde(alias = "when_created")]
     pub when_created: Option<DateTime<Utc>>,
     #[serde(alias = "@creator")]
     #[serde(alias = "creator")]
@@ -131,55 +68,26 @@ pub struct Site {
     #[serde(rename = "numberOfForms")]
     #[serde(alias = "@numberOfForms")]
     #[serde(alias = "numberOfForms")]
+    #[serde(alias = "number_of_forms")]
     pub number_of_forms: usize,
 
     #[serde(rename = "form")]
     #[serde(alias = "form")]
+    #[serde(alias = "forms")]
     pub forms: Option<Arc<Vec<Form>>>,
 }
 
 #[cfg(feature = "python")]
-impl Site {
-    pub(crate) fn from_attributes(e: &BytesStart<'_>) -> Result<Self, crate::errors::Error> {
-        let mut name: Option<&str> = None;
-        let mut unique_id: Option<&str> = None;
-        let mut number_of_patients = "";
-        let mut count_of_randomized_patients = "";
-        let mut when_created = "";
-        let mut creator: Option<&str> = None;
-        let mut number_of_forms = "";
-
-        visit_attributes(e, |key, attr| match key {
-            b"name" => name = Some(attr),
-            b"uniqueId" => unique_id = Some(attr),
-            b"numberOfPatients" => number_of_patients = attr,
-            b"countOfRandomizedPatients" => count_of_randomized_patients = attr,
-            b"whenCreated" => when_created = attr,
-            b"creator" => creator = Some(attr),
-            b"numberOfForms" => number_of_forms = attr,
-            _ => {}
-        })?;
-
-        Ok(Site {
-            name: required_attribute(name, "name")?,
-            unique_id: required_attribute(unique_id, "uniqueId")?,
-            number_of_patients: number_of_patients.parse().unwrap_or(0),
-            count_of_randomized_patients: count_of_randomized_patients.parse().unwrap_or(0),
-            when_created: checked_datetime(when_created)?,
-            creator: required_attribute(creator, "creator")?,
-            number_of_forms: number_of_forms.parse().unwrap_or(0),
-            forms: None,
-        })
-    }
-
-    pub(crate) fn set_forms(&mut self, forms: Vec<Form>) {
-        self.forms = if forms.is_empty() {
-            None
-        } else {
-            Some(Arc::new(forms))
-        };
-    }
-}
+to_dict!(Site {
+    name,
+    unique_id,
+    number_of_patients,
+    count_of_randomized_patients,
+    when_created,
+    creator,
+    number_of_forms,
+    forms
+});
 
 #[cfg(feature = "python")]
 #[pymethods]
@@ -223,48 +131,57 @@ impl Site {
     }
 
     #[getter]
-    fn forms(&self) -> PyResult<Option<Vec<Form>>> {
-        Ok(self.forms.as_deref().cloned())
+    fn forms(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .forms
+            .clone()
+            .map(|items| ListView::new(py, Source::Forms(items))))
     }
 
-    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("name", &self.name)?;
-        dict.set_item("unique_id", &self.unique_id)?;
-        dict.set_item("number_of_patients", self.number_of_patients)?;
-        dict.set_item(
-            "count_of_randomized_patients",
-            self.count_of_randomized_patients,
-        )?;
-        dict.set_item(
-            "when_created",
-            self.when_created
-                .as_ref()
-                .map(|dt| to_py_datetime(py, dt))
-                .transpose()?,
-        )?;
-        dict.set_item("creator", &self.creator)?;
-        dict.set_item("number_of_forms", self.number_of_forms)?;
-
-        let mut form_dicts = Vec::new();
-        if let Some(forms) = &self.forms {
-            for form in forms.iter() {
-                let form_dict = form.to_dict(py)?;
-                form_dicts.push(form_dict);
-            }
-            dict.set_item("forms", form_dicts)?;
-        } else {
-            dict.set_item("forms", py.None())?;
-        }
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "Site")
+            .field("name", &*self.name)?
+            .field("unique_id", &*self.unique_id)?
+            .list("forms", self.forms.as_ref().map(|items| items.len()))
+            .finish())
+    }
+
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
+
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
+
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
 
-        Ok(dict)
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ToDict::to_dict(self, py)
     }
 }
 
-#[cfg(not(feature = "python"))]
 /// Contains the information from the Prelude native site XML.
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        dict,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct SiteNative {
     #[serde(default)]
     pub export: Option<Export>,
@@ -300,19 +217,36 @@ impl SiteNative {
     }
 }
 
-#[cfg(feature = "python")]
-/// Contains the information from the Prelude native site XML.
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[serde(rename_all = "camelCase")]
-#[pyclass(get_all, skip_from_py_object)]
-pub struct SiteNative {
-    #[serde(default)]
-    pub export: Option<Export>,
+impl SiteNative {
+    /// Build lookups of the sites by id, name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use prelude_xml_parser::parse_site_native_file;
+    ///
+    /// let native = parse_site_native_file(Path::new("tests/assets/site_native_small.xml")).unwrap();
+    /// let index = native.index();
+    ///
+    /// assert_eq!(index.by_name["Some Site"].unique_id, "1681574834910");
+    /// ```
+    pub fn index(&self) -> SiteIndex<'_> {
+        SiteIndex::new(&self.sites)
+    }
+}
 
-    #[serde(alias = "site")]
-    pub sites: Vec<Site>,
+#[cfg(feature = "python")]
+impl SiteNative {
+    fn positions<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, SitePositions>> {
+        positions(slf.as_any(), || SitePositions::new(&slf.get().sites))
+    }
 }
 
+#[cfg(feature = "python")]
+to_dict!(SiteNative { export, sites });
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl SiteNative {
@@ -322,24 +256,57 @@ impl SiteNative {
     }
 
     #[getter]
-    fn sites(&self) -> PyResult<Vec<Site>> {
-        Ok(self.sites.clone())
+    fn sites(slf: Bound<'_, Self>) -> PyResult<ListView> {
+        Ok(ListView::new(slf.py(), Source::Sites(slf.unbind())))
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "SiteNative")
+            .field("export", self.export.clone())?
+            .list("sites", Some(self.sites.len()))
+            .finish())
+    }
+
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
+
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
+
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
     }
 
     /// Convert the class instance to a dictionary
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        let mut site_dicts = Vec::new();
-        for site in &self.sites {
-            let site_dict = site.to_dict(py)?;
-            site_dicts.push(site_dict);
-        }
-        match &self.export {
-            Some(export) => dict.set_item("export", export.to_dict(py)?)?,
-            None => dict.set_item("export", py.None())?,
-        }
-        dict.set_item("sites", site_dicts)?;
-        Ok(dict)
+        ToDict::to_dict(self, py)
+    }
+
+    /// Look up a site by `name`
+    fn __getitem__(slf: &Bound<'_, Self>, name: &str) -> PyResult<Site> {
+        Self::positions(slf)?
+            .get()
+            .by_name
+            .get(name)
+            .map(|&position| slf.get().sites[position].clone())
+            .ok_or_else(|| PyErr::new::<PyKeyError, _>(name.to_string()))
+    }
+
+    /// Look up a site by `unique_id`
+    fn by_unique_id(slf: &Bound<'_, Self>, unique_id: &str) -> PyResult<Site> {
+        Self::positions(slf)?
+            .get()
+            .by_unique_id
+            .get(unique_id)
+            .map(|&position| slf.get().sites[position].clone())
+            .ok_or_else(|| PyErr::new::<PyKeyError, _>(unique_id.to_string()))
     }
 
     /// Convert the class instance to a JSON string
@@ -347,6 +314,18 @@ impl SiteNative {
         serde_json::to_string(&self)
             .map_err(|_| PyErr::new::<PyValueError, _>("Error converting to JSON"))
     }
+
+    /// Create the class instance from a JSON string such as the one `to_json` gives
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        from_json(json)
+    }
+
+    /// Find the nodes matching a selector such as `form[name=demographics]/category/field`
+    #[pyo3(name = "select")]
+    fn select_matches(&self, expression: &str) -> PyResult<Vec<crate::selector::PyMatch>> {
+        crate::selector::py_select(self, expression)
+    }
 }
 
 #[cfg(test)]
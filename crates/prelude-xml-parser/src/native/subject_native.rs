@@ -1,125 +1,72 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
 
 use chrono::{DateTime, Utc};
 
 #[cfg(feature = "python")]
 use pyo3::{
-    exceptions::PyValueError,
+    exceptions::{PyKeyError, PyValueError},
     prelude::*,
-    types::{PyDateTime, PyDict},
+    types::{PyBytes, PyDateTime, PyDict},
 };
 
-use quick_xml::events::BytesStart;
+#[cfg(feature = "python")]
+use crate::native::{
+    list_view::{ListView, Source},
+    python::{
+        from_dict, from_json, positions, reduce, to_dict, unpickle, Repr, SubjectPositions, ToDict,
+    },
+};
 
 use crate::native::deserializers::{
-    checked_datetime, deserialize_empty_string_as_none_datetime, optional_string,
-    required_attribute, visit_attributes,
+    deserialize_empty_string_as_none, deserialize_empty_string_as_none_datetime,
 };
 
 #[cfg(feature = "python")]
-use crate::native::deserializers::{deserialize_empty_string_as_none, to_py_datetime};
+use crate::native::deserializers::to_py_datetime;
 
 use serde::{Deserialize, Serialize};
 
+use crate::{errors::Error, index::SubjectIndex};
+
 pub use crate::native::common::{
     Category, Comment, Entry, Export, Field, Form, Reason, State, Value,
 };
 
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct Patient {
-    #[serde(rename = "patientId")]
-    pub patient_id: String,
-    #[serde(rename = "uniqueId")]
-    pub unique_id: String,
-    #[serde(rename = "whenCreated")]
-    pub when_created: Option<DateTime<Utc>>,
-    #[serde(rename = "passwordChangeDate")]
-    #[serde(
-        default,
-        deserialize_with = "deserialize_empty_string_as_none_datetime"
-    )]
-    pub password_change_date: Option<DateTime<Utc>>,
-    pub creator: String,
-    #[serde(rename = "siteName")]
-    pub site_name: String,
-    #[serde(rename = "siteUniqueId")]
-    pub site_unique_id: String,
-    #[serde(rename = "lastLanguage")]
-    pub last_language: Option<String>,
-    #[serde(rename = "numberOfForms")]
-    pub number_of_forms: usize,
-    pub forms: Option<Arc<Vec<Form>>>,
-}
-
-impl Patient {
-    pub(crate) fn from_attributes(e: &BytesStart<'_>) -> Result<Self, crate::errors::Error> {
-        let mut patient_id: Option<&str> = None;
-        let mut unique_id: Option<&str> = None;
-        let mut when_created = "";
-        let mut password_change_date = "";
-        let mut creator: Option<&str> = None;
-        let mut site_name: Option<&str> = None;
-        let mut site_unique_id: Option<&str> = None;
-        let mut last_language = "";
-        let mut number_of_forms = "";
-
-        visit_attributes(e, |key, attr| match key {
-            b"patientId" => patient_id = Some(attr),
-            b"uniqueId" => unique_id = Some(attr),
-            b"whenCreated" => when_created = attr,
-            b"passwordChangeDate" => password_change_date = attr,
-            b"creator" => creator = Some(attr),
-            b"siteName" => site_name = Some(attr),
-            b"siteUniqueId" => site_unique_id = Some(attr),
-            b"lastLanguage" => last_language = attr,
-            b"numberOfForms" => number_of_forms = attr,
-            _ => {}
-        })?;
-
-        Ok(Patient {
-            patient_id: required_attribute(patient_id, "patientId")?,
-            unique_id: required_attribute(unique_id, "uniqueId")?,
-            when_created: checked_datetime(when_created)?,
-            password_change_date: checked_datetime(password_change_date)?,
-            creator: required_attribute(creator, "creator")?,
-            site_name: required_attribute(site_name, "siteName")?,
-            site_unique_id: required_attribute(site_unique_id, "siteUniqueId")?,
-            last_language: optional_string(last_language),
-            number_of_forms: number_of_forms.parse().unwrap_or(0),
-            forms: None,
-        })
-    }
-
-    pub(crate) fn set_forms(&mut self, forms: Vec<Form>) {
-        self.forms = if forms.is_empty() {
-            None
-        } else {
-            Some(Arc::new(forms))
-        };
-    }
-}
-
-#[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
-#[pyclass(skip_from_py_object)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct Patient {
     #[serde(rename = "patientId")]
     #[serde(alias = "@patientId")]
     #[serde(alias = "patientId")]
+    #[serde(alias = "patient_id")]
     pub patient_id: String,
     #[serde(rename = "uniqueId")]
     #[serde(alias = "@uniqueId")]
     #[serde(alias = "uniqueId")]
+    #[serde(alias = "unique_id")]
     pub unique_id: String,
     #[serde(rename = "whenCreated")]
     #[serde(alias = "@whenCreated")]
     #[serde(alias = "whenCreated")]
+    #[serde(alias = "when_created")]
     pub when_created: Option<DateTime<Utc>>,
     #[serde(rename = "passwordChangeDate")]
     #[serde(alias = "@passwordChangeDate")]
     #[serde(alias = "passwordChangeDate")]
+    #[serde(alias = "password_change_date")]
     #[serde(
         default,
         deserialize_with = "deserialize_empty_string_as_none_datetime"
@@ -133,27 +80,45 @@ pub struct Patient {
     #[serde(rename = "siteName")]
     #[serde(alias = "@siteName")]
     #[serde(alias = "siteName")]
+    #[serde(alias = "site_name")]
     pub site_name: String,
     #[serde(rename = "siteUniqueId")]
     #[serde(alias = "@siteUniqueId")]
     #[serde(alias = "siteUniqueId")]
+    #[serde(alias = "site_unique_id")]
     pub site_unique_id: String,
 
     #[serde(rename = "lastLanguage")]
     #[serde(alias = "@lastLanguage")]
     #[serde(alias = "lastLanguage")]
+    #[serde(alias = "last_language")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub last_language: Option<String>,
 
     #[serde(rename = "numberOfForms")]
     #[serde(alias = "@numberOfForms")]
     #[serde(alias = "numberOfForms")]
+    #[serde(alias = "number_of_forms")]
     pub number_of_forms: usize,
 
     #[serde(alias = "form")]
     pub forms: Option<Arc<Vec<Form>>>,
 }
 
+#[cfg(feature = "python")]
+to_dict!(Patient {
+    patient_id,
+    unique_id,
+    when_created,
+    password_change_date,
+    creator,
+    site_name,
+    site_unique_id,
+    last_language,
+    number_of_forms,
+    forms
+});
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl Patient {
@@ -212,55 +177,63 @@ impl Patient {
     }
 
     #[getter]
-    fn forms(&self) -> PyResult<Option<Vec<Form>>> {
-        Ok(self.forms.as_deref().cloned())
+    fn forms(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .forms
+            .clone()
+            .map(|items| ListView::new(py, Source::Forms(items))))
     }
 
-    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("patient_id", &self.patient_id)?;
-        dict.set_item("unique_id", &self.unique_id)?;
-        dict.set_item(
-            "when_created",
-            self.when_created
-                .as_ref()
-                .map(|dt| to_py_datetime(py, dt))
-                .transpose()?,
-        )?;
-        dict.set_item(
-            "password_change_date",
-            self.password_change_date
-                .as_ref()
-                .map(|dt| to_py_datetime(py, dt))
-                .transpose()?,
-        )?;
-        dict.set_item("creator", &self.creator)?;
-        dict.set_item("site_name", &self.site_name)?;
-        dict.set_item("site_unique_id", &self.site_unique_id)?;
-        dict.set_item("last_language", &self.last_language)?;
-        dict.set_item("number_of_forms", self.number_of_forms)?;
-
-        let mut form_dicts = Vec::new();
-        if let Some(forms) = &self.forms {
-            for form in forms.iter() {
-                let form_dict = form.to_dict(py)?;
-                form_dicts.push(form_dict);
-            }
-            dict.set_item("forms", form_dicts)?;
-        } else {
-            dict.set_item("forms", py.None())?;
-        }
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "Patient")
+            .field("patient_id", &*self.patient_id)?
+            .field("unique_id", &*self.unique_id)?
+            .field("site_name", &*self.site_name)?
+            .list("forms", self.forms.as_ref().map(|items| items.len()))
+            .finish())
+    }
 
-        Ok(dict)
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
+
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
+
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
+
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ToDict::to_dict(self, py)
     }
 }
 
-#[cfg(not(feature = "python"))]
 /// Contains the information from the Prelude native subject XML.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        dict,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct SubjectNative {
     #[serde(default)]
     pub export: Option<Export>,
+
+    #[serde(alias = "patient")]
     pub patients: Vec<Patient>,
 }
 
@@ -290,19 +263,172 @@ impl SubjectNative {
     }
 }
 
-#[cfg(feature = "python")]
-/// Contains the information from the Prelude native subject XML.
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[serde(rename_all = "camelCase")]
-#[pyclass(get_all, skip_from_py_object)]
-pub struct SubjectNative {
-    #[serde(default)]
-    pub export: Option<Export>,
+impl SubjectNative {
+    /// Build lookups of the patients by id and site.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use prelude_xml_parser::parse_subject_native_file;
+    ///
+    /// let native = parse_subject_native_file(Path::new("tests/assets/subject_native_small.xml")).unwrap();
+    /// let index = native.index();
+    ///
+    /// assert_eq!(index.by_patient_id["ABC-001"].unique_id, "1681574905819");
+    /// assert_eq!(index.by_site["Some Site"].len(), native.patients.len());
+    /// ```
+    pub fn index(&self) -> SubjectIndex<'_> {
+        SubjectIndex::new(&self.patients)
+    }
 
-    #[serde(alias = "patient")]
-    pub patients: Vec<Patient>,
+    /// Combine the pages of a split export into one.
+    ///
+    /// The pages may be given in any order. They have to come from the same export, going by its
+    /// date and creator, be numbered `1 of n` to `n of n` with none missing or repeated, and hold
+    /// no patient more than once. The result keeps the first page's header, without a page
+    /// number, and the patients of every page in page order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use prelude_xml_parser::{native::subject_native::SubjectNative, parse_subject_native_file};
+    ///
+    /// let whole = parse_subject_native_file(Path::new("tests/assets/subject_native.xml")).unwrap();
+    /// let (first, second) = whole.patients.split_at(1);
+    /// let page = |number: &str, patients: &[_]| {
+    ///     let mut page = whole.clone();
+    ///     page.export.as_mut().unwrap().page_number = Some(number.to_string());
+    ///     page.patients = patients.to_vec();
+    ///     page
+    /// };
+    ///
+    /// let merged = SubjectNative::merge([page("2 of 2", second), page("1 of 2", first)]).unwrap();
+    ///
+    /// assert_eq!(merged.patients, whole.patients);
+    /// assert!(SubjectNative::merge([page("2 of 2", second)]).is_err());
+    /// ```
+    pub fn merge(pages: impl IntoIterator<Item = SubjectNative>) -> Result<Self, Error> {
+        let mut pages = pages
+            .into_iter()
+            .map(|page| Ok((page_number(page.export.as_ref())?, page)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        pages.sort_by_key(|((number, _), _)| *number);
+
+        let Some((_, first)) = pages.first() else {
+            return Err(Error::InvalidPages("there are no pages".to_string()));
+        };
+        let export = first.export.clone().expect("numbered pages have a header");
+
+        let mut problems = Vec::new();
+        let totals: BTreeSet<usize> = pages.iter().filter_map(|((_, total), _)| *total).collect();
+        if totals.len() > 1 {
+            problems.push(format!(
+                "the pages disagree on how many there are: {totals:?}"
+            ));
+        }
+        let expected = totals
+            .last()
+            .copied()
+            .unwrap_or_else(|| pages.last().map_or(0, |((number, _), _)| *number));
+        let count = |n: usize| pages.iter().filter(|((number, _), _)| *number == n).count();
+        let missing: Vec<usize> = (1..=expected).filter(|&n| count(n) == 0).collect();
+        if !missing.is_empty() {
+            problems.push(format!("pages {missing:?} of {expected} are missing"));
+        }
+        let numbers: BTreeSet<usize> = pages.iter().map(|((number, _), _)| *number).collect();
+        let repeated: Vec<usize> = numbers.iter().copied().filter(|&n| count(n) > 1).collect();
+        if !repeated.is_empty() {
+            problems.push(format!("pages {repeated:?} were given more than once"));
+        }
+        let extra: Vec<usize> = numbers.range(expected + 1..).copied().collect();
+        if !extra.is_empty() {
+            problems.push(format!(
+                "pages {extra:?} are past the last page, {expected}"
+            ));
+        }
+
+        for ((number, _), page) in &pages {
+            let header = page.export.as_ref().expect("numbered pages have a header");
+            if header.date != export.date || header.created_by != export.created_by {
+                problems.push(format!(
+                    "page {number} is from a different export than page {}",
+                    pages[0].0 .0
+                ));
+            }
+        }
+
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        let mut patients = Vec::new();
+        for ((number, _), page) in pages {
+            for patient in page.patients {
+                match seen.get(&patient.unique_id) {
+                    Some(first_page) => problems.push(format!(
+                        "patient {} is on page {first_page} and page {number}",
+                        patient.patient_id
+                    )),
+                    None => {
+                        seen.insert(patient.unique_id.clone(), number);
+                        patients.push(patient);
+                    }
+                }
+            }
+        }
+
+        if !problems.is_empty() {
+            return Err(Error::InvalidPages(problems.join("; ")));
+        }
+
+        Ok(SubjectNative {
+            export: Some(Export {
+                page_number: None,
+                ..export
+            }),
+            patients,
+        })
+    }
 }
 
+/// The number of a page of a split export, and how many pages it says there are.
+fn page_number(export: Option<&Export>) -> Result<(usize, Option<usize>), Error> {
+    let page_number = export
+        .and_then(|export| export.page_number.as_deref())
+        .ok_or_else(|| Error::InvalidPages("a page has no page number".to_string()))?;
+    let invalid = || {
+        Error::InvalidPages(format!(
+            "{page_number:?} is not a page number like \"1 of 6\""
+        ))
+    };
+
+    let (number, total) = match page_number.split_once(" of ") {
+        Some((number, total)) => (number, Some(total)),
+        None => (page_number, None),
+    };
+    let number = number.trim().parse().map_err(|_| invalid())?;
+    let total = total
+        .map(|total| total.trim().parse())
+        .transpose()
+        .map_err(|_| invalid())?;
+
+    match number {
+        0 => Err(invalid()),
+        _ => Ok((number, total)),
+    }
+}
+
+#[cfg(feature = "python")]
+impl SubjectNative {
+    fn positions<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, SubjectPositions>> {
+        positions(slf.as_any(), || SubjectPositions::new(&slf.get().patients))
+    }
+}
+
+#[cfg(feature = "python")]
+to_dict!(SubjectNative { export, patients });
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl SubjectNative {
@@ -312,24 +438,80 @@ impl SubjectNative {
     }
 
     #[getter]
-    fn patients(&self) -> PyResult<Vec<Patient>> {
-        Ok(self.patients.clone())
+    fn patients(slf: Bound<'_, Self>) -> PyResult<ListView> {
+        Ok(ListView::new(slf.py(), Source::Patients(slf.unbind())))
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "SubjectNative")
+            .field("export", self.export.clone())?
+            .list("patients", Some(self.patients.len()))
+            .finish())
+    }
+
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
+
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
+
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
     }
 
     /// Convert the class instance to a dictionary
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        let mut patient_dicts = Vec::new();
-        for patient in &self.patients {
-            let patient_dict = patient.to_dict(py)?;
-            patient_dicts.push(patient_dict);
-        }
-        match &self.export {
-            Some(export) => dict.set_item("export", export.to_dict(py)?)?,
-            None => dict.set_item("export", py.None())?,
-        }
-        dict.set_item("patients", patient_dicts)?;
-        Ok(dict)
+        ToDict::to_dict(self, py)
+    }
+
+    /// Combine the pages of a split export into one
+    #[staticmethod]
+    #[pyo3(name = "merge")]
+    fn merge_pages(pages: Vec<PyRef<'_, SubjectNative>>) -> PyResult<SubjectNative> {
+        SubjectNative::merge(pages.iter().map(|page| (**page).clone()))
+            .map_err(|e| PyErr::new::<PyValueError, _>(e.to_string()))
+    }
+
+    /// Look up a patient by `patient_id`
+    fn __getitem__(slf: &Bound<'_, Self>, patient_id: &str) -> PyResult<Patient> {
+        Self::positions(slf)?
+            .get()
+            .by_patient_id
+            .get(patient_id)
+            .map(|&position| slf.get().patients[position].clone())
+            .ok_or_else(|| PyErr::new::<PyKeyError, _>(patient_id.to_string()))
+    }
+
+    /// Look up a patient by `unique_id`
+    fn by_unique_id(slf: &Bound<'_, Self>, unique_id: &str) -> PyResult<Patient> {
+        Self::positions(slf)?
+            .get()
+            .by_unique_id
+            .get(unique_id)
+            .map(|&position| slf.get().patients[position].clone())
+            .ok_or_else(|| PyErr::new::<PyKeyError, _>(unique_id.to_string()))
+    }
+
+    /// The patients of the site named `site_name`, in document order
+    fn by_site(slf: &Bound<'_, Self>, site_name: &str) -> PyResult<Vec<Patient>> {
+        Ok(Self::positions(slf)?
+            .get()
+            .by_site
+            .get(site_name)
+            .map(|positions| {
+                positions
+                    .iter()
+                    .map(|&position| slf.get().patients[position].clone())
+                    .collect()
+            })
+            .unwrap_or_default())
     }
 
     /// Convert the class instance to a JSON string
@@ -337,6 +519,18 @@ impl SubjectNative {
         serde_json::to_string(&self)
             .map_err(|_| PyErr::new::<PyValueError, _>("Error converting to JSON"))
     }
+
+    /// Create the class instance from a JSON string such as the one `to_json` gives
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        from_json(json)
+    }
+
+    /// Find the nodes matching a selector such as `form[name=demographics]/category/field`
+    #[pyo3(name = "select")]
+    fn select_matches(&self, expression: &str) -> PyResult<Vec<crate::selector::PyMatch>> {
+        crate::selector::py_select(self, expression)
+    }
 }
 
 #[cfg(test)]
@@ -345,6 +539,92 @@ mod tests {
 
     use super::*;
 
+    fn split_into_pages(numbers: &[&str]) -> (SubjectNative, Vec<SubjectNative>) {
+        let whole = crate::parse_subject_native_file(std::path::Path::new(
+            "tests/assets/subject_native.xml",
+        ))
+        .unwrap();
+        let count = whole.patients.len();
+        let pages = numbers
+            .iter()
+            .enumerate()
+            .map(|(i, number)| {
+                let mut page = whole.clone();
+                page.export.as_mut().unwrap().page_number = Some(number.to_string());
+                page.patients = whole.patients
+                    [i * count / numbers.len()..(i + 1) * count / numbers.len()]
+                    .to_vec();
+                page
+            })
+            .collect();
+
+        (whole, pages)
+    }
+
+    fn merge_error(pages: Vec<SubjectNative>) -> String {
+        match SubjectNative::merge(pages) {
+            Err(Error::InvalidPages(message)) => message,
+            other => panic!("expected the pages to be rejected, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn merge_orders_pages() {
+        let (whole, mut pages) = split_into_pages(&["1 of 3", "2 of 3", "3 of 3"]);
+        pages.reverse();
+
+        let merged = SubjectNative::merge(pages).unwrap();
+
+        assert_eq!(merged.patients, whole.patients);
+        assert_eq!(merged.export.unwrap().page_number, None);
+    }
+
+    #[test]
+    fn merge_reports_missing_and_repeated_pages() {
+        let (_, mut pages) = split_into_pages(&["1 of 3", "2 of 3", "3 of 3"]);
+        pages.remove(1);
+        assert_eq!(merge_error(pages), "pages [2] of 3 are missing");
+
+        let (_, mut pages) = split_into_pages(&["1 of 2", "2 of 2"]);
+        pages[1].export.as_mut().unwrap().page_number = Some("1 of 2".to_string());
+        let message = merge_error(pages);
+        assert!(message.contains("pages [2] of 2 are missing"), "{message}");
+        assert!(
+            message.contains("pages [1] were given more than once"),
+            "{message}"
+        );
+
+        let (_, pages) = split_into_pages(&["1 of 2", "3 of 2"]);
+        assert!(merge_error(pages).contains("pages [3] are past the last page, 2"));
+    }
+
+    #[test]
+    fn merge_reports_other_exports_and_repeated_patients() {
+        let (_, mut pages) = split_into_pages(&["1 of 2", "2 of 2"]);
+        pages[1].export.as_mut().unwrap().created_by = Some("Someone Else".to_string());
+        assert_eq!(
+            merge_error(pages),
+            "page 2 is from a different export than page 1"
+        );
+
+        let (whole, mut pages) = split_into_pages(&["1 of 2", "2 of 2"]);
+        pages[1].patients.push(whole.patients[0].clone());
+        assert_eq!(
+            merge_error(pages),
+            format!(
+                "patient {} is on page 1 and page 2",
+                whole.patients[0].patient_id
+            )
+        );
+
+        let (_, mut pages) = split_into_pages(&["1 of 2", "2 of 2"]);
+        pages[0].export.as_mut().unwrap().page_number = Some("first".to_string());
+        assert_eq!(
+            merge_error(pages),
+            "\"first\" is not a page number like \"1 of 6\""
+        );
+    }
+
     #[test]
     fn deserialize_subject_native_json() {
         let json_str = r#"{
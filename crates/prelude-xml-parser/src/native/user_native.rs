@@ -2,92 +2,52 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+use crate::index::UserIndex;
+
 #[cfg(feature = "python")]
-use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+use pyo3::{
+    exceptions::{PyKeyError, PyValueError},
+    prelude::*,
+    types::{PyBytes, PyDict},
+};
 
 pub use crate::native::common::{
     Category, Comment, Entry, Export, Field, Form, Reason, State, Value,
 };
-use quick_xml::events::BytesStart;
 
-use crate::native::deserializers::{
-    deserialize_empty_string_as_none, optional_string, required_attribute, visit_attributes,
+#[cfg(feature = "python")]
+use crate::native::{
+    list_view::{ListView, Source},
+    python::{
+        from_dict, from_json, positions, reduce, to_dict, unpickle, Repr, ToDict, UserPositions,
+    },
 };
 
-#[cfg(not(feature = "python"))]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[serde(rename_all = "camelCase")]
-pub struct User {
-    #[serde(rename = "uniqueId")]
-    #[serde(alias = "@uniqueId")]
-    #[serde(alias = "uniqueId")]
-    pub unique_id: String,
-
-    #[serde(rename = "lastLanguage")]
-    #[serde(alias = "@lastLanguage")]
-    #[serde(alias = "lastLanguage")]
-    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
-    pub last_language: Option<String>,
-    #[serde(rename = "creator")]
-    #[serde(alias = "@creator")]
-    #[serde(alias = "creator")]
-    pub creator: String,
-    #[serde(rename = "numberOfForms")]
-    #[serde(alias = "@numberOfForms")]
-    #[serde(alias = "numberOfForms")]
-    pub number_of_forms: usize,
+use crate::native::deserializers::deserialize_empty_string_as_none;
 
-    #[serde(alias = "form")]
-    pub forms: Option<Arc<Vec<Form>>>,
-}
-
-#[cfg(not(feature = "python"))]
-impl User {
-    pub(crate) fn from_attributes(e: &BytesStart<'_>) -> Result<Self, crate::errors::Error> {
-        let mut unique_id: Option<&str> = None;
-        let mut last_language = "";
-        let mut creator: Option<&str> = None;
-        let mut number_of_forms = "";
-
-        visit_attributes(e, |key, attr| match key {
-            b"uniqueId" => unique_id = Some(attr),
-            b"lastLanguage" => last_language = attr,
-            b"creator" => creator = Some(attr),
-            b"numberOfForms" => number_of_forms = attr,
-            _ => {}
-        })?;
-
-        Ok(User {
-            unique_id: required_attribute(unique_id, "uniqueId")?,
-            last_language: optional_string(last_language),
-            creator: required_attribute(creator, "creator")?,
-            number_of_forms: number_of_forms.parse().unwrap_or(0),
-            forms: None,
-        })
-    }
-
-    pub(crate) fn set_forms(&mut self, forms: Vec<Form>) {
-        self.forms = if forms.is_empty() {
-            None
-        } else {
-            Some(Arc::new(forms))
-        };
-    }
-}
-
-#[cfg(feature = "python")]
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
-#[pyclass(skip_from_py_object)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct User {
     #[serde(rename = "uniqueId")]
     #[serde(alias = "@uniqueId")]
     #[serde(alias = "uniqueId")]
+    #[serde(alias = "unique_id")]
     pub unique_id: String,
 
     #[serde(rename = "lastLanguage")]
     #[serde(alias = "@lastLanguage")]
     #[serde(alias = "lastLanguage")]
+    #[serde(alias = "last_language")]
     #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
     pub last_language: Option<String>,
     #[serde(rename = "creator")]
@@ -97,6 +57,7 @@ pub struct User {
     #[serde(rename = "numberOfForms")]
     #[serde(alias = "@numberOfForms")]
     #[serde(alias = "numberOfForms")]
+    #[serde(alias = "number_of_forms")]
     pub number_of_forms: usize,
 
     #[serde(alias = "form")]
@@ -104,38 +65,13 @@ pub struct User {
 }
 
 #[cfg(feature = "python")]
-impl User {
-    pub(crate) fn from_attributes(e: &BytesStart<'_>) -> Result<Self, crate::errors::Error> {
-        let mut unique_id: Option<&str> = None;
-        let mut last_language = "";
-        let mut creator: Option<&str> = None;
-        let mut number_of_forms = "";
-
-        visit_attributes(e, |key, attr| match key {
-            b"uniqueId" => unique_id = Some(attr),
-            b"lastLanguage" => last_language = attr,
-            b"creator" => creator = Some(attr),
-            b"numberOfForms" => number_of_forms = attr,
-            _ => {}
-        })?;
-
-        Ok(User {
-            unique_id: required_attribute(unique_id, "uniqueId")?,
-            last_language: optional_string(last_language),
-            creator: required_attribute(creator, "creator")?,
-            number_of_forms: number_of_forms.parse().unwrap_or(0),
-            forms: None,
-        })
-    }
-
-    pub(crate) fn set_forms(&mut self, forms: Vec<Form>) {
-        self.forms = if forms.is_empty() {
-            None
-        } else {
-            Some(Arc::new(forms))
-        };
-    }
-}
+to_dict!(User {
+    unique_id,
+    last_language,
+    creator,
+    number_of_forms,
+    forms
+});
 
 #[cfg(feature = "python")]
 #[pymethods]
@@ -161,36 +97,56 @@ impl User {
     }
 
     #[getter]
-    fn forms(&self) -> PyResult<Option<Vec<Form>>> {
-        Ok(self.forms.as_deref().cloned())
+    fn forms(&self, py: Python<'_>) -> PyResult<Option<ListView>> {
+        Ok(self
+            .forms
+            .clone()
+            .map(|items| ListView::new(py, Source::Forms(items))))
     }
 
-    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        dict.set_item("unique_id", &self.unique_id)?;
-        dict.set_item("last_language", &self.last_language)?;
-        dict.set_item("creator", &self.creator)?;
-        dict.set_item("number_of_forms", self.number_of_forms)?;
-
-        let mut form_dicts = Vec::new();
-        if let Some(forms) = &self.forms {
-            for form in forms.iter() {
-                let form_dict = form.to_dict(py)?;
-                form_dicts.push(form_dict);
-            }
-            dict.set_item("forms", form_dicts)?;
-        } else {
-            dict.set_item("forms", py.None())?;
-        }
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "User")
+            .field("unique_id", &*self.unique_id)?
+            .list("forms", self.forms.as_ref().map(|items| items.len()))
+            .finish())
+    }
+
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
+
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
+
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
+    }
 
-        Ok(dict)
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ToDict::to_dict(self, py)
     }
 }
 
-#[cfg(not(feature = "python"))]
 /// Contains the information from the Prelude native user XML.
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "prelude_parser._prelude_parser",
+        frozen,
+        dict,
+        eq,
+        hash,
+        skip_from_py_object
+    )
+)]
 pub struct UserNative {
     #[serde(default)]
     pub export: Option<Export>,
@@ -224,19 +180,36 @@ impl UserNative {
     }
 }
 
-#[cfg(feature = "python")]
-/// Contains the information from the Prelude native user XML.
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[serde(rename_all = "camelCase")]
-#[pyclass(get_all, skip_from_py_object)]
-pub struct UserNative {
-    #[serde(default)]
-    pub export: Option<Export>,
+impl UserNative {
+    /// Build lookups of the users by id.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use prelude_xml_parser::parse_user_native_file;
+    ///
+    /// let native = parse_user_native_file(Path::new("tests/assets/user_native_small.xml")).unwrap();
+    /// let index = native.index();
+    ///
+    /// assert!(index.by_unique_id.contains_key("1691421275437"));
+    /// ```
+    pub fn index(&self) -> UserIndex<'_> {
+        UserIndex::new(&self.users)
+    }
+}
 
-    #[serde(alias = "user")]
-    pub users: Vec<User>,
+#[cfg(feature = "python")]
+impl UserNative {
+    fn positions<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, UserPositions>> {
+        positions(slf.as_any(), || UserPositions::new(&slf.get().users))
+    }
 }
 
+#[cfg(feature = "python")]
+to_dict!(UserNative { export, users });
+
 #[cfg(feature = "python")]
 #[pymethods]
 impl UserNative {
@@ -246,24 +219,47 @@ impl UserNative {
     }
 
     #[getter]
-    fn users(&self) -> PyResult<Vec<User>> {
-        Ok(self.users.clone())
+    fn users(slf: Bound<'_, Self>) -> PyResult<ListView> {
+        Ok(ListView::new(slf.py(), Source::Users(slf.unbind())))
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(Repr::new(py, "UserNative")
+            .field("export", self.export.clone())?
+            .list("users", Some(self.users.len()))
+            .finish())
+    }
+
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        reduce(slf)
+    }
+
+    #[staticmethod]
+    fn _unpickle(state: &[u8]) -> PyResult<Self> {
+        unpickle(state)
+    }
+
+    /// Create the class instance from a dictionary such as the one `to_dict` gives
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        from_dict(data)
     }
 
     /// Convert the class instance to a dictionary
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
-        let dict = PyDict::new(py);
-        let mut user_dicts = Vec::new();
-        for user in &self.users {
-            let user_dict = user.to_dict(py)?;
-            user_dicts.push(user_dict);
-        }
-        match &self.export {
-            Some(export) => dict.set_item("export", export.to_dict(py)?)?,
-            None => dict.set_item("export", py.None())?,
-        }
-        dict.set_item("users", user_dicts)?;
-        Ok(dict)
+        ToDict::to_dict(self, py)
+    }
+
+    /// Look up a user by `unique_id`
+    fn __getitem__(slf: &Bound<'_, Self>, unique_id: &str) -> PyResult<User> {
+        Self::positions(slf)?
+            .get()
+            .by_unique_id
+            .get(unique_id)
+            .map(|&position| slf.get().users[position].clone())
+            .ok_or_else(|| PyErr::new::<PyKeyError, _>(unique_id.to_string()))
     }
 
     /// Convert the class instance to a JSON string
@@ -271,6 +267,18 @@ impl UserNative {
         serde_json::to_string(&self)
             .map_err(|_| PyErr::new::<PyValueError, _>("Error converting to JSON"))
     }
+
+    /// Create the class instance from a JSON string such as the one `to_json` gives
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        from_json(json)
+    }
+
+    /// Find the nodes matching a selector such as `form[name=demographics]/category/field`
+    #[pyo3(name = "select")]
+    fn select_matches(&self, expression: &str) -> PyResult<Vec<crate::selector::PyMatch>> {
+        crate::selector::py_select(self, expression)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,218 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use rayon::ThreadPool;
+
+/// How much of each field's audit trail to keep.
+///
+/// Prelude lists a field's entries newest first, so the first `entry` is the current value and
+/// every one after it is an earlier revision kept for the audit trail.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum History {
+    /// Keep every entry, including earlier revisions and their reasons.
+    #[default]
+    Full,
+
+    /// Keep only the current entry of each `Field` and `File`, dropping earlier revisions.
+    CurrentOnly,
+}
+
+/// How the records of an export are spread across threads.
+///
+/// Records are parsed independently, so by default they are shared out over rayon's global pool.
+/// That pool is shared with everything else in the process that uses rayon, which is not always
+/// wanted: a caller with its own rayon work, or one running inside worker processes that are
+/// already one per core, can confine parsing to a pool of its choosing or to the calling thread.
+#[derive(Clone, Debug, Default)]
+pub enum Parallelism {
+    /// Use rayon's global thread pool.
+    #[default]
+    Global,
+
+    /// Parse every record on the calling thread without involving rayon at all.
+    Sequential,
+
+    /// Build a dedicated pool with this many threads for each parse. One thread is the same as
+    /// [`Parallelism::Sequential`] and zero lets rayon pick, as it does for its global pool.
+    ///
+    /// The pool's threads are started and stopped by every parse. For many parses, build a pool
+    /// once and use [`Parallelism::Pool`] instead.
+    Threads(usize),
+
+    /// Run on a pool the caller already owns.
+    Pool(Arc<ThreadPool>),
+}
+
+impl PartialEq for Parallelism {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Parallelism::Global, Parallelism::Global) => true,
+            (Parallelism::Sequential, Parallelism::Sequential) => true,
+            (Parallelism::Threads(a), Parallelism::Threads(b)) => a == b,
+            (Parallelism::Pool(a), Parallelism::Pool(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Parallelism {}
+
+/// How the `parse_*_native_file` functions get at the contents of the file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FileInput {
+    /// Map the file into memory, so the OS page cache backs the document rather than a copy of it
+    /// on the heap. The file must not be changed by another process while it is being parsed.
+    #[default]
+    Mmap,
+
+    /// Read the whole file onto the heap first. Use this for files that may change while they are
+    /// parsed, or on file systems where mapping is unreliable.
+    Read,
+}
+
+/// How far a parse has got, as passed to a [`ProgressCallback`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// Records (patients, sites or users) parsed so far.
+    pub records: usize,
+
+    /// Records in the whole document.
+    pub total_records: usize,
+
+    /// Bytes of the document covered by the records parsed so far. This reaches `total_bytes`
+    /// once parsing has finished, the text between records included.
+    pub bytes: usize,
+
+    /// Length of the document in bytes.
+    pub total_bytes: usize,
+}
+
+/// Called as records are parsed to report [`Progress`].
+///
+/// It is called once after every record, possibly from a worker thread, and once more when the
+/// whole document has been parsed. Calls never overlap and the counts never go backwards, but the
+/// parse waits on each call, so it should be cheap: store the value or update a progress bar.
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(Progress) + Send + Sync>);
+
+impl ProgressCallback {
+    /// Wrap a function to be called with the progress of a parse.
+    pub fn new(callback: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    pub(crate) fn call(&self, progress: Progress) {
+        (self.0)(progress)
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ProgressCallback").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for ProgressCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ProgressCallback {}
+
+/// Stops a parse that is under way.
+///
+/// Clones share the same flag, so keep one and hand another to the parse options. Once
+/// [`cancel`](Self::cancel) is called no further records are started and the parse returns
+/// [`Error::Cancelled`](crate::errors::Error::Cancelled).
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+///
+/// use prelude_xml_parser::errors::Error;
+/// use prelude_xml_parser::native::subject_native::SubjectNative;
+/// use prelude_xml_parser::options::CancellationToken;
+/// use prelude_xml_parser::NativeParser;
+///
+/// let token = CancellationToken::new();
+/// token.cancel();
+///
+/// let result = NativeParser::new()
+///     .cancellation(token)
+///     .parse_file::<SubjectNative>(Path::new("tests/assets/subject_native.xml"));
+///
+/// assert!(matches!(result, Err(Error::Cancelled)));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every parse holding this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CancellationToken {}
+
+/// Options that control how a native export is parsed.
+///
+/// Every `parse_*_with_options` function takes these, and [`NativeParser`](crate::NativeParser)
+/// builds them up one setting at a time. New settings may be added, so construct this through
+/// [`Default`] and assign the fields that matter.
+///
+/// # Example
+///
+/// ```
+/// use prelude_xml_parser::options::{History, ParseOptions};
+///
+/// let mut options = ParseOptions::default();
+/// options.history = History::CurrentOnly;
+/// options.skip_export = true;
+///
+/// assert_ne!(options, ParseOptions::default());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// How much of each field's audit trail to keep.
+    pub history: History,
+
+    /// Leave `export` as `None` instead of reading the attributes of the root element.
+    pub skip_export: bool,
+
+    /// How the records are spread across threads.
+    pub parallelism: Parallelism,
+
+    /// Called with the progress of the parse as records are finished.
+    pub progress: Option<ProgressCallback>,
+
+    /// Checked before each record is parsed; once cancelled the parse stops with an error.
+    pub cancellation: Option<CancellationToken>,
+
+    /// How the file functions get at the contents of the file.
+    pub file_input: FileInput,
+}
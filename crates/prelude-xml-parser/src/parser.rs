@@ -0,0 +1,212 @@
+use std::{path::Path, sync::Arc};
+
+use rayon::ThreadPool;
+
+use crate::{
+    errors::Error,
+    native::{
+        borrowed::{SiteNativeRef, SubjectNativeRef, UserNativeRef},
+        site_native::SiteNative,
+        subject_native::SubjectNative,
+        user_native::UserNative,
+    },
+    options::{
+        CancellationToken, FileInput, History, Parallelism, ParseOptions, Progress,
+        ProgressCallback,
+    },
+    parse_site_native_borrowed_with_options, parse_site_native_file_with_options,
+    parse_site_native_string_with_options, parse_subject_native_borrowed_with_options,
+    parse_subject_native_file_with_options, parse_subject_native_string_with_options,
+    parse_user_native_borrowed_with_options, parse_user_native_file_with_options,
+    parse_user_native_string_with_options,
+};
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for crate::native::site_native::SiteNative {}
+    impl Sealed for crate::native::subject_native::SubjectNative {}
+    impl Sealed for crate::native::user_native::UserNative {}
+    impl Sealed for crate::native::borrowed::SiteNativeRef<'_> {}
+    impl Sealed for crate::native::borrowed::SubjectNativeRef<'_> {}
+    impl Sealed for crate::native::borrowed::UserNativeRef<'_> {}
+}
+
+/// A native export that [`NativeParser`] can produce: `SubjectNative`, `SiteNative` or
+/// `UserNative`.
+pub trait NativeDocument: sealed::Sealed + Sized {
+    /// Parse a native XML file of this kind using the given options.
+    fn parse_file_with_options(xml_path: &Path, options: &ParseOptions) -> Result<Self, Error>;
+
+    /// Parse a string of native XML of this kind using the given options.
+    fn parse_str_with_options(xml: &str, options: &ParseOptions) -> Result<Self, Error>;
+}
+
+impl NativeDocument for SiteNative {
+    fn parse_file_with_options(xml_path: &Path, options: &ParseOptions) -> Result<Self, Error> {
+        parse_site_native_file_with_options(xml_path, options)
+    }
+
+    fn parse_str_with_options(xml: &str, options: &ParseOptions) -> Result<Self, Error> {
+        parse_site_native_string_with_options(xml, options)
+    }
+}
+
+impl NativeDocument for SubjectNative {
+    fn parse_file_with_options(xml_path: &Path, options: &ParseOptions) -> Result<Self, Error> {
+        parse_subject_native_file_with_options(xml_path, options)
+    }
+
+    fn parse_str_with_options(xml: &str, options: &ParseOptions) -> Result<Self, Error> {
+        parse_subject_native_string_with_options(xml, options)
+    }
+}
+
+impl NativeDocument for UserNative {
+    fn parse_file_with_options(xml_path: &Path, options: &ParseOptions) -> Result<Self, Error> {
+        parse_user_native_file_with_options(xml_path, options)
+    }
+
+    fn parse_str_with_options(xml: &str, options: &ParseOptions) -> Result<Self, Error> {
+        parse_user_native_string_with_options(xml, options)
+    }
+}
+
+/// A borrowed native export that [`NativeParser`] can produce: `SubjectNativeRef`, `SiteNativeRef`
+/// or `UserNativeRef`.
+pub trait BorrowedDocument<'a>: sealed::Sealed + Sized {
+    /// Parse a string of native XML of this kind into a view that borrows from it.
+    fn parse_borrowed_with_options(xml: &'a str, options: &ParseOptions) -> Result<Self, Error>;
+}
+
+impl<'a> BorrowedDocument<'a> for SiteNativeRef<'a> {
+    fn parse_borrowed_with_options(xml: &'a str, options: &ParseOptions) -> Result<Self, Error> {
+        parse_site_native_borrowed_with_options(xml, options)
+    }
+}
+
+impl<'a> BorrowedDocument<'a> for SubjectNativeRef<'a> {
+    fn parse_borrowed_with_options(xml: &'a str, options: &ParseOptions) -> Result<Self, Error> {
+        parse_subject_native_borrowed_with_options(xml, options)
+    }
+}
+
+impl<'a> BorrowedDocument<'a> for UserNativeRef<'a> {
+    fn parse_borrowed_with_options(xml: &'a str, options: &ParseOptions) -> Result<Self, Error> {
+        parse_user_native_borrowed_with_options(xml, options)
+    }
+}
+
+/// Builds up [`ParseOptions`] and parses native exports with them.
+///
+/// The kind of export is chosen by the type being parsed into.
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+///
+/// use prelude_xml_parser::native::subject_native::SubjectNative;
+/// use prelude_xml_parser::options::History;
+/// use prelude_xml_parser::NativeParser;
+///
+/// let native: SubjectNative = NativeParser::new()
+///     .skip_export(true)
+///     .threads(4)
+///     .history(History::CurrentOnly)
+///     .parse_file(Path::new("tests/assets/subject_native.xml"))
+///     .unwrap();
+///
+/// assert!(native.export.is_none());
+/// assert!(native.patients.len() >= 1, "Vector length is less than 1");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NativeParser {
+    options: ParseOptions,
+}
+
+impl NativeParser {
+    /// Create a parser with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a parser from already built options.
+    pub fn with_options(options: ParseOptions) -> Self {
+        Self { options }
+    }
+
+    /// Set how much of each field's audit trail to keep.
+    pub fn history(mut self, history: History) -> Self {
+        self.options.history = history;
+        self
+    }
+
+    /// Set whether to skip reading the attributes of the root element.
+    pub fn skip_export(mut self, skip_export: bool) -> Self {
+        self.options.skip_export = skip_export;
+        self
+    }
+
+    /// Set how the records are spread across threads.
+    pub fn parallelism(mut self, parallelism: Parallelism) -> Self {
+        self.options.parallelism = parallelism;
+        self
+    }
+
+    /// Parse on a dedicated pool of this many threads; one thread parses on the calling thread.
+    ///
+    /// The pool is built for each parse, so a parser used many times is better given a pool with
+    /// [`NativeParser::thread_pool`].
+    pub fn threads(self, threads: usize) -> Self {
+        self.parallelism(Parallelism::Threads(threads))
+    }
+
+    /// Parse on a thread pool the caller already owns.
+    pub fn thread_pool(self, pool: Arc<ThreadPool>) -> Self {
+        self.parallelism(Parallelism::Pool(pool))
+    }
+
+    /// Parse every record on the calling thread.
+    pub fn single_threaded(self) -> Self {
+        self.parallelism(Parallelism::Sequential)
+    }
+
+    /// Call `callback` with the progress of each parse as records are finished.
+    pub fn progress(mut self, callback: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        self.options.progress = Some(ProgressCallback::new(callback));
+        self
+    }
+
+    /// Stop parsing once `token` is cancelled.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.options.cancellation = Some(token);
+        self
+    }
+
+    /// Set how the file functions get at the contents of the file.
+    pub fn file_input(mut self, file_input: FileInput) -> Self {
+        self.options.file_input = file_input;
+        self
+    }
+
+    /// The options this parser was built with.
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// Parse a native XML file.
+    pub fn parse_file<T: NativeDocument>(&self, xml_path: &Path) -> Result<T, Error> {
+        T::parse_file_with_options(xml_path, &self.options)
+    }
+
+    /// Parse a string of native XML.
+    pub fn parse_str<T: NativeDocument>(&self, xml: &str) -> Result<T, Error> {
+        T::parse_str_with_options(xml, &self.options)
+    }
+
+    /// Parse a string of native XML into a view that borrows from it.
+    pub fn parse_borrowed<'a, T: BorrowedDocument<'a>>(&self, xml: &'a str) -> Result<T, Error> {
+        T::parse_borrowed_with_options(xml, &self.options)
+    }
+}
@@ -0,0 +1,1024 @@
+//! A small path language for picking nodes out of a parsed export.
+//!
+//! A selector is a `/`-separated list of steps, each naming a level of the export and optionally
+//! filtering it with `[attribute=value]` predicates:
+//!
+//! ```text
+//! form[name=demographics]/category/field[name=sex]/current[value=F]
+//! ```
+//!
+//! Selectors start at the export's records. The record level may be named (`patient`, `site` or
+//! `user`) to filter it, or left out to start from every record's forms. Each further step goes
+//! down one level:
+//!
+//! | Step       | Follows                      | Selects                                    |
+//! |------------|------------------------------|--------------------------------------------|
+//! | `form`     | the record                   | the record's forms                         |
+//! | `state`    | `form`                       | the form's signature states                |
+//! | `category` | `form`                       | the form's categories                      |
+//! | `field`    | `category`                   | the category's fields                      |
+//! | `file`     | `category`                   | the category's files                       |
+//! | `entry`    | `field`, `file`              | every entry, newest first                  |
+//! | `current`  | `field`, `file`              | the newest entry only                      |
+//! | `comment`  | `field`, `file`              | the field's or file's comments             |
+//! | `query`    | `field`, `file`              | the field's or file's queries              |
+//! | `value`    | `entry`, `current`, `comment`, `query` | the recorded value               |
+//!
+//! A file's download history cannot be selected.
+//!
+//! A predicate compares one attribute of the node with a value using `=` or `!=`. Values may be
+//! quoted with `'` or `"` when they contain `/`, `]`, `,` or spaces, and several predicates, in one
+//! bracket separated by commas or in several brackets, must all hold. Attribute names are those of
+//! the Rust model, so a field's `type` is written `type` and a form's title `form_title`. An
+//! absent attribute is never equal to anything.
+
+use std::{borrow::Cow, fmt, str::FromStr};
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::{
+    errors::Error,
+    native::{
+        common::{Category, Comment, Entry, Field, File, Form, Query, State, Value},
+        site_native::{Site, SiteNative},
+        subject_native::{Patient, SubjectNative},
+        user_native::{User, UserNative},
+    },
+};
+
+/// A level of the export that a step can select.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Level {
+    Patient,
+    Site,
+    User,
+    Form,
+    State,
+    Category,
+    Field,
+    File,
+    Entry,
+    Current,
+    Comment,
+    Query,
+    Value,
+}
+
+impl Level {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "patient" => Level::Patient,
+            "site" => Level::Site,
+            "user" => Level::User,
+            "form" => Level::Form,
+            "state" => Level::State,
+            "category" => Level::Category,
+            "field" => Level::Field,
+            "file" => Level::File,
+            "entry" => Level::Entry,
+            "current" => Level::Current,
+            "comment" => Level::Comment,
+            "query" => Level::Query,
+            "value" => Level::Value,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Level::Patient => "patient",
+            Level::Site => "site",
+            Level::User => "user",
+            Level::Form => "form",
+            Level::State => "state",
+            Level::Category => "category",
+            Level::Field => "field",
+            Level::File => "file",
+            Level::Entry => "entry",
+            Level::Current => "current",
+            Level::Comment => "comment",
+            Level::Query => "query",
+            Level::Value => "value",
+        }
+    }
+
+    fn is_record(self) -> bool {
+        matches!(self, Level::Patient | Level::Site | Level::User)
+    }
+
+    /// Whether a step at this level may come straight after one at `parent`.
+    fn follows(self, parent: Level) -> bool {
+        match self {
+            Level::Patient | Level::Site | Level::User => false,
+            Level::Form => parent.is_record(),
+            Level::State | Level::Category => parent == Level::Form,
+            Level::Field | Level::File => parent == Level::Category,
+            Level::Entry | Level::Current | Level::Comment | Level::Query => {
+                matches!(parent, Level::Field | Level::File)
+            }
+            Level::Value => matches!(
+                parent,
+                Level::Entry | Level::Current | Level::Comment | Level::Query
+            ),
+        }
+    }
+
+    /// The attributes a predicate on this level may test.
+    fn attributes(self) -> &'static [&'static str] {
+        match self {
+            Level::Patient => &[
+                "patient_id",
+                "unique_id",
+                "creator",
+                "site_name",
+                "site_unique_id",
+                "last_language",
+            ],
+            Level::Site => &["name", "unique_id", "creator"],
+            Level::User => &["unique_id", "creator", "last_language"],
+            Level::Form => &[
+                "name",
+                "form_title",
+                "form_index",
+                "form_group",
+                "form_state",
+                "who_last_modified_name",
+                "who_last_modified_role",
+                "locked",
+                "has_errors",
+                "has_warnings",
+            ],
+            Level::State => &["value", "signer", "signer_unique_id"],
+            Level::Category => &["name", "type"],
+            Level::Field => &["name", "type", "data_type", "error_code"],
+            Level::File => &["name", "type", "data_type", "error_code", "file_location"],
+            Level::Entry | Level::Current => {
+                &["id", "value", "by", "role", "reviewed_by", "reason"]
+            }
+            Level::Comment => &["id", "value", "by", "role"],
+            Level::Query => &["id", "value", "by", "role", "answer"],
+            Level::Value => &["value", "by", "by_unique_id", "role", "when"],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Predicate {
+    attribute: String,
+    comparison: Comparison,
+    value: String,
+}
+
+impl Predicate {
+    fn holds(&self, node: Node<'_>) -> bool {
+        let actual = node.attribute(&self.attribute);
+        match self.comparison {
+            Comparison::Equal => actual.as_deref() == Some(self.value.as_str()),
+            Comparison::NotEqual => actual.as_deref() != Some(self.value.as_str()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Step {
+    level: Level,
+    predicates: Vec<Predicate>,
+}
+
+/// A parsed selector, ready to be run against any number of exports.
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+///
+/// use prelude_xml_parser::{parse_subject_native_file, selector::Selector};
+///
+/// let native = parse_subject_native_file(Path::new("tests/assets/subject_native.xml")).unwrap();
+/// let selector: Selector = "form/category/field[name=breed]/current".parse().unwrap();
+/// let matches = selector.select(&native);
+///
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!(matches[0].patient().unwrap().patient_id, "ABC-001");
+/// assert_eq!(matches[0].node().to_string(), "current 1 = \"Labrador\"");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Selector {
+    steps: Vec<Step>,
+}
+
+impl Selector {
+    /// Parse a selector, checking that its steps follow each other and that every predicate
+    /// tests an attribute the step has.
+    pub fn parse(expression: &str) -> Result<Self, Error> {
+        Parser::new(expression).selector()
+    }
+
+    /// Every node the selector picks out of `root`, each with its ancestry, in document order.
+    ///
+    /// A selector that starts at a different kind of record than `root` holds matches nothing.
+    pub fn select<'a, R: Selectable>(&self, root: &'a R) -> Vec<Match<'a>> {
+        let mut matches: Vec<Match<'a>> = root
+            .records()
+            .into_iter()
+            .map(|record| Match {
+                ancestry: vec![record],
+            })
+            .collect();
+
+        let mut steps = self.steps.iter().peekable();
+        if let Some(first) = steps.next_if(|step| step.level.is_record()) {
+            matches.retain(|m| {
+                m.node().level() == first.level
+                    && first.predicates.iter().all(|p| p.holds(m.node()))
+            });
+        }
+
+        for step in steps {
+            matches = matches
+                .iter()
+                .flat_map(|m| {
+                    m.node()
+                        .children(step.level)
+                        .into_iter()
+                        .filter(|child| step.predicates.iter().all(|p| p.holds(*child)))
+                        .map(move |child| {
+                            let mut ancestry = m.ancestry.clone();
+                            ancestry.push(child);
+                            Match { ancestry }
+                        })
+                })
+                .collect();
+        }
+
+        matches
+    }
+}
+
+impl FromStr for Selector {
+    type Err = Error;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        Selector::parse(expression)
+    }
+}
+
+/// A recursive-descent parser over the selector's characters.
+struct Parser<'e> {
+    expression: &'e str,
+    pos: usize,
+}
+
+impl<'e> Parser<'e> {
+    fn new(expression: &'e str) -> Self {
+        Self { expression, pos: 0 }
+    }
+
+    fn error(&self, message: impl Into<String>) -> Error {
+        Error::InvalidSelector {
+            position: self.pos,
+            message: message.into(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.expression[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn selector(mut self) -> Result<Selector, Error> {
+        let mut steps: Vec<Step> = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            let start = self.pos;
+            let step = self.step()?;
+
+            let fits = match steps.last() {
+                None => step.level.is_record() || step.level == Level::Form,
+                Some(previous) => step.level.follows(previous.level),
+            };
+            if !fits {
+                self.pos = start;
+                return Err(self.error(match steps.last() {
+                    None => format!(
+                        "a selector has to start at a record or a form, not `{}`",
+                        step.level.name()
+                    ),
+                    Some(previous) => format!(
+                        "`{}` cannot follow `{}`",
+                        step.level.name(),
+                        previous.level.name()
+                    ),
+                }));
+            }
+            steps.push(step);
+
+            self.skip_whitespace();
+            if self.peek().is_none() {
+                return Ok(Selector { steps });
+            }
+            if !self.eat('/') {
+                return Err(self.error("expected `/` or `[`"));
+            }
+        }
+    }
+
+    fn step(&mut self) -> Result<Step, Error> {
+        let start = self.pos;
+        let name = self.identifier();
+        let Some(level) = Level::from_name(name) else {
+            self.pos = start;
+            return Err(self.error(if name.is_empty() {
+                "expected a step".to_string()
+            } else {
+                format!("unknown step `{name}`")
+            }));
+        };
+
+        let mut predicates = Vec::new();
+        self.skip_whitespace();
+        while self.eat('[') {
+            loop {
+                predicates.push(self.predicate(level)?);
+                self.skip_whitespace();
+                if self.eat(']') {
+                    break;
+                }
+                if !self.eat(',') {
+                    return Err(self.error("expected `,` or `]`"));
+                }
+            }
+            self.skip_whitespace();
+        }
+
+        Ok(Step { level, predicates })
+    }
+
+    fn predicate(&mut self, level: Level) -> Result<Predicate, Error> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let attribute = self.identifier();
+        if !level.attributes().contains(&attribute) {
+            self.pos = start;
+            return Err(self.error(format!(
+                "`{}` has no attribute `{attribute}`; expected one of {}",
+                level.name(),
+                level.attributes().join(", ")
+            )));
+        }
+        let attribute = attribute.to_string();
+
+        self.skip_whitespace();
+        let comparison = if self.eat('=') {
+            Comparison::Equal
+        } else if self.eat('!') && self.eat('=') {
+            Comparison::NotEqual
+        } else {
+            return Err(self.error("expected `=` or `!=`"));
+        };
+
+        self.skip_whitespace();
+        let value = self.literal()?;
+
+        Ok(Predicate {
+            attribute,
+            comparison,
+            value,
+        })
+    }
+
+    fn identifier(&mut self) -> &'e str {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            self.pos += 1;
+        }
+
+        &self.expression[start..self.pos]
+    }
+
+    fn literal(&mut self) -> Result<String, Error> {
+        if let Some(quote) = self.peek().filter(|&c| c == '\'' || c == '"') {
+            let start = self.pos;
+            self.pos += 1;
+            let Some(len) = self.expression[self.pos..].find(quote) else {
+                self.pos = start;
+                return Err(self.error("unterminated quoted value"));
+            };
+            let value = self.expression[self.pos..self.pos + len].to_string();
+            self.pos += len + 1;
+            return Ok(value);
+        }
+
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| !matches!(c, ']' | ',' | '/' | '[') && !c.is_whitespace())
+        {
+            self.pos += self.peek().map_or(0, char::len_utf8);
+        }
+
+        Ok(self.expression[start..self.pos].to_string())
+    }
+}
+
+/// A node of a parsed export that a selector can select.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Node<'a> {
+    Patient(&'a Patient),
+    Site(&'a Site),
+    User(&'a User),
+    Form(&'a Form),
+    State(&'a State),
+    Category(&'a Category),
+    Field(&'a Field),
+    File(&'a File),
+    /// An entry of a field. `current` is whether it was selected as the field's newest entry.
+    Entry {
+        entry: &'a Entry,
+        current: bool,
+    },
+    Comment(&'a Comment),
+    Query(&'a Query),
+    Value(&'a Value),
+}
+
+impl<'a> Node<'a> {
+    fn level(self) -> Level {
+        match self {
+            Node::Patient(_) => Level::Patient,
+            Node::Site(_) => Level::Site,
+            Node::User(_) => Level::User,
+            Node::Form(_) => Level::Form,
+            Node::State(_) => Level::State,
+            Node::Category(_) => Level::Category,
+            Node::Field(_) => Level::Field,
+            Node::File(_) => Level::File,
+            Node::Entry { current: false, .. } => Level::Entry,
+            Node::Entry { current: true, .. } => Level::Current,
+            Node::Comment(_) => Level::Comment,
+            Node::Query(_) => Level::Query,
+            Node::Value(_) => Level::Value,
+        }
+    }
+
+    fn children(self, level: Level) -> Vec<Node<'a>> {
+        fn all<T>(items: &Option<std::sync::Arc<Vec<T>>>) -> &[T] {
+            items.as_deref().map_or(&[], Vec::as_slice)
+        }
+
+        let forms = |forms| all(forms).iter().map(Node::Form).collect();
+        // Fields and files both hold entries, comments and queries.
+        let (entries, comments, queries) = match self {
+            Node::Field(field) => (&field.entries, &field.comments, &field.queries),
+            Node::File(file) => (&file.entries, &file.comments, &file.queries),
+            _ => (&None, &None, &None),
+        };
+        match (self, level) {
+            (Node::Patient(patient), Level::Form) => forms(&patient.forms),
+            (Node::Site(site), Level::Form) => forms(&site.forms),
+            (Node::User(user), Level::Form) => forms(&user.forms),
+            (Node::Form(form), Level::State) => all(&form.states).iter().map(Node::State).collect(),
+            (Node::Form(form), Level::Category) => {
+                all(&form.categories).iter().map(Node::Category).collect()
+            }
+            (Node::Category(category), Level::Field) => {
+                all(&category.fields).iter().map(Node::Field).collect()
+            }
+            (Node::Category(category), Level::File) => {
+                all(&category.files).iter().map(Node::File).collect()
+            }
+            (Node::Field(_) | Node::File(_), Level::Entry) => all(entries)
+                .iter()
+                .map(|entry| Node::Entry {
+                    entry,
+                    current: false,
+                })
+                .collect(),
+            (Node::Field(_) | Node::File(_), Level::Current) => all(entries)
+                .first()
+                .map(|entry| Node::Entry {
+                    entry,
+                    current: true,
+                })
+                .into_iter()
+                .collect(),
+            (Node::Field(_) | Node::File(_), Level::Comment) => {
+                all(comments).iter().map(Node::Comment).collect()
+            }
+            (Node::Field(_) | Node::File(_), Level::Query) => {
+                all(queries).iter().map(Node::Query).collect()
+            }
+            (Node::Entry { entry, .. }, Level::Value) => {
+                entry.value.iter().map(Node::Value).collect()
+            }
+            (Node::Comment(comment), Level::Value) => {
+                comment.value.iter().map(Node::Value).collect()
+            }
+            (Node::Query(query), Level::Value) => query.value.iter().map(Node::Value).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The value of one of the attributes listed for the node's level, as text.
+    fn attribute(self, name: &str) -> Option<Cow<'a, str>> {
+        fn text(value: &str) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(value))
+        }
+        fn optional<S: AsRef<str> + ?Sized>(value: Option<&S>) -> Option<Cow<'_, str>> {
+            value.map(|value| Cow::Borrowed(value.as_ref()))
+        }
+        fn shown(value: impl ToString) -> Option<Cow<'static, str>> {
+            Some(Cow::Owned(value.to_string()))
+        }
+        fn of_value<'a>(value: Option<&'a Value>, name: &str) -> Option<Cow<'a, str>> {
+            value.and_then(|value| Node::Value(value).attribute(name))
+        }
+
+        match (self, name) {
+            (Node::Patient(patient), "patient_id") => text(&patient.patient_id),
+            (Node::Patient(patient), "unique_id") => text(&patient.unique_id),
+            (Node::Patient(patient), "creator") => text(&patient.creator),
+            (Node::Patient(patient), "site_name") => text(&patient.site_name),
+            (Node::Patient(patient), "site_unique_id") => text(&patient.site_unique_id),
+            (Node::Patient(patient), "last_language") => optional(patient.last_language.as_ref()),
+            (Node::Site(site), "name") => text(&site.name),
+            (Node::Site(site), "unique_id") => text(&site.unique_id),
+            (Node::Site(site), "creator") => text(&site.creator),
+            (Node::User(user), "unique_id") => text(&user.unique_id),
+            (Node::User(user), "creator") => text(&user.creator),
+            (Node::User(user), "last_language") => optional(user.last_language.as_ref()),
+            (Node::Form(form), "name") => text(&form.name),
+            (Node::Form(form), "form_title") => text(&form.form_title),
+            (Node::Form(form), "form_index") => shown(form.form_index),
+            (Node::Form(form), "form_group") => optional(form.form_group.as_deref()),
+            (Node::Form(form), "form_state") => text(&form.form_state),
+            (Node::Form(form), "who_last_modified_name") => {
+                optional(form.who_last_modified_name.as_ref())
+            }
+            (Node::Form(form), "who_last_modified_role") => {
+                optional(form.who_last_modified_role.as_ref())
+            }
+            (Node::Form(form), "locked") => shown(form.locked),
+            (Node::Form(form), "has_errors") => shown(form.has_errors),
+            (Node::Form(form), "has_warnings") => shown(form.has_warnings),
+            (Node::State(state), "value") => text(&state.value),
+            (Node::State(state), "signer") => text(&state.signer),
+            (Node::State(state), "signer_unique_id") => text(&state.signer_unique_id),
+            (Node::Category(category), "name") => text(&category.name),
+            (Node::Category(category), "type") => text(&category.category_type),
+            (Node::Field(field), "name") => text(&field.name),
+            (Node::Field(field), "type") => text(&field.field_type),
+            (Node::Field(field), "data_type") => optional(field.data_type.as_deref()),
+            (Node::Field(field), "error_code") => text(&field.error_code),
+            (Node::File(file), "name") => text(&file.name),
+            (Node::File(file), "type") => text(&file.file_type),
+            (Node::File(file), "data_type") => optional(file.data_type.as_deref()),
+            (Node::File(file), "error_code") => text(&file.error_code),
+            (Node::File(file), "file_location") => optional(file.file_location.as_ref()),
+            (Node::Entry { entry, .. }, "id") => text(&entry.entry_id),
+            (Node::Entry { entry, .. }, "reviewed_by") => optional(entry.reviewed_by.as_ref()),
+            (Node::Entry { entry, .. }, "reason") => entry
+                .reason
+                .as_ref()
+                .map(|reason| Cow::Borrowed(reason.value.as_str())),
+            (Node::Entry { entry, .. }, name) => of_value(entry.value.as_ref(), name),
+            (Node::Comment(comment), "id") => text(&comment.comment_id),
+            (Node::Comment(comment), name) => of_value(comment.value.as_ref(), name),
+            (Node::Query(query), "id") => text(&query.query_id),
+            (Node::Query(query), "answer") => of_value(query.answer.as_ref(), "value"),
+            (Node::Query(query), name) => of_value(query.value.as_ref(), name),
+            (Node::Value(value), "value") => text(&value.value),
+            (Node::Value(value), "by") => text(&value.by),
+            (Node::Value(value), "by_unique_id") => optional(value.by_unique_id.as_deref()),
+            (Node::Value(value), "role") => text(&value.role),
+            (Node::Value(value), "when") => value.when.map(|when| Cow::Owned(when.to_rfc3339())),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Node<'_> {
+    /// The node's level and what identifies it, followed by its value if it has one.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: Option<&Value>| match value {
+            Some(value) => format!(" = {:?}", value.value),
+            None => String::new(),
+        };
+
+        match self {
+            Node::Patient(patient) => write!(f, "patient {}", patient.patient_id),
+            Node::Site(site) => write!(f, "site {}", site.name),
+            Node::User(user) => write!(f, "user {}", user.unique_id),
+            Node::Form(form) => write!(f, "form {}[{}]", form.name, form.form_index),
+            Node::State(state) => write!(f, "state {}", state.value),
+            Node::Category(category) => write!(f, "category {}", category.name),
+            Node::Field(field) => write!(f, "field {}", field.name),
+            Node::File(file) => write!(f, "file {}", file.name),
+            Node::Entry { entry, current } => write!(
+                f,
+                "{} {}{}",
+                if *current { "current" } else { "entry" },
+                entry.entry_id,
+                value(entry.value.as_ref())
+            ),
+            Node::Comment(comment) => {
+                write!(
+                    f,
+                    "comment {}{}",
+                    comment.comment_id,
+                    value(comment.value.as_ref())
+                )
+            }
+            Node::Query(query) => {
+                write!(f, "query {}{}", query.query_id, value(query.value.as_ref()))
+            }
+            Node::Value(v) => write!(f, "value{}", value(Some(v))),
+        }
+    }
+}
+
+/// A node picked out by a selector, together with the nodes above it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match<'a> {
+    /// The path from the record down to the selected node, which is last.
+    pub ancestry: Vec<Node<'a>>,
+}
+
+impl<'a> Match<'a> {
+    /// The selected node.
+    pub fn node(&self) -> Node<'a> {
+        *self
+            .ancestry
+            .last()
+            .expect("a match always holds at least its record")
+    }
+
+    pub fn patient(&self) -> Option<&'a Patient> {
+        self.ancestry.iter().find_map(|node| match node {
+            Node::Patient(patient) => Some(*patient),
+            _ => None,
+        })
+    }
+
+    pub fn site(&self) -> Option<&'a Site> {
+        self.ancestry.iter().find_map(|node| match node {
+            Node::Site(site) => Some(*site),
+            _ => None,
+        })
+    }
+
+    pub fn user(&self) -> Option<&'a User> {
+        self.ancestry.iter().find_map(|node| match node {
+            Node::User(user) => Some(*user),
+            _ => None,
+        })
+    }
+
+    pub fn form(&self) -> Option<&'a Form> {
+        self.ancestry.iter().find_map(|node| match node {
+            Node::Form(form) => Some(*form),
+            _ => None,
+        })
+    }
+
+    pub fn category(&self) -> Option<&'a Category> {
+        self.ancestry.iter().find_map(|node| match node {
+            Node::Category(category) => Some(*category),
+            _ => None,
+        })
+    }
+
+    pub fn field(&self) -> Option<&'a Field> {
+        self.ancestry.iter().find_map(|node| match node {
+            Node::Field(field) => Some(*field),
+            _ => None,
+        })
+    }
+
+    pub fn file(&self) -> Option<&'a File> {
+        self.ancestry.iter().find_map(|node| match node {
+            Node::File(file) => Some(*file),
+            _ => None,
+        })
+    }
+
+    pub fn entry(&self) -> Option<&'a Entry> {
+        self.ancestry.iter().find_map(|node| match node {
+            Node::Entry { entry, .. } => Some(*entry),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for Match<'_> {
+    /// The ancestry, one node after another, separated by ` / `.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, node) in self.ancestry.iter().enumerate() {
+            if i > 0 {
+                write!(f, " / ")?;
+            }
+            write!(f, "{node}")?;
+        }
+
+        Ok(())
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for crate::native::subject_native::SubjectNative {}
+    impl Sealed for crate::native::site_native::SiteNative {}
+    impl Sealed for crate::native::user_native::UserNative {}
+}
+
+/// A parsed export that selectors can run against.
+pub trait Selectable: sealed::Sealed {
+    /// The export's records, where every selector starts.
+    fn records(&self) -> Vec<Node<'_>>;
+
+    /// Parse `expression` and select from this export with it.
+    fn select(&self, expression: &str) -> Result<Vec<Match<'_>>, Error>
+    where
+        Self: Sized,
+    {
+        Ok(Selector::parse(expression)?.select(self))
+    }
+}
+
+impl Selectable for SubjectNative {
+    fn records(&self) -> Vec<Node<'_>> {
+        self.patients.iter().map(Node::Patient).collect()
+    }
+}
+
+impl Selectable for SiteNative {
+    fn records(&self) -> Vec<Node<'_>> {
+        self.sites.iter().map(Node::Site).collect()
+    }
+}
+
+impl Selectable for UserNative {
+    fn records(&self) -> Vec<Node<'_>> {
+        self.users.iter().map(Node::User).collect()
+    }
+}
+
+/// A selector match as handed to Python, holding a copy of each node in its ancestry.
+#[cfg(feature = "python")]
+#[pyclass(
+    name = "Match",
+    module = "prelude_parser._prelude_parser",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Clone, Debug)]
+pub struct PyMatch {
+    /// The ancestry written out as by the command line's `select`.
+    path: String,
+    patient: Option<Patient>,
+    site: Option<Site>,
+    user: Option<User>,
+    form: Option<Form>,
+    state: Option<State>,
+    category: Option<Category>,
+    field: Option<Field>,
+    file: Option<File>,
+    entry: Option<Entry>,
+    comment: Option<Comment>,
+    query: Option<Query>,
+    value: Option<Value>,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl PyMatch {
+    fn __repr__(&self) -> String {
+        format!("Match({})", self.path)
+    }
+}
+
+#[cfg(feature = "python")]
+impl From<&Match<'_>> for PyMatch {
+    fn from(m: &Match<'_>) -> Self {
+        let mut py_match = PyMatch {
+            path: m.to_string(),
+            patient: None,
+            site: None,
+            user: None,
+            form: None,
+            state: None,
+            category: None,
+            field: None,
+            file: None,
+            entry: None,
+            comment: None,
+            query: None,
+            value: None,
+        };
+
+        for node in &m.ancestry {
+            match *node {
+                Node::Patient(patient) => py_match.patient = Some(patient.clone()),
+                Node::Site(site) => py_match.site = Some(site.clone()),
+                Node::User(user) => py_match.user = Some(user.clone()),
+                Node::Form(form) => py_match.form = Some(form.clone()),
+                Node::State(state) => py_match.state = Some(state.clone()),
+                Node::Category(category) => py_match.category = Some(category.clone()),
+                Node::Field(field) => py_match.field = Some(field.clone()),
+                Node::File(file) => py_match.file = Some(file.clone()),
+                Node::Entry { entry, .. } => py_match.entry = Some(entry.clone()),
+                Node::Comment(comment) => py_match.comment = Some(comment.clone()),
+                Node::Query(query) => py_match.query = Some(query.clone()),
+                Node::Value(value) => py_match.value = Some(value.clone()),
+            }
+        }
+
+        py_match
+    }
+}
+
+/// Run `expression` against `root` for Python, raising `ValueError` for an invalid selector.
+#[cfg(feature = "python")]
+pub(crate) fn py_select<R: Selectable>(root: &R, expression: &str) -> PyResult<Vec<PyMatch>> {
+    let matches = root
+        .select(expression)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+
+    Ok(matches.iter().map(PyMatch::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{parse_site_native_file, parse_subject_native_file, parse_subject_native_string};
+
+    fn subject() -> SubjectNative {
+        parse_subject_native_file(Path::new("tests/assets/subject_native.xml")).unwrap()
+    }
+
+    fn paths(matches: &[Match<'_>]) -> Vec<String> {
+        matches.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn selects_current_values_with_their_ancestry() {
+        let native = subject();
+        let matches = native
+            .select("form[name=day.0.form.name.demographics]/category/field[name=breed]/current")
+            .unwrap();
+
+        assert_eq!(
+            paths(&matches),
+            vec![
+                "patient ABC-001 / form day.0.form.name.demographics[1] / category Demographics / field breed / current 1 = \"Labrador\""
+            ]
+        );
+        assert_eq!(matches[0].field().unwrap().name.as_ref(), "breed");
+        assert_eq!(matches[0].form().unwrap().form_index, 1);
+    }
+
+    #[test]
+    fn filters_on_values_and_records() {
+        let native = subject();
+
+        let females = native
+            .select("form/category/field[name=gender]/current[value='Female Spayed']")
+            .unwrap();
+        assert_eq!(females.len(), 1);
+        assert_eq!(females[0].patient().unwrap().patient_id, "ABC-001");
+
+        let other = native.select("patient[patient_id!=ABC-001]/form").unwrap();
+        assert!(other
+            .iter()
+            .all(|m| m.patient().unwrap().patient_id != "ABC-001"));
+        assert!(!other.is_empty());
+
+        let none = native
+            .select("patient[patient_id=ABC-001][site_name=Nowhere]/form")
+            .unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn selects_from_sites() {
+        let native = parse_site_native_file(Path::new("tests/assets/site_native.xml")).unwrap();
+
+        let sites = native
+            .select("site/form/category/field[name=address]")
+            .unwrap();
+        assert_eq!(sites.len(), native.sites.len());
+
+        let patients = native.select("patient/form").unwrap();
+        assert!(patients.is_empty());
+    }
+
+    #[test]
+    fn selects_from_files() {
+        let native = parse_subject_native_string(
+            r#"<export_from_vision_EDC date="01-Jun-2024 18:17 -0500" createdBy="Paul Sanders" role="Project Manager" numberSubjectsProcessed="1">
+  <patient patientId="ABC-001" uniqueId="1681574905819" whenCreated="2023-04-15 12:09:02 -0400" creator="Paul Sanders" siteName="Some Site" siteUniqueId="1681574834910" lastLanguage="" numberOfForms="1">
+    <form name="scan" lastModified="2023-04-15 12:09:15 -0400" whoLastModifiedName="Paul Sanders" whoLastModifiedRole="Project Manager" whenCreated="1681574905839" hasErrors="false" hasWarnings="false" locked="false" user="" dateTimeChanged="" formTitle="Scan" formIndex="1" formGroup="" formState="In-Work">
+      <category name="Scan" type="normal" highestIndex="0">
+        <file name="report" type="file" dataType="file" errorCode="valid" whenCreated="2023-04-15 12:08:26 -0400" keepHistory="true" fileLocation="reports/report.pdf">
+          <entry id="1">
+            <value by="Paul Sanders" byUniqueId="1681162687395" role="Project Manager" when="2023-04-15 12:09:02 -0400" xml:space="preserve">report.pdf</value>
+          </entry>
+          <comment id="1">
+            <value by="Paul Sanders" byUniqueId="1681162687395" role="Project Manager" when="2023-04-15 12:09:02 -0400" xml:space="preserve">Rescanned</value>
+          </comment>
+        </file>
+      </category>
+    </form>
+  </patient>
+</export_from_vision_EDC>"#,
+        )
+        .unwrap();
+
+        let current = native
+            .select("form/category/file[file_location='reports/report.pdf']/current")
+            .unwrap();
+        assert_eq!(
+            paths(&current),
+            vec!["patient ABC-001 / form scan[1] / category Scan / file report / current 1 = \"report.pdf\""]
+        );
+        assert_eq!(current[0].file().unwrap().name.as_ref(), "report");
+        assert!(current[0].field().is_none());
+
+        let comments = native
+            .select("form/category/file[name=report]/comment[value=Rescanned]")
+            .unwrap();
+        assert_eq!(comments.len(), 1);
+        assert!(native
+            .select("form/category/file/query")
+            .unwrap()
+            .is_empty());
+        assert!(native.select("form/category/field").unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_selectors_error() {
+        for (expression, position, message) in [
+            (
+                "field[name=sex]",
+                0,
+                "a selector has to start at a record or a form, not `field`",
+            ),
+            ("form/field", 5, "`field` cannot follow `form`"),
+            ("form/file", 5, "`file` cannot follow `form`"),
+            (
+                "form/category/file[size=1]",
+                19,
+                "`file` has no attribute `size`",
+            ),
+            ("form/categry", 5, "unknown step `categry`"),
+            ("form[title=x]", 5, "`form` has no attribute `title`"),
+            ("form[name~x]", 9, "expected `=` or `!=`"),
+            ("form[name='x]", 10, "unterminated quoted value"),
+            ("form[name=x", 11, "expected `,` or `]`"),
+            ("form/", 5, "expected a step"),
+            // Whitespace wider than a byte is skipped a whole character at a time.
+            ("form\u{00A0}/categry", 7, "unknown step `categry`"),
+            ("form[name=\u{3000}x", 14, "expected `,` or `]`"),
+        ] {
+            match Selector::parse(expression) {
+                Err(Error::InvalidSelector {
+                    position: at,
+                    message: got,
+                }) => {
+                    assert_eq!(at, position, "{expression}");
+                    assert!(got.starts_with(message), "{expression}: {got}");
+                }
+                other => panic!("{expression}: expected an error, got {other:?}"),
+            }
+        }
+    }
+}
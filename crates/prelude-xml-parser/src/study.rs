@@ -0,0 +1,402 @@
+//! The subject, site and user exports of one study, joined on the ids they share.
+//!
+//! A patient names its site by `site_unique_id`, and values, signatures and lock states name the
+//! user behind them by unique id. Each export is parsed on its own, so [`Study`] borrows all three
+//! and follows those ids between them, reporting the ones that lead nowhere.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    index::{SiteIndex, UserIndex},
+    native::{
+        common::{Comment, Entry, Form, Query, State, Value},
+        site_native::{Site, SiteNative},
+        subject_native::{Patient, SubjectNative},
+        user_native::{User, UserNative},
+    },
+    selector::Node,
+    visit::{walk_form, walk_patient, walk_site, walk_user, Visitor},
+};
+
+/// The signer id Prelude records for signatures it makes itself, which has no user behind it.
+const SYSTEM_SIGNER: &str = "system";
+
+/// What an id found in an export refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReferenceKind {
+    /// A patient's `site_unique_id`, referring to a site.
+    PatientSite,
+    /// The `by_unique_id` of a value or reason, referring to the user who recorded it.
+    Author,
+    /// A state's `signer_unique_id`, referring to the user who signed the form.
+    Signer,
+    /// A lock state's `user_unique_id`, referring to the user who locked or unlocked the form.
+    Locker,
+}
+
+/// An id in one export that refers to a record in another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reference<'a> {
+    pub kind: ReferenceKind,
+    /// The patient, site or user whose export holds the id.
+    pub record: Node<'a>,
+    /// The form holding the id, unless it is the record's own.
+    pub form: Option<&'a Form>,
+    pub unique_id: &'a str,
+}
+
+/// A form signed by a user, at the patient it belongs to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature<'a> {
+    pub patient: &'a Patient,
+    pub site: &'a Site,
+    pub form: &'a Form,
+    pub state: &'a State,
+    pub signer: &'a User,
+}
+
+/// The three exports of a study, with the references between them resolved.
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+///
+/// use prelude_xml_parser::{
+///     parse_site_native_file, parse_subject_native_file, parse_user_native_file, study::Study,
+/// };
+///
+/// let subjects = parse_subject_native_file(Path::new("tests/assets/subject_native.xml")).unwrap();
+/// let sites = parse_site_native_file(Path::new("tests/assets/site_native.xml")).unwrap();
+/// let users = parse_user_native_file(Path::new("tests/assets/user_native.xml")).unwrap();
+/// let study = Study::new(&subjects, &sites, &users);
+///
+/// let patient = &subjects.patients[0];
+/// assert_eq!(study.site_of(patient).unwrap().name, patient.site_name);
+///
+/// for signature in study.forms_signed_by("Project Manager", "Some Site") {
+///     assert_eq!(signature.site.name, "Some Site");
+///     assert_eq!(&*signature.state.signer, "Paul Sanders - Project Manager");
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Study<'a> {
+    pub subjects: &'a SubjectNative,
+    pub sites: &'a SiteNative,
+    pub users: &'a UserNative,
+    site_index: SiteIndex<'a>,
+    user_index: UserIndex<'a>,
+    roles: HashMap<&'a str, BTreeSet<&'a str>>,
+    references: Vec<Reference<'a>>,
+}
+
+impl<'a> Study<'a> {
+    pub fn new(subjects: &'a SubjectNative, sites: &'a SiteNative, users: &'a UserNative) -> Self {
+        let mut collect = CollectReferences::default();
+        collect.visit_subject_native(subjects);
+        collect.visit_site_native(sites);
+        collect.visit_user_native(users);
+
+        Study {
+            subjects,
+            sites,
+            users,
+            site_index: sites.index(),
+            user_index: users.index(),
+            roles: collect.roles,
+            references: collect.references,
+        }
+    }
+
+    /// The site a patient belongs to.
+    pub fn site_of(&self, patient: &Patient) -> Option<&'a Site> {
+        self.site_index
+            .by_unique_id
+            .get(&*patient.site_unique_id)
+            .copied()
+    }
+
+    /// The patients belonging to a site, in document order.
+    pub fn patients_at(&self, site: &Site) -> Vec<&'a Patient> {
+        self.subjects
+            .patients
+            .iter()
+            .filter(|patient| patient.site_unique_id == site.unique_id)
+            .collect()
+    }
+
+    pub fn user(&self, unique_id: &str) -> Option<&'a User> {
+        self.user_index.by_unique_id.get(unique_id).copied()
+    }
+
+    /// The roles a user has recorded values under, in any of the three exports.
+    ///
+    /// Users carry no role of their own in an export, so this is the closest there is to one. A
+    /// signature names the role it was made under, which is what [`Study::forms_signed_by`] goes
+    /// by when it can.
+    pub fn roles_of(&self, user: &User) -> BTreeSet<&'a str> {
+        self.roles
+            .get(&*user.unique_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Every id in the three exports that refers to another record, in document order.
+    pub fn references(&self) -> &[Reference<'a>] {
+        &self.references
+    }
+
+    /// The references to sites or users that are not in the study's exports.
+    pub fn dangling(&self) -> Vec<&Reference<'a>> {
+        self.references
+            .iter()
+            .filter(|reference| match reference.kind {
+                ReferenceKind::PatientSite => !self
+                    .site_index
+                    .by_unique_id
+                    .contains_key(reference.unique_id),
+                ReferenceKind::Author | ReferenceKind::Signer | ReferenceKind::Locker => {
+                    self.user(reference.unique_id).is_none()
+                }
+            })
+            .collect()
+    }
+
+    /// The signatures on the forms of a site's patients made under a role.
+    ///
+    /// `site` is the site's name. Each state of a form is its own signature, and its role is the
+    /// one in the state's signer, `"Name - Role"`. Where the signer has no role in it, the
+    /// signature counts for each of [`Study::roles_of`] its user.
+    pub fn forms_signed_by(&self, role: &str, site: &str) -> Vec<Signature<'a>> {
+        let Some(&site) = self.site_index.by_name.get(site) else {
+            return Vec::new();
+        };
+
+        let mut signatures = Vec::new();
+        for patient in self.patients_at(site) {
+            for form in patient.forms.iter().flat_map(|forms| forms.iter()) {
+                for state in form.states.iter().flat_map(|states| states.iter()) {
+                    let Some(signer) = self.user(&state.signer_unique_id) else {
+                        continue;
+                    };
+                    let signed_as = match signed_role(state) {
+                        Some(signed_role) => signed_role == role,
+                        None => self
+                            .roles
+                            .get(&*signer.unique_id)
+                            .is_some_and(|roles| roles.contains(role)),
+                    };
+                    if signed_as {
+                        signatures.push(Signature {
+                            patient,
+                            site,
+                            form,
+                            state,
+                            signer,
+                        });
+                    }
+                }
+            }
+        }
+
+        signatures
+    }
+}
+
+/// The role a state was signed under, from a signer of the form `"Name - Role"`.
+fn signed_role(state: &State) -> Option<&str> {
+    state
+        .signer
+        .rsplit_once(" - ")
+        .map(|(_, role)| role.trim())
+        .filter(|role| !role.is_empty())
+}
+
+/// Walks the exports for the ids they hold and the roles their values were recorded under.
+#[derive(Default)]
+struct CollectReferences<'a> {
+    record: Option<Node<'a>>,
+    form: Option<&'a Form>,
+    roles: HashMap<&'a str, BTreeSet<&'a str>>,
+    references: Vec<Reference<'a>>,
+}
+
+impl<'a> CollectReferences<'a> {
+    fn push(&mut self, kind: ReferenceKind, unique_id: &'a str) {
+        let record = self
+            .record
+            .expect("references are only collected inside a record");
+
+        self.references.push(Reference {
+            kind,
+            record,
+            form: self.form,
+            unique_id,
+        });
+    }
+
+    fn value(&mut self, value: Option<&'a Value>) {
+        let Some(value) = value else {
+            return;
+        };
+        if let Some(by) = value.by_unique_id.as_deref() {
+            self.roles.entry(by).or_default().insert(&value.role);
+            self.push(ReferenceKind::Author, by);
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for CollectReferences<'a> {
+    fn visit_patient(&mut self, patient: &'a Patient) {
+        self.record = Some(Node::Patient(patient));
+        self.form = None;
+        self.push(ReferenceKind::PatientSite, &patient.site_unique_id);
+        walk_patient(self, patient);
+    }
+
+    fn visit_site(&mut self, site: &'a Site) {
+        self.record = Some(Node::Site(site));
+        walk_site(self, site);
+    }
+
+    fn visit_user(&mut self, user: &'a User) {
+        self.record = Some(Node::User(user));
+        walk_user(self, user);
+    }
+
+    fn visit_form(&mut self, form: &'a Form) {
+        self.form = Some(form);
+        for state in form.states.iter().flat_map(|states| states.iter()) {
+            if &*state.signer_unique_id != SYSTEM_SIGNER && !state.signer_unique_id.is_empty() {
+                self.push(ReferenceKind::Signer, &state.signer_unique_id);
+            }
+        }
+        for lock_state in form.lock_states.iter().flat_map(|states| states.iter()) {
+            if let Some(user) = lock_state.user_unique_id.as_deref() {
+                self.push(ReferenceKind::Locker, user);
+            }
+        }
+        walk_form(self, form);
+        self.form = None;
+    }
+
+    fn visit_entry(&mut self, entry: &'a Entry) {
+        self.value(entry.value.as_ref());
+        if let Some(by) = entry
+            .reason
+            .as_ref()
+            .and_then(|reason| reason.by_unique_id.as_deref())
+        {
+            self.push(ReferenceKind::Author, by);
+        }
+    }
+
+    fn visit_comment(&mut self, comment: &'a Comment) {
+        self.value(comment.value.as_ref());
+    }
+
+    fn visit_query(&mut self, query: &'a Query) {
+        self.value(query.value.as_ref());
+        self.value(query.answer.as_ref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::Arc};
+
+    use super::*;
+    use crate::{parse_site_native_file, parse_subject_native_file, parse_user_native_file};
+
+    fn exports() -> (SubjectNative, SiteNative, UserNative) {
+        (
+            parse_subject_native_file(Path::new("tests/assets/subject_native.xml")).unwrap(),
+            parse_site_native_file(Path::new("tests/assets/site_native.xml")).unwrap(),
+            parse_user_native_file(Path::new("tests/assets/user_native.xml")).unwrap(),
+        )
+    }
+
+    #[test]
+    fn resolves_sites_and_users() {
+        let (subjects, sites, users) = exports();
+        let study = Study::new(&subjects, &sites, &users);
+
+        for patient in &subjects.patients {
+            let site = study.site_of(patient).unwrap();
+            assert!(study.patients_at(site).contains(&patient));
+        }
+
+        let user = study.user("1681162687395").unwrap();
+        assert!(study.roles_of(user).contains("Project Manager"));
+    }
+
+    #[test]
+    fn finds_forms_signed_by_a_role_at_a_site() {
+        let (subjects, sites, users) = exports();
+        let study = Study::new(&subjects, &sites, &users);
+
+        let signatures = study.forms_signed_by("Project Manager", "Some Site");
+        assert!(!signatures.is_empty());
+        assert!(signatures
+            .iter()
+            .all(|signature| &*signature.state.signer_unique_id == "1681162687395"));
+
+        assert!(study.forms_signed_by("Nobody", "Some Site").is_empty());
+        assert!(study
+            .forms_signed_by("Project Manager", "No Site")
+            .is_empty());
+    }
+
+    #[test]
+    fn goes_by_the_role_each_form_was_signed_under() {
+        let (mut subjects, sites, users) = exports();
+        let forms = Arc::make_mut(subjects.patients[0].forms.as_mut().unwrap());
+        let states = Arc::make_mut(forms[0].states.as_mut().unwrap());
+        for signer in ["Paul Sanders - Data Manager", "Paul Sanders"] {
+            states.push(State {
+                signer: signer.into(),
+                ..states[0].clone()
+            });
+        }
+        let study = Study::new(&subjects, &sites, &users);
+
+        let user = study.user("1681162687395").unwrap();
+        assert!(study.roles_of(user).contains("Project Manager"));
+        assert!(!study.roles_of(user).contains("Data Manager"));
+
+        let signers = |role| {
+            study
+                .forms_signed_by(role, "Some Site")
+                .into_iter()
+                .map(|signature| &*signature.state.signer)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(signers("Data Manager"), ["Paul Sanders - Data Manager"]);
+        assert_eq!(
+            signers("Project Manager"),
+            ["Paul Sanders - Project Manager", "Paul Sanders"]
+        );
+    }
+
+    #[test]
+    fn reports_dangling_references() {
+        let (mut subjects, sites, users) = exports();
+        subjects.patients[0].site_unique_id = "missing".to_string();
+        let study = Study::new(&subjects, &sites, &users);
+
+        let dangling = study.dangling();
+        assert!(dangling.contains(&&Reference {
+            kind: ReferenceKind::PatientSite,
+            record: Node::Patient(&subjects.patients[0]),
+            form: None,
+            unique_id: "missing",
+        }));
+        assert!(dangling
+            .iter()
+            .all(|reference| reference.unique_id != "1681162687395"));
+        assert!(study
+            .references()
+            .iter()
+            .all(|reference| reference.unique_id != SYSTEM_SIGNER));
+    }
+}
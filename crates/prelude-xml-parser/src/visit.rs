@@ -0,0 +1,448 @@
+//! Walking every node of a parsed export without writing the nested loops by hand.
+//!
+//! [`Visitor`] has a hook for each kind of node. Each hook's default implementation carries on
+//! into the node's children through the matching `walk_*` function, so a visitor only overrides
+//! the hooks it cares about, and calls the `walk_*` function itself if it still wants the
+//! children. [`VisitorMut`] does the same over mutable references.
+//!
+//! Nodes are visited in document order: a record, then each of its forms, each form's
+//! categories, each category's fields followed by its files, and each field's or file's entries,
+//! comments and queries. A file's download history is not visited.
+//!
+//! ```
+//! use std::path::Path;
+//!
+//! use prelude_xml_parser::{
+//!     native::common::Entry, parse_subject_native_file, visit::Visitor,
+//! };
+//!
+//! #[derive(Default)]
+//! struct CountEntries(usize);
+//!
+//! impl Visitor<'_> for CountEntries {
+//!     fn visit_entry(&mut self, _entry: &Entry) {
+//!         self.0 += 1;
+//!     }
+//! }
+//!
+//! let native = parse_subject_native_file(Path::new("tests/assets/subject_native.xml")).unwrap();
+//! let mut counter = CountEntries::default();
+//! counter.visit_subject_native(&native);
+//!
+//! assert!(counter.0 > 0);
+//! ```
+
+use std::sync::Arc;
+
+use crate::native::{
+    common::{Category, Comment, Entry, Field, File, Form, Query},
+    site_native::{Site, SiteNative},
+    subject_native::{Patient, SubjectNative},
+    user_native::{User, UserNative},
+};
+
+/// Hooks called for each node of an export, borrowed for `'a`.
+pub trait Visitor<'a> {
+    fn visit_subject_native(&mut self, native: &'a SubjectNative) {
+        walk_subject_native(self, native);
+    }
+
+    fn visit_site_native(&mut self, native: &'a SiteNative) {
+        walk_site_native(self, native);
+    }
+
+    fn visit_user_native(&mut self, native: &'a UserNative) {
+        walk_user_native(self, native);
+    }
+
+    fn visit_patient(&mut self, patient: &'a Patient) {
+        walk_patient(self, patient);
+    }
+
+    fn visit_site(&mut self, site: &'a Site) {
+        walk_site(self, site);
+    }
+
+    fn visit_user(&mut self, user: &'a User) {
+        walk_user(self, user);
+    }
+
+    fn visit_form(&mut self, form: &'a Form) {
+        walk_form(self, form);
+    }
+
+    fn visit_category(&mut self, category: &'a Category) {
+        walk_category(self, category);
+    }
+
+    fn visit_field(&mut self, field: &'a Field) {
+        walk_field(self, field);
+    }
+
+    fn visit_file(&mut self, file: &'a File) {
+        walk_file(self, file);
+    }
+
+    fn visit_entry(&mut self, _entry: &'a Entry) {}
+
+    fn visit_comment(&mut self, _comment: &'a Comment) {}
+
+    fn visit_query(&mut self, _query: &'a Query) {}
+}
+
+/// The nodes behind an optional shared list, or none.
+fn each<T>(items: &Option<Arc<Vec<T>>>) -> &[T] {
+    items.as_deref().map_or(&[], Vec::as_slice)
+}
+
+pub fn walk_subject_native<'a, V: Visitor<'a> + ?Sized>(
+    visitor: &mut V,
+    native: &'a SubjectNative,
+) {
+    for patient in &native.patients {
+        visitor.visit_patient(patient);
+    }
+}
+
+pub fn walk_site_native<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, native: &'a SiteNative) {
+    for site in &native.sites {
+        visitor.visit_site(site);
+    }
+}
+
+pub fn walk_user_native<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, native: &'a UserNative) {
+    for user in &native.users {
+        visitor.visit_user(user);
+    }
+}
+
+pub fn walk_patient<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, patient: &'a Patient) {
+    for form in each(&patient.forms) {
+        visitor.visit_form(form);
+    }
+}
+
+pub fn walk_site<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, site: &'a Site) {
+    for form in each(&site.forms) {
+        visitor.visit_form(form);
+    }
+}
+
+pub fn walk_user<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, user: &'a User) {
+    for form in each(&user.forms) {
+        visitor.visit_form(form);
+    }
+}
+
+pub fn walk_form<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, form: &'a Form) {
+    for category in each(&form.categories) {
+        visitor.visit_category(category);
+    }
+}
+
+pub fn walk_category<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, category: &'a Category) {
+    for field in each(&category.fields) {
+        visitor.visit_field(field);
+    }
+    for file in each(&category.files) {
+        visitor.visit_file(file);
+    }
+}
+
+pub fn walk_field<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, field: &'a Field) {
+    for entry in each(&field.entries) {
+        visitor.visit_entry(entry);
+    }
+    for comment in each(&field.comments) {
+        visitor.visit_comment(comment);
+    }
+    for query in each(&field.queries) {
+        visitor.visit_query(query);
+    }
+}
+
+pub fn walk_file<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, file: &'a File) {
+    for entry in each(&file.entries) {
+        visitor.visit_entry(entry);
+    }
+    for comment in each(&file.comments) {
+        visitor.visit_comment(comment);
+    }
+    for query in each(&file.queries) {
+        visitor.visit_query(query);
+    }
+}
+
+/// Hooks called for each node of an export, which they may change.
+///
+/// Lists of nodes are shared between clones of an export, so walking into one that is shared
+/// copies it first and leaves the other clones as they were.
+pub trait VisitorMut {
+    fn visit_subject_native_mut(&mut self, native: &mut SubjectNative) {
+        walk_subject_native_mut(self, native);
+    }
+
+    fn visit_site_native_mut(&mut self, native: &mut SiteNative) {
+        walk_site_native_mut(self, native);
+    }
+
+    fn visit_user_native_mut(&mut self, native: &mut UserNative) {
+        walk_user_native_mut(self, native);
+    }
+
+    fn visit_patient_mut(&mut self, patient: &mut Patient) {
+        walk_patient_mut(self, patient);
+    }
+
+    fn visit_site_mut(&mut self, site: &mut Site) {
+        walk_site_mut(self, site);
+    }
+
+    fn visit_user_mut(&mut self, user: &mut User) {
+        walk_user_mut(self, user);
+    }
+
+    fn visit_form_mut(&mut self, form: &mut Form) {
+        walk_form_mut(self, form);
+    }
+
+    fn visit_category_mut(&mut self, category: &mut Category) {
+        walk_category_mut(self, category);
+    }
+
+    fn visit_field_mut(&mut self, field: &mut Field) {
+        walk_field_mut(self, field);
+    }
+
+    fn visit_file_mut(&mut self, file: &mut File) {
+        walk_file_mut(self, file);
+    }
+
+    fn visit_entry_mut(&mut self, _entry: &mut Entry) {}
+
+    fn visit_comment_mut(&mut self, _comment: &mut Comment) {}
+
+    fn visit_query_mut(&mut self, _query: &mut Query) {}
+}
+
+/// The nodes behind an optional shared list, copied out of the list first if it is shared.
+fn each_mut<T: Clone>(items: &mut Option<Arc<Vec<T>>>) -> &mut [T] {
+    match items {
+        Some(items) => Arc::make_mut(items).as_mut_slice(),
+        None => &mut [],
+    }
+}
+
+pub fn walk_subject_native_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    native: &mut SubjectNative,
+) {
+    for patient in &mut native.patients {
+        visitor.visit_patient_mut(patient);
+    }
+}
+
+pub fn walk_site_native_mut<V: VisitorMut + ?Sized>(visitor: &mut V, native: &mut SiteNative) {
+    for site in &mut native.sites {
+        visitor.visit_site_mut(site);
+    }
+}
+
+pub fn walk_user_native_mut<V: VisitorMut + ?Sized>(visitor: &mut V, native: &mut UserNative) {
+    for user in &mut native.users {
+        visitor.visit_user_mut(user);
+    }
+}
+
+pub fn walk_patient_mut<V: VisitorMut + ?Sized>(visitor: &mut V, patient: &mut Patient) {
+    for form in each_mut(&mut patient.forms) {
+        visitor.visit_form_mut(form);
+    }
+}
+
+pub fn walk_site_mut<V: VisitorMut + ?Sized>(visitor: &mut V, site: &mut Site) {
+    for form in each_mut(&mut site.forms) {
+        visitor.visit_form_mut(form);
+    }
+}
+
+pub fn walk_user_mut<V: VisitorMut + ?Sized>(visitor: &mut V, user: &mut User) {
+    for form in each_mut(&mut user.forms) {
+        visitor.visit_form_mut(form);
+    }
+}
+
+pub fn walk_form_mut<V: VisitorMut + ?Sized>(visitor: &mut V, form: &mut Form) {
+    for category in each_mut(&mut form.categories) {
+        visitor.visit_category_mut(category);
+    }
+}
+
+pub fn walk_category_mut<V: VisitorMut + ?Sized>(visitor: &mut V, category: &mut Category) {
+    for field in each_mut(&mut category.fields) {
+        visitor.visit_field_mut(field);
+    }
+    for file in each_mut(&mut category.files) {
+        visitor.visit_file_mut(file);
+    }
+}
+
+pub fn walk_field_mut<V: VisitorMut + ?Sized>(visitor: &mut V, field: &mut Field) {
+    for entry in each_mut(&mut field.entries) {
+        visitor.visit_entry_mut(entry);
+    }
+    for comment in each_mut(&mut field.comments) {
+        visitor.visit_comment_mut(comment);
+    }
+    for query in each_mut(&mut field.queries) {
+        visitor.visit_query_mut(query);
+    }
+}
+
+pub fn walk_file_mut<V: VisitorMut + ?Sized>(visitor: &mut V, file: &mut File) {
+    for entry in each_mut(&mut file.entries) {
+        visitor.visit_entry_mut(entry);
+    }
+    for comment in each_mut(&mut file.comments) {
+        visitor.visit_comment_mut(comment);
+    }
+    for query in each_mut(&mut file.queries) {
+        visitor.visit_query_mut(query);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::parse_subject_native_file;
+
+    fn subject() -> SubjectNative {
+        parse_subject_native_file(Path::new("tests/assets/subject_native.xml")).unwrap()
+    }
+
+    #[derive(Default)]
+    struct Collect<'a> {
+        forms: Vec<&'a str>,
+        fields: usize,
+        entries: usize,
+        comments: usize,
+    }
+
+    impl<'a> Visitor<'a> for Collect<'a> {
+        fn visit_form(&mut self, form: &'a Form) {
+            self.forms.push(&form.name);
+            walk_form(self, form);
+        }
+
+        fn visit_field(&mut self, field: &'a Field) {
+            self.fields += 1;
+            walk_field(self, field);
+        }
+
+        fn visit_entry(&mut self, _entry: &'a Entry) {
+            self.entries += 1;
+        }
+
+        fn visit_comment(&mut self, _comment: &'a Comment) {
+            self.comments += 1;
+        }
+    }
+
+    #[test]
+    fn visits_every_node_in_document_order() {
+        let native = subject();
+        let mut collect = Collect::default();
+        collect.visit_subject_native(&native);
+
+        let forms: Vec<&Form> = native
+            .patients
+            .iter()
+            .flat_map(|patient| each(&patient.forms))
+            .collect();
+        let fields: Vec<&Field> = forms
+            .iter()
+            .flat_map(|form| each(&form.categories))
+            .flat_map(|category| each(&category.fields))
+            .collect();
+
+        assert_eq!(
+            collect.forms,
+            forms.iter().map(|form| &*form.name).collect::<Vec<_>>()
+        );
+        assert_eq!(collect.fields, fields.len());
+        assert_eq!(
+            collect.entries,
+            fields
+                .iter()
+                .map(|field| each(&field.entries).len())
+                .sum::<usize>()
+        );
+        assert_eq!(
+            collect.comments,
+            fields
+                .iter()
+                .map(|field| each(&field.comments).len())
+                .sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn hooks_that_do_not_walk_skip_children() {
+        struct Patients(usize, usize);
+
+        impl Visitor<'_> for Patients {
+            fn visit_patient(&mut self, _patient: &Patient) {
+                self.0 += 1;
+            }
+
+            fn visit_form(&mut self, _form: &Form) {
+                self.1 += 1;
+            }
+        }
+
+        let native = subject();
+        let mut patients = Patients(0, 0);
+        patients.visit_subject_native(&native);
+
+        assert_eq!(patients.0, native.patients.len());
+        assert_eq!(patients.1, 0);
+    }
+
+    #[test]
+    fn mutable_visits_leave_clones_untouched() {
+        struct Redact;
+
+        impl VisitorMut for Redact {
+            fn visit_entry_mut(&mut self, entry: &mut Entry) {
+                if let Some(value) = &mut entry.value {
+                    value.value = "redacted".to_string();
+                }
+            }
+        }
+
+        let original = subject();
+        let mut redacted = original.clone();
+        Redact.visit_subject_native_mut(&mut redacted);
+
+        let values = |native: &SubjectNative| {
+            let mut values = Vec::new();
+            for patient in &native.patients {
+                for form in each(&patient.forms) {
+                    for category in each(&form.categories) {
+                        for field in each(&category.fields) {
+                            for entry in each(&field.entries) {
+                                values.extend(entry.value.as_ref().map(|v| v.value.clone()));
+                            }
+                        }
+                    }
+                }
+            }
+            values
+        };
+
+        assert!(values(&redacted).iter().all(|value| value == "redacted"));
+        assert!(values(&original).iter().any(|value| value == "Labrador"));
+    }
+}
@@ -6,8 +6,12 @@ from prelude_parser._prelude_parser import (
     Field,
     File,
     Form,
+    ListView,
     LockState,
+    Match,
+    PartialDate,
     Patient,
+    Progress,
     Query,
     Reason,
     Site,
@@ -25,7 +29,14 @@ from prelude_parser._prelude_parser import (
     parse_user_native_file,
     parse_user_native_string,
 )
-from prelude_parser.parser import parse_to_classes, parse_to_dict
+from prelude_parser.parser import (
+    flat_schema,
+    parse_flat_bytes,
+    parse_flat_string,
+    parse_to_classes,
+    parse_to_dict,
+)
+from prelude_parser.progress import tqdm_callback
 
 __all__ = [
     "Category",
@@ -35,8 +46,12 @@ __all__ = [
     "Field",
     "File",
     "Form",
+    "ListView",
     "LockState",
+    "Match",
+    "PartialDate",
     "Patient",
+    "Progress",
     "Query",
     "Reason",
     "Site",
@@ -47,6 +62,9 @@ __all__ = [
     "UserNative",
     "Value",
     "__version__",
+    "flat_schema",
+    "parse_flat_bytes",
+    "parse_flat_string",
     "parse_site_native_file",
     "parse_site_native_string",
     "parse_subject_native_file",
@@ -55,4 +73,5 @@ __all__ = [
     "parse_to_dict",
     "parse_user_native_file",
     "parse_user_native_string",
+    "tqdm_callback",
 ]
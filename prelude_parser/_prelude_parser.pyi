@@ -1,80 +1,120 @@
+# This file is generated from the Rust sources by `crates/prelude-parser-py/build.rs`.
+# Do not edit it by hand; run `just stubs` after changing a Python class or function.
+
 from __future__ import annotations
 
+from collections.abc import Callable, Iterator
 from datetime import datetime
 from pathlib import Path
+from typing import Generic, Literal, TypeVar, overload
 
-from prelude_parser.types import FlatFormInfo
+from prelude_parser.types import Dtype, FlatColumnSchema, FlatFormInfo
 
 __version__: str
 
-class Value:
-    by: str
-    by_unique_id: str | None
-    role: str
-    when: datetime | None
-    value: str
+_T = TypeVar("_T")
 
-    def to_dict(self) -> dict: ...
+class Category:
+    name: str
+    category_type: str
+    highest_index: int
+    fields: ListView[Field] | None
+    files: ListView[File] | None
+    obfuscated: bool
+    over_ride_highest_index: bool
 
-class Reason:
-    by: str
-    by_unique_id: str | None
-    role: str
-    when: datetime | None
-    value: str
-
-    def to_dict(self) -> dict: ...
-
-class Entry:
-    entry_id: str
-    reviewed_by: str | None
-    reviewed_by_unique_id: str | None
-    reviewed_by_when: datetime | None
-    value: Value | None
-    reason: Reason | None
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> Category: ...
+    @staticmethod
+    def from_dict(data: dict) -> Category:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
     def to_dict(self) -> dict: ...
 
 class Comment:
     comment_id: str
+    value: Value | None
     reviewed_by: str | None
     reviewed_by_unique_id: str | None
     reviewed_by_when: datetime | None
-    value: Value | None
-
-    def to_dict(self) -> dict: ...
 
-class LockState:
-    locked: bool
-    user: str | None
-    user_unique_id: str | None
-    date_time_changed: datetime | None
+    def __init__(
+        self,
+        comment_id: str,
+        value: Value | None = None,
+        *,
+        reviewed_by: str | None = None,
+        reviewed_by_unique_id: str | None = None,
+        reviewed_by_when: datetime | None = None,
+    ) -> None: ...
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> Comment: ...
+    @staticmethod
+    def from_dict(data: dict) -> Comment:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
     def to_dict(self) -> dict: ...
 
-class Query:
-    query_id: str
+class Entry:
+    entry_id: str
     reviewed_by: str | None
     reviewed_by_unique_id: str | None
     reviewed_by_when: datetime | None
     value: Value | None
-    answer: Value | None
+    reason: Reason | None
+
+    def __init__(
+        self,
+        entry_id: str,
+        value: Value | None = None,
+        reason: Reason | None = None,
+        *,
+        reviewed_by: str | None = None,
+        reviewed_by_unique_id: str | None = None,
+        reviewed_by_when: datetime | None = None,
+    ) -> None: ...
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> Entry: ...
+    @staticmethod
+    def from_dict(data: dict) -> Entry:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
     def to_dict(self) -> dict: ...
 
-class File:
-    name: str
-    file_type: str
-    data_type: str | None
-    error_code: str
-    when_created: datetime | None
-    keep_history: bool
-    file_location: str | None
-    size: int | None
-    entries: list[Entry] | None
-    comments: list[Comment] | None
-    queries: list[Query] | None
-    download_history: list[Comment] | None
+class Export:
+    """The attributes on the `export_from_vision_EDC` root element: who produced the export, when, and
+    how much of the study it covers.
+    """
+
+    date: datetime | None
+    created_by: str | None
+    role: str | None
+    number_subjects_processed: int | None
+    page_number: str | None
+    """Present when the export was split, e.g. `"1 of 6"`."""
+
+    def __init__(
+        self,
+        *,
+        date: datetime | None = None,
+        created_by: str | None = None,
+        role: str | None = None,
+        number_subjects_processed: int | None = None,
+        page_number: str | None = None,
+    ) -> None: ...
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> Export: ...
+    @staticmethod
+    def from_dict(data: dict) -> Export:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
     def to_dict(self) -> dict: ...
 
@@ -85,28 +125,41 @@ class Field:
     error_code: str
     when_created: datetime | None
     keep_history: bool
-    entries: list[Entry] | None
-    comments: list[Comment] | None
-    queries: list[Query] | None
+    entries: ListView[Entry] | None
+    comments: ListView[Comment] | None
+    queries: ListView[Query] | None
+
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> Field: ...
+    @staticmethod
+    def from_dict(data: dict) -> Field:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
     def to_dict(self) -> dict: ...
 
-class Category:
+class File:
     name: str
-    category_type: str
-    highest_index: int
-    obfuscated: bool
-    over_ride_highest_index: bool
-    fields: list[Field] | None
-    files: list[File] | None
-
-    def to_dict(self) -> dict: ...
-
-class State:
-    value: str
-    signer: str
-    signer_unique_id: str
-    date_signed: datetime | None
+    file_type: str
+    data_type: str | None
+    error_code: str
+    when_created: datetime | None
+    keep_history: bool
+    file_location: str | None
+    size: int | None
+    entries: ListView[Entry] | None
+    comments: ListView[Comment] | None
+    queries: ListView[Query] | None
+    download_history: ListView[Comment] | None
+
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> File: ...
+    @staticmethod
+    def from_dict(data: dict) -> File:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
     def to_dict(self) -> dict: ...
 
@@ -125,12 +178,107 @@ class Form:
     form_index: int
     form_group: str | None
     form_state: str
-    lock_states: list[LockState] | None
-    states: list[State] | None
-    categories: list[Category] | None
+    states: ListView[State] | None
+    lock_states: ListView[LockState] | None
+    categories: ListView[Category] | None
+
+    def __getitem__(self, name: str) -> Field:
+        """Look up a field of the form by name"""
+
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> Form: ...
+    @staticmethod
+    def from_dict(data: dict) -> Form:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
+
+    def to_dict(self) -> dict: ...
+
+class ListView(Generic[_T]):
+    """A read-only sequence over a list in an export, copying only the items that are accessed."""
+
+    def __len__(self) -> int: ...
+    @overload
+    def __getitem__(self, index: int) -> _T: ...
+    @overload
+    def __getitem__(self, index: slice) -> ListView[_T]: ...
+    def __iter__(self) -> Iterator[_T]: ...
+    def __repr__(self) -> str: ...
+    def to_list(self) -> list[_T]:
+        """Copy the items into a list"""
+
+class LockState:
+    locked: bool
+    user: str | None
+    user_unique_id: str | None
+    date_time_changed: datetime | None
+
+    def __init__(
+        self,
+        locked: bool,
+        *,
+        user: str | None = None,
+        user_unique_id: str | None = None,
+        date_time_changed: datetime | None = None,
+    ) -> None: ...
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> LockState: ...
+    @staticmethod
+    def from_dict(data: dict) -> LockState:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
     def to_dict(self) -> dict: ...
 
+class Match:
+    """A selector match as handed to Python, holding a copy of each node in its ancestry."""
+
+    path: str
+    """The ancestry written out as by the command line's `select`."""
+    patient: Patient | None
+    site: Site | None
+    user: User | None
+    form: Form | None
+    state: State | None
+    category: Category | None
+    field: Field | None
+    file: File | None
+    entry: Entry | None
+    comment: Comment | None
+    query: Query | None
+    value: Value | None
+
+    def __repr__(self) -> str: ...
+
+class Progress:
+    """How far a native parse has got, passed to the `progress` callback."""
+
+    records: int
+    total_records: int
+    bytes: int
+    total_bytes: int
+
+    def __repr__(self) -> str: ...
+
+class PartialDate:
+    """A date that may be missing its day, month or year."""
+
+    year: int | None
+    month: int | None
+    day: int | None
+
+    def __init__(
+        self,
+        year: int | None = None,
+        month: int | None = None,
+        day: int | None = None,
+    ) -> None: ...
+    def __repr__(self) -> str: ...
+    def __str__(self) -> str:
+        """The date in ISO order with `UN` for the unknown parts, e.g. `2023-01-UN`"""
+
 class Patient:
     patient_id: str
     unique_id: str
@@ -141,7 +289,69 @@ class Patient:
     site_unique_id: str
     last_language: str | None
     number_of_forms: int
-    forms: list[Form] | None
+    forms: ListView[Form] | None
+
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> Patient: ...
+    @staticmethod
+    def from_dict(data: dict) -> Patient:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
+
+    def to_dict(self) -> dict: ...
+
+class Query:
+    query_id: str
+    reviewed_by: str | None
+    reviewed_by_unique_id: str | None
+    reviewed_by_when: datetime | None
+    value: Value | None
+    answer: Value | None
+
+    def __init__(
+        self,
+        query_id: str,
+        value: Value | None = None,
+        answer: Value | None = None,
+        *,
+        reviewed_by: str | None = None,
+        reviewed_by_unique_id: str | None = None,
+        reviewed_by_when: datetime | None = None,
+    ) -> None: ...
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> Query: ...
+    @staticmethod
+    def from_dict(data: dict) -> Query:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
+
+    def to_dict(self) -> dict: ...
+
+class Reason:
+    by: str
+    by_unique_id: str | None
+    role: str
+    when: datetime | None
+    value: str
+
+    def __init__(
+        self,
+        value: str,
+        by: str,
+        role: str,
+        *,
+        by_unique_id: str | None = None,
+        when: datetime | None = None,
+    ) -> None: ...
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> Reason: ...
+    @staticmethod
+    def from_dict(data: dict) -> Reason:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
     def to_dict(self) -> dict: ...
 
@@ -153,61 +363,274 @@ class Site:
     when_created: datetime | None
     creator: str
     number_of_forms: int
-    forms: list[Form] | None
+    forms: ListView[Form] | None
+
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> Site: ...
+    @staticmethod
+    def from_dict(data: dict) -> Site:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
     def to_dict(self) -> dict: ...
 
-class User:
-    unique_id: str
-    last_language: str | None
-    creator: str
-    number_of_forms: int
-    forms: list[Form] | None
+class SiteNative:
+    """Contains the information from the Prelude native site XML."""
 
-    def to_dict(self) -> dict: ...
+    export: Export | None
+    sites: ListView[Site]
 
-class Export:
-    date: datetime | None
-    created_by: str | None
-    role: str | None
-    number_subjects_processed: int | None
-    page_number: str | None
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> SiteNative: ...
+    @staticmethod
+    def from_dict(data: dict) -> SiteNative:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
-    def to_dict(self) -> dict: ...
+    def to_dict(self) -> dict:
+        """Convert the class instance to a dictionary"""
 
-class SiteNative:
-    export: Export | None
-    sites: list[Site]
+    def __getitem__(self, name: str) -> Site:
+        """Look up a site by `name`"""
+
+    def by_unique_id(self, unique_id: str) -> Site:
+        """Look up a site by `unique_id`"""
+
+    def to_json(self) -> str:
+        """Convert the class instance to a JSON string"""
+
+    @staticmethod
+    def from_json(json: str) -> SiteNative:
+        """Create the class instance from a JSON string such as the one `to_json` gives"""
+
+    def select(self, expression: str) -> list[Match]:
+        """Find the nodes matching a selector such as `form[name=demographics]/category/field`"""
+
+class State:
+    value: str
+    signer: str
+    signer_unique_id: str
+    date_signed: datetime | None
+
+    def __init__(
+        self,
+        value: str,
+        signer: str,
+        signer_unique_id: str,
+        date_signed: datetime | None = None,
+    ) -> None: ...
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> State: ...
+    @staticmethod
+    def from_dict(data: dict) -> State:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
     def to_dict(self) -> dict: ...
-    def to_json(self) -> str: ...
 
 class SubjectNative:
+    """Contains the information from the Prelude native subject XML."""
+
     export: Export | None
-    patients: list[Patient]
+    patients: ListView[Patient]
+
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> SubjectNative: ...
+    @staticmethod
+    def from_dict(data: dict) -> SubjectNative:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
+
+    def to_dict(self) -> dict:
+        """Convert the class instance to a dictionary"""
+
+    @staticmethod
+    def merge(pages: list[SubjectNative]) -> SubjectNative:
+        """Combine the pages of a split export into one"""
+
+    def __getitem__(self, patient_id: str) -> Patient:
+        """Look up a patient by `patient_id`"""
+
+    def by_unique_id(self, unique_id: str) -> Patient:
+        """Look up a patient by `unique_id`"""
+
+    def by_site(self, site_name: str) -> list[Patient]:
+        """The patients of the site named `site_name`, in document order"""
+
+    def to_json(self) -> str:
+        """Convert the class instance to a JSON string"""
+
+    @staticmethod
+    def from_json(json: str) -> SubjectNative:
+        """Create the class instance from a JSON string such as the one `to_json` gives"""
+
+    def select(self, expression: str) -> list[Match]:
+        """Find the nodes matching a selector such as `form[name=demographics]/category/field`"""
+
+class User:
+    unique_id: str
+    last_language: str | None
+    creator: str
+    number_of_forms: int
+    forms: ListView[Form] | None
+
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> User: ...
+    @staticmethod
+    def from_dict(data: dict) -> User:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
     def to_dict(self) -> dict: ...
-    def to_json(self) -> str: ...
 
 class UserNative:
+    """Contains the information from the Prelude native user XML."""
+
     export: Export | None
-    users: list[User]
+    users: ListView[User]
+
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> UserNative: ...
+    @staticmethod
+    def from_dict(data: dict) -> UserNative:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
+
+    def to_dict(self) -> dict:
+        """Convert the class instance to a dictionary"""
+
+    def __getitem__(self, unique_id: str) -> User:
+        """Look up a user by `unique_id`"""
+
+    def to_json(self) -> str:
+        """Convert the class instance to a JSON string"""
+
+    @staticmethod
+    def from_json(json: str) -> UserNative:
+        """Create the class instance from a JSON string such as the one `to_json` gives"""
+
+    def select(self, expression: str) -> list[Match]:
+        """Find the nodes matching a selector such as `form[name=demographics]/category/field`"""
+
+class Value:
+    by: str
+    by_unique_id: str | None
+    role: str
+    when: datetime | None
+    value: str
+
+    def __init__(
+        self,
+        value: str,
+        by: str,
+        role: str,
+        *,
+        by_unique_id: str | None = None,
+        when: datetime | None = None,
+    ) -> None: ...
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[object, tuple[bytes]]: ...
+    @staticmethod
+    def _unpickle(state: bytes) -> Value: ...
+    @staticmethod
+    def from_dict(data: dict) -> Value:
+        """Create the class instance from a dictionary such as the one `to_dict` gives"""
 
     def to_dict(self) -> dict: ...
-    def to_json(self) -> str: ...
 
 def _parse_flat_file_to_dict(
-    xml_file: str | Path, *, short_names: bool = False
+    xml_file: str | Path,
+    *,
+    short_names: bool = False,
+    dtypes: dict[str, dict[str, Dtype]] | None = None,
+) -> dict[str, FlatFormInfo]: ...
+def _parse_flat_string_to_dict(
+    xml_str: str, *, short_names: bool = False, dtypes: dict[str, dict[str, Dtype]] | None = None
+) -> dict[str, FlatFormInfo]: ...
+def _parse_flat_bytes_to_dict(
+    xml_bytes: bytes,
+    *,
+    short_names: bool = False,
+    dtypes: dict[str, dict[str, Dtype]] | None = None,
 ) -> dict[str, FlatFormInfo]: ...
 def _parse_flat_file_to_pandas_dict(
-    xml_file: str | Path, *, short_names: bool = False
+    xml_file: str | Path,
+    *,
+    short_names: bool = False,
+    dtypes: dict[str, dict[str, Dtype]] | None = None,
 ) -> dict[str, FlatFormInfo]: ...
-def parse_site_native_file(xml_file: str | Path) -> SiteNative: ...
-def parse_site_native_string(xml_str: str) -> SiteNative: ...
-def parse_subject_native_file(xml_file: str | Path) -> SubjectNative: ...
-def parse_subject_native_string(xml_str: str) -> SubjectNative: ...
-def parse_user_native_file(xml_file: str | Path) -> UserNative: ...
-def parse_user_native_string(xml_str: str) -> UserNative: ...
+def _parse_flat_string_to_pandas_dict(
+    xml_str: str, *, short_names: bool = False, dtypes: dict[str, dict[str, Dtype]] | None = None
+) -> dict[str, FlatFormInfo]: ...
+def _parse_flat_bytes_to_pandas_dict(
+    xml_bytes: bytes,
+    *,
+    short_names: bool = False,
+    dtypes: dict[str, dict[str, Dtype]] | None = None,
+) -> dict[str, FlatFormInfo]: ...
+def _flat_schema_file(
+    xml_file: str | Path, *, short_names: bool = False
+) -> dict[str, dict[str, FlatColumnSchema]]: ...
+def _flat_schema_string(
+    xml_str: str, *, short_names: bool = False
+) -> dict[str, dict[str, FlatColumnSchema]]: ...
+def _flat_schema_bytes(
+    xml_bytes: bytes, *, short_names: bool = False
+) -> dict[str, dict[str, FlatColumnSchema]]: ...
+def parse_site_native_file(
+    xml_file: str | Path,
+    *,
+    history: Literal["full", "current_only"] = "full",
+    skip_export: bool = False,
+    threads: int | None = None,
+    progress: Callable[[Progress], object] | None = None,
+) -> SiteNative: ...
+def parse_site_native_string(
+    xml_str: str,
+    *,
+    history: Literal["full", "current_only"] = "full",
+    skip_export: bool = False,
+    threads: int | None = None,
+    progress: Callable[[Progress], object] | None = None,
+) -> SiteNative: ...
+def parse_subject_native_file(
+    xml_file: str | Path,
+    *,
+    history: Literal["full", "current_only"] = "full",
+    skip_export: bool = False,
+    threads: int | None = None,
+    progress: Callable[[Progress], object] | None = None,
+) -> SubjectNative: ...
+def parse_subject_native_string(
+    xml_str: str,
+    *,
+    history: Literal["full", "current_only"] = "full",
+    skip_export: bool = False,
+    threads: int | None = None,
+    progress: Callable[[Progress], object] | None = None,
+) -> SubjectNative: ...
+def parse_user_native_file(
+    xml_file: str | Path,
+    *,
+    history: Literal["full", "current_only"] = "full",
+    skip_export: bool = False,
+    threads: int | None = None,
+    progress: Callable[[Progress], object] | None = None,
+) -> UserNative: ...
+def parse_user_native_string(
+    xml_str: str,
+    *,
+    history: Literal["full", "current_only"] = "full",
+    skip_export: bool = False,
+    threads: int | None = None,
+    progress: Callable[[Progress], object] | None = None,
+) -> UserNative: ...
 
 class FileNotFoundError(Exception):
     pass
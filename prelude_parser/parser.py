@@ -1,22 +1,71 @@
 from __future__ import annotations
 
+import os
 from pathlib import Path
-from typing import TYPE_CHECKING, Any
+from typing import IO, TYPE_CHECKING, Any, Callable, TypeVar
 
 from camel_converter import to_pascal
 
-from prelude_parser._prelude_parser import _parse_flat_file_to_dict
+from prelude_parser._prelude_parser import (
+    _flat_schema_bytes,
+    _flat_schema_file,
+    _flat_schema_string,
+    _parse_flat_bytes_to_dict,
+    _parse_flat_file_to_dict,
+    _parse_flat_string_to_dict,
+)
 
 if TYPE_CHECKING:
-    from prelude_parser.types import FlatFormInfo
+    from prelude_parser.types import Dtype, FlatColumnSchema, FlatFormInfo
 
+_T = TypeVar("_T")
 
-def parse_to_dict(xml_file: str | Path, *, short_names: bool = False) -> dict[str, FlatFormInfo]:
+
+def _parse_flat(
+    xml_file: str | Path | IO[str] | IO[bytes],
+    parse_file: Callable[[str | Path], _T],
+    parse_string: Callable[[str], _T],
+    parse_bytes: Callable[[bytes], _T],
+) -> _T:
+    """Parse a path with `parse_file`, or the contents of a file-like object with `parse_string`
+    or `parse_bytes` depending on whether it was opened in text or binary mode.
+    """
+    if isinstance(xml_file, (str, os.PathLike)):
+        return parse_file(xml_file)
+
+    contents = xml_file.read()
+    if isinstance(contents, str):
+        return parse_string(contents)
+
+    return parse_bytes(contents)
+
+
+def _category_columns(dtypes: dict[str, dict[str, Dtype]] | None) -> list[str]:
+    """The columns `dtypes` asks to be categorical, which a `DataFrame` has to convert itself."""
+    return sorted(
+        {
+            column
+            for columns in (dtypes or {}).values()
+            for column, dtype in columns.items()
+            if dtype == "category"
+        }
+    )
+
+
+def parse_to_dict(
+    xml_file: str | Path | IO[str] | IO[bytes],
+    *,
+    short_names: bool = False,
+    dtypes: dict[str, dict[str, Dtype]] | None = None,
+) -> dict[str, FlatFormInfo]:
     """Parse a Prelude flat XML file into a dict.
 
     Args:
-        xml_file: The path to the XML file to parser.
+        xml_file: The path to the XML file to parser, or a file-like object to read it from.
         short_names: Set to True if short names were used in the export.
+        dtypes: Column types to use instead of inferring them, by form and then column name. For
+            example `{"demographics": {"subject_number": "str"}}`. Naming a form or column that
+            is not in the export raises a ValueError.
 
     Returns:
         A Python dictionary containing the data from the XML file.
@@ -25,10 +74,105 @@ def parse_to_dict(xml_file: str | Path, *, short_names: bool = False) -> dict[st
         >>> from prelude_parser import parse_to_dict
         >>> data = parse_to_dict("physical_examination.xml")
     """
-    return _parse_flat_file_to_dict(xml_file, short_names=short_names)
+    return _parse_flat(
+        xml_file,
+        lambda path: _parse_flat_file_to_dict(path, short_names=short_names, dtypes=dtypes),
+        lambda xml_str: parse_flat_string(xml_str, short_names=short_names, dtypes=dtypes),
+        lambda xml_bytes: parse_flat_bytes(xml_bytes, short_names=short_names, dtypes=dtypes),
+    )
+
+
+def parse_flat_string(
+    xml_str: str,
+    *,
+    short_names: bool = False,
+    dtypes: dict[str, dict[str, Dtype]] | None = None,
+) -> dict[str, FlatFormInfo]:
+    """Parse the contents of a Prelude flat XML file into a dict.
+
+    Args:
+        xml_str: The XML to parse.
+        short_names: Set to True if short names were used in the export.
+        dtypes: Column types to use instead of inferring them, by form and then column name. For
+            example `{"demographics": {"subject_number": "str"}}`. Naming a form or column that
+            is not in the export raises a ValueError.
+
+    Returns:
+        A Python dictionary containing the data from the XML.
+
+    Examples:
+        >>> from prelude_parser import parse_flat_string
+        >>> data = parse_flat_string(request.text)
+    """
+    return _parse_flat_string_to_dict(xml_str, short_names=short_names, dtypes=dtypes)
+
+
+def parse_flat_bytes(
+    xml_bytes: bytes,
+    *,
+    short_names: bool = False,
+    dtypes: dict[str, dict[str, Dtype]] | None = None,
+) -> dict[str, FlatFormInfo]:
+    """Parse the undecoded contents of a Prelude flat XML file into a dict.
+
+    The bytes are decoded using the `encoding` declared in the XML prolog, or the byte order mark,
+    the same as when parsing a file.
+
+    Args:
+        xml_bytes: The XML to parse.
+        short_names: Set to True if short names were used in the export.
+        dtypes: Column types to use instead of inferring them, by form and then column name. For
+            example `{"demographics": {"subject_number": "str"}}`. Naming a form or column that
+            is not in the export raises a ValueError.
+
+    Returns:
+        A Python dictionary containing the data from the XML.
+
+    Examples:
+        >>> from prelude_parser import parse_flat_bytes
+        >>> data = parse_flat_bytes(request.content)
+    """
+    return _parse_flat_bytes_to_dict(xml_bytes, short_names=short_names, dtypes=dtypes)
+
+
+def flat_schema(
+    xml_file: str | Path | IO[str] | IO[bytes],
+    *,
+    short_names: bool = False,
+) -> dict[str, dict[str, FlatColumnSchema]]:
+    """Describe the columns inferred for each form of a Prelude flat XML file.
 
+    Each column gets the type it was inferred as, how many records have it empty or missing, how
+    many distinct values it has and, if values that do not fit the type the rest of the column
+    would have forced it to text, the first such value. A column that is empty in every record has
+    the type `"unknown"`.
 
-def parse_to_classes(xml_file: str | Path, short_names: bool = False) -> list[Any]:
+    Args:
+        xml_file: The path to the XML file to parser, or a file-like object to read it from.
+        short_names: Set to True if short names were used in the export.
+
+    Returns:
+        A dictionary of the columns of each form, by form and then column name.
+
+    Examples:
+        >>> from prelude_parser import flat_schema
+        >>> schema = flat_schema("physical_examination.xml")
+        >>> schema["physical_examination"]["weight"]["widened_by"]
+        'not done'
+    """
+    return _parse_flat(
+        xml_file,
+        lambda path: _flat_schema_file(path, short_names=short_names),
+        lambda xml_str: _flat_schema_string(xml_str, short_names=short_names),
+        lambda xml_bytes: _flat_schema_bytes(xml_bytes, short_names=short_names),
+    )
+
+
+def parse_to_classes(
+    xml_file: str | Path | IO[str] | IO[bytes],
+    short_names: bool = False,
+    dtypes: dict[str, dict[str, Dtype]] | None = None,
+) -> list[Any]:
     """Parse a Prelude flat XML file into a list of Python objects.
 
     One class is created per form, named after the form name node in the XML file converted to
@@ -36,8 +180,11 @@ def parse_to_classes(xml_file: str | Path, short_names: bool = False) -> list[An
     <physical_examination> node will result in PhysicalExamination instances.
 
     Args:
-        xml_file: The path to the XML file to parser.
+        xml_file: The path to the XML file to parser, or a file-like object to read it from.
         short_names: Set to True if short names were used in the export.
+        dtypes: Column types to use instead of inferring them, by form and then column name. For
+            example `{"demographics": {"subject_number": "str"}}`. Naming a form or column that
+            is not in the export raises a ValueError.
 
     Returns:
         A list of Python objects containing the data from the XML file.
@@ -46,7 +193,7 @@ def parse_to_classes(xml_file: str | Path, short_names: bool = False) -> list[An
         >>> from prelude_parser import parse_to_classes
         >>> data = parse_to_classes("physical_examination.xml")
     """
-    parsed = parse_to_dict(xml_file, short_names=short_names)
+    parsed = parse_to_dict(xml_file, short_names=short_names, dtypes=dtypes)
     formatted: list[Any] = []
     for form, data in parsed.items():
         form_class = type(to_pascal(form), (object,), {})
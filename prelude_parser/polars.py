@@ -1,8 +1,18 @@
 from __future__ import annotations
 
 from pathlib import Path
+from typing import IO, TYPE_CHECKING
+
+from prelude_parser._prelude_parser import (
+    _parse_flat_bytes_to_pandas_dict,
+    _parse_flat_file_to_pandas_dict,
+    _parse_flat_string_to_pandas_dict,
+)
+from prelude_parser.parser import _category_columns, _parse_flat
+
+if TYPE_CHECKING:
+    from prelude_parser.types import Dtype, FieldInfo
 
-from prelude_parser._prelude_parser import _parse_flat_file_to_pandas_dict
 
 try:
     import polars as pl
@@ -12,15 +22,35 @@ except ImportError as e:  # pragma: no cover
     ) from e
 
 
-def to_dataframe(xml_file: str | Path, *, short_names: bool = False) -> pl.DataFrame:
+def _frame(
+    data: dict[str, list[FieldInfo]], dtypes: dict[str, dict[str, Dtype]] | None
+) -> pl.DataFrame:
+    df = pl.from_dict(data)
+    categories = [column for column in _category_columns(dtypes) if column in df.columns]
+    if categories:
+        df = df.with_columns(pl.col(categories).cast(pl.Categorical))
+
+    return df
+
+
+def to_dataframe(
+    xml_file: str | Path | IO[str] | IO[bytes],
+    *,
+    short_names: bool = False,
+    dtypes: dict[str, dict[str, Dtype]] | None = None,
+) -> pl.DataFrame:
     """Parse a Prelude flat XML file into a Polars DataFrame.
 
     This works for Prelude flat XML files that were exported with the "write tables to separate
     files" option.
 
     Args:
-        xml_file: The path to the XML file to parser.
+        xml_file: The path to the XML file to parser, or a file-like object to read it from.
         short_names: Set to True if short names were used in the export.
+        dtypes: Column types to use instead of inferring them, by form and then column name. For
+            example `{"demographics": {"subject_number": "str"}}`. The forms of a DataFrame share
+            their columns, so a type given for a column in one form applies to it in all of them.
+            Naming a form or column that is not in the export raises a ValueError.
 
     Returns:
         A Polars DataFrame the data from the XML file.
@@ -29,5 +59,68 @@ def to_dataframe(xml_file: str | Path, *, short_names: bool = False) -> pl.DataF
         >>> from prelude_parser.polars import to_dataframe
         >>> df = to_dataframe("physical_examination.xml")
     """
-    data = _parse_flat_file_to_pandas_dict(xml_file, short_names=short_names)
-    return pl.from_dict(data)
+    return _parse_flat(
+        xml_file,
+        lambda path: _frame(
+            _parse_flat_file_to_pandas_dict(path, short_names=short_names, dtypes=dtypes), dtypes
+        ),
+        lambda xml_str: parse_flat_string(xml_str, short_names=short_names, dtypes=dtypes),
+        lambda xml_bytes: parse_flat_bytes(xml_bytes, short_names=short_names, dtypes=dtypes),
+    )
+
+
+def parse_flat_string(
+    xml_str: str,
+    *,
+    short_names: bool = False,
+    dtypes: dict[str, dict[str, Dtype]] | None = None,
+) -> pl.DataFrame:
+    """Parse the contents of a Prelude flat XML file into a Polars DataFrame.
+
+    Args:
+        xml_str: The XML to parse.
+        short_names: Set to True if short names were used in the export.
+        dtypes: Column types to use instead of inferring them, by form and then column name. For
+            example `{"demographics": {"subject_number": "str"}}`. The forms of a DataFrame share
+            their columns, so a type given for a column in one form applies to it in all of them.
+            Naming a form or column that is not in the export raises a ValueError.
+
+    Returns:
+        A Polars DataFrame the data from the XML.
+
+    Examples:
+        >>> from prelude_parser.polars import parse_flat_string
+        >>> df = parse_flat_string(request.text)
+    """
+    data = _parse_flat_string_to_pandas_dict(xml_str, short_names=short_names, dtypes=dtypes)
+    return _frame(data, dtypes)
+
+
+def parse_flat_bytes(
+    xml_bytes: bytes,
+    *,
+    short_names: bool = False,
+    dtypes: dict[str, dict[str, Dtype]] | None = None,
+) -> pl.DataFrame:
+    """Parse the undecoded contents of a Prelude flat XML file into a Polars DataFrame.
+
+    The bytes are decoded using the `encoding` declared in the XML prolog, or the byte order mark,
+    the same as when parsing a file.
+
+    Args:
+        xml_bytes: The XML to parse.
+        short_names: Set to True if short names were used in the export.
+        dtypes: Column types to use instead of inferring them, by form and then column name. For
+            example `{"demographics": {"subject_number": "str"}}`. The forms of a DataFrame share
+            their columns, so a type given for a column in one form applies to it in all of them.
+            Naming a form or column that is not in the export raises a ValueError.
+
+    Returns:
+        A Polars DataFrame the data from the XML.
+
+    Examples:
+        >>> from prelude_parser.polars import parse_flat_bytes
+        >>> df = parse_flat_bytes(request.content)
+    """
+    data = _parse_flat_bytes_to_pandas_dict(xml_bytes, short_names=short_names, dtypes=dtypes)
+    return _frame(data, dtypes)
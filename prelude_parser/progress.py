@@ -0,0 +1,35 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from typing import TYPE_CHECKING, Any
+
+if TYPE_CHECKING:
+    from prelude_parser._prelude_parser import Progress
+
+
+def tqdm_callback(bar: Any) -> Callable[[Progress], None]:
+    """Build a `progress` callback for the native parsers that drives a tqdm progress bar.
+
+    The bar counts bytes of the document. Its total is filled in from the first report, so the bar
+    can be created without one.
+
+    Args:
+        bar: The tqdm progress bar to update.
+
+    Returns:
+        A function to pass as the `progress` argument of a native parser.
+
+    Examples:
+        >>> from tqdm import tqdm
+        >>> from prelude_parser import parse_subject_native_file, tqdm_callback
+        >>> with tqdm(unit="B", unit_scale=True) as bar:
+        ...     data = parse_subject_native_file("subject.xml", progress=tqdm_callback(bar))
+    """
+
+    def update(progress: Progress) -> None:
+        if bar.total != progress.total_bytes:
+            bar.total = progress.total_bytes
+            bar.refresh()
+        bar.update(progress.bytes - bar.n)
+
+    return update
@@ -1,4 +1,24 @@
-from datetime import date, datetime  # pragma: no cover
+from datetime import date, datetime, time  # pragma: no cover
+from typing import Literal, TypedDict  # pragma: no cover
 
-FieldInfo = str | int | float | date | datetime | None  # pragma: no cover
+from prelude_parser._prelude_parser import PartialDate  # pragma: no cover
+
+FieldInfo = (  # pragma: no cover
+    str | int | float | bool | date | datetime | time | PartialDate | None
+)
 FlatFormInfo = list[dict[str, FieldInfo]]  # pragma: no cover
+Dtype = Literal[  # pragma: no cover
+    "str", "int", "float", "date", "datetime", "time", "partial_date", "bool", "category"
+]
+
+
+class FlatColumnSchema(TypedDict):  # pragma: no cover
+    """What was inferred about one column of a flat export.
+
+    `dtype` is `"unknown"` for a column that is empty in every record.
+    """
+
+    dtype: Dtype | Literal["unknown"]
+    null_count: int
+    distinct_count: int
+    widened_by: str | None
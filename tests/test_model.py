@@ -0,0 +1,283 @@
+import ast
+import copy
+import json
+import pickle
+import re
+from datetime import datetime, timedelta, timezone
+from pathlib import Path
+
+import pytest
+
+import prelude_parser
+from prelude_parser import (
+    Entry,
+    Export,
+    ListView,
+    LockState,
+    Reason,
+    SiteNative,
+    State,
+    SubjectNative,
+    UserNative,
+    Value,
+    parse_site_native_file,
+    parse_subject_native_string,
+    parse_user_native_file,
+)
+
+STUB_PATH = Path(prelude_parser.__file__).parent / "_prelude_parser.pyi"
+
+# One of everything the native model holds, so that every type is reached.
+XML = """<?xml version="1.0" encoding="UTF-8"?>
+<export_from_vision_EDC date="30-May-2024 10:35 -0500" createdBy="Test User" role="Project Manager" numberSubjectsProcessed="1" pageNumber="1 of 1">
+  <patient patientId="TEST-001" uniqueId="1" whenCreated="2023-04-15 12:09:02 -0400" passwordChangeDate="2023-04-15 12:09:02 -0400" creator="Test User" siteName="Test Site" siteUniqueId="2" lastLanguage="English" numberOfForms="1">
+    <form name="test.form" lastModified="2023-04-15 12:09:15 -0400" whoLastModifiedName="Test User" whoLastModifiedRole="Manager" whenCreated="1681574905839" hasErrors="false" hasWarnings="false" locked="true" user="Test User" dateTimeChanged="2023-04-15 12:09:15 -0400" formTitle="Test Form" formIndex="1" formGroup="Test" formState="In-Work">
+      <state value="form.state.in.work" signer="Test User - Manager" signerUniqueId="1" dateSigned="2023-04-15 12:09:02 -0400" />
+      <lockState locked="true" user="Test User" userUniqueId="1" dateTimeChanged="2023-04-15 12:09:02 -0400" />
+      <category name="Test Category" type="normal" highestIndex="0">
+        <field name="test_field" type="text" dataType="string" errorCode="valid" whenCreated="2023-04-15 12:08:26 -0400" keepHistory="true">
+          <entry id="1" reviewedBy="Test User" reviewedByUniqueId="1" reviewedByWhen="2023-04-15 12:09:02 -0400">
+            <value by="Test User" byUniqueId="1" role="Manager" when="2023-04-15 12:09:02 -0400">Current</value>
+            <reason by="Test User" byUniqueId="1" role="Manager" when="2023-04-15 12:09:02 -0400">Corrected</reason>
+          </entry>
+          <comment id="1" reviewedBy="Test User" reviewedByUniqueId="1" reviewedByWhen="2023-04-15 12:09:02 -0400">
+            <value by="Test User" byUniqueId="1" role="Manager" when="2023-04-15 12:09:02 -0400">A comment</value>
+          </comment>
+          <query id="1" reviewedBy="Test User" reviewedByUniqueId="1" reviewedByWhen="2023-04-15 12:09:02 -0400">
+            <value by="Test User" byUniqueId="1" role="Manager" when="2023-04-15 12:09:02 -0400">A query</value>
+            <answer by="Test User" byUniqueId="1" role="Manager" when="2023-04-15 12:09:02 -0400">An answer</answer>
+          </query>
+        </field>
+        <file name="test_file" type="file" dataType="file" errorCode="valid" whenCreated="2023-04-15 12:08:26 -0400" keepHistory="true" fileLocation="files/new.pdf" size="10">
+          <entry id="1">
+            <value by="Test User" byUniqueId="1" role="Manager" when="2023-04-15 12:09:02 -0400">new.pdf</value>
+          </entry>
+          <downloadHistory id="1">
+            <value by="Test User" byUniqueId="1" role="Manager" when="2023-04-15 12:09:02 -0400">Downloaded</value>
+          </downloadHistory>
+        </file>
+      </category>
+    </form>
+  </patient>
+</export_from_vision_EDC>"""
+
+
+# Serialized keys that keep the name of the XML element rather than the Rust field.
+RENAMED = {"form": "forms"}
+
+
+def _snake(name):
+    return RENAMED.get(name) or re.sub(r"(?<!^)(?=[A-Z])", "_", name).lower()
+
+
+def _stub_fields():
+    tree = ast.parse(STUB_PATH.read_text())
+    return {
+        node.name: {
+            item.target.id
+            for item in node.body
+            if isinstance(item, ast.AnnAssign) and isinstance(item.target, ast.Name)
+        }
+        for node in tree.body
+        if isinstance(node, ast.ClassDef)
+    }
+
+
+def _python_fields(cls):
+    return {
+        name
+        for name in dir(cls)
+        if not name.startswith("_") and not callable(getattr(cls, name))
+    }
+
+
+def _walk(obj, serialized, seen):
+    """Pair each model object with its serialized form, recursing through both."""
+    seen.setdefault(type(obj).__name__, (obj, serialized))
+    for key, value in serialized.items():
+        child = getattr(obj, _snake(key))
+        if isinstance(value, dict):
+            _walk(child, value, seen)
+        elif isinstance(value, list) and value and isinstance(value[0], dict):
+            assert isinstance(child, ListView)
+            for item, item_serialized in zip(child, value):
+                _walk(item, item_serialized, seen)
+
+
+def _assert_fields_exposed(native):
+    """Assert that every field Rust serializes is a getter, a `to_dict` key and a stub attribute."""
+    seen = {}
+    _walk(native, json.loads(native.to_json()), seen)
+    stubs = _stub_fields()
+
+    for name, (obj, serialized) in seen.items():
+        rust_fields = {_snake(key) for key in serialized}
+
+        assert _python_fields(type(obj)) == rust_fields, name
+        assert stubs[name] == rust_fields, name
+        assert set(obj.to_dict()) == rust_fields, name
+
+    return set(seen)
+
+
+def test_subject_native_fields_are_exposed():
+    seen = _assert_fields_exposed(parse_subject_native_string(XML))
+
+    assert seen == {
+        "SubjectNative",
+        "Export",
+        "Patient",
+        "Form",
+        "State",
+        "LockState",
+        "Category",
+        "Field",
+        "File",
+        "Entry",
+        "Value",
+        "Reason",
+        "Comment",
+        "Query",
+    }
+
+
+def test_site_native_fields_are_exposed(site_native_xml):
+    seen = _assert_fields_exposed(parse_site_native_file(site_native_xml))
+
+    assert {"SiteNative", "Site", "Form"} <= seen
+
+
+def test_user_native_fields_are_exposed(user_native_xml):
+    seen = _assert_fields_exposed(parse_user_native_file(user_native_xml))
+
+    assert {"UserNative", "User", "Form"} <= seen
+
+
+@pytest.mark.parametrize("protocol", [2, pickle.HIGHEST_PROTOCOL])
+def test_every_class_pickles(protocol, site_native_xml, user_native_xml):
+    seen = {}
+    for native in (
+        parse_subject_native_string(XML),
+        parse_site_native_file(site_native_xml),
+        parse_user_native_file(user_native_xml),
+    ):
+        _walk(native, json.loads(native.to_json()), seen)
+
+    for name, (obj, _) in seen.items():
+        restored = pickle.loads(pickle.dumps(obj, protocol=protocol))
+
+        assert type(restored) is type(obj), name
+        assert restored == obj, name
+        assert restored.to_dict() == obj.to_dict(), name
+
+
+def test_deepcopy():
+    native = parse_subject_native_string(XML)
+
+    assert copy.deepcopy(native) == native
+
+
+def test_equality_and_hash():
+    first = parse_subject_native_string(XML)
+    second = parse_subject_native_string(XML)
+    other = parse_subject_native_string(XML.replace("Current", "Changed"))
+
+    assert first == second
+    assert hash(first) == hash(second)
+    assert first != other
+    assert first.patients[0].forms[0] != other.patients[0].forms[0]
+    assert first.patients[0].forms[0].categories[0].files[0] == (
+        other.patients[0].forms[0].categories[0].files[0]
+    )
+    assert first != "SubjectNative"
+    assert len({first.patients[0], second.patients[0]}) == 1
+
+
+def test_repr():
+    native = parse_subject_native_string(XML)
+    patient = native.patients[0]
+    entry = patient.forms[0].categories[0].fields[0].entries[0]
+
+    assert repr(native).startswith("SubjectNative(export=Export(date=datetime.datetime(2024, 5, 30")
+    assert repr(native).endswith(", patients=<1 item>)")
+    assert repr(patient) == (
+        "Patient(patient_id='TEST-001', unique_id='1', site_name='Test Site', forms=<1 item>)"
+    )
+    assert repr(entry.reason).startswith("Reason(value='Corrected', by='Test User', role='Manager'")
+    assert repr(entry).startswith("Entry(entry_id='1', value=Value(value='Current'")
+
+
+def test_from_json(site_native_xml, user_native_xml):
+    for cls, native in (
+        (SubjectNative, parse_subject_native_string(XML)),
+        (SiteNative, parse_site_native_file(site_native_xml)),
+        (UserNative, parse_user_native_file(user_native_xml)),
+    ):
+        assert cls.from_json(native.to_json()) == native
+
+
+def test_every_class_from_dict(site_native_xml, user_native_xml):
+    seen = {}
+    for native in (
+        parse_subject_native_string(XML),
+        parse_site_native_file(site_native_xml),
+        parse_user_native_file(user_native_xml),
+    ):
+        _walk(native, json.loads(native.to_json()), seen)
+
+    for name, (obj, _) in seen.items():
+        assert type(obj).from_dict(obj.to_dict()) == obj, name
+
+
+def test_from_dict_with_models_and_aware_datetimes():
+    native = parse_subject_native_string(XML)
+    patient = native.patients[0]
+    data = patient.to_dict()
+    data["forms"] = patient.forms
+    eastern = timezone(timedelta(hours=-4))
+    data["when_created"] = data["when_created"].replace(tzinfo=timezone.utc).astimezone(eastern)
+
+    assert type(patient).from_dict(data) == patient
+
+
+def test_from_json_and_dict_errors():
+    with pytest.raises(ValueError, match="Error loading JSON"):
+        SubjectNative.from_json("{")
+    with pytest.raises(ValueError, match="Error loading dict"):
+        Value.from_dict({"by": "Test User"})
+
+
+def test_constructors():
+    when = datetime(2023, 4, 15, 16, 9, 2)
+    entry = parse_subject_native_string(XML).patients[0].forms[0].categories[0].fields[0].entries[0]
+    form = parse_subject_native_string(XML).patients[0].forms[0]
+
+    value = Value("Current", "Test User", "Manager", by_unique_id="1", when=when)
+    reason = Reason(
+        "Corrected",
+        "Test User",
+        "Manager",
+        by_unique_id="1",
+        when=when.replace(tzinfo=timezone.utc),
+    )
+
+    assert value == entry.value
+    assert reason == entry.reason
+    assert (
+        Entry(
+            "1",
+            value,
+            reason,
+            reviewed_by="Test User",
+            reviewed_by_unique_id="1",
+            reviewed_by_when=when,
+        )
+        == entry
+    )
+    assert State("form.state.in.work", "Test User - Manager", "1", when) == form.states[0]
+    assert (
+        LockState(True, user="Test User", user_unique_id="1", date_time_changed=when)
+        == form.lock_states[0]
+    )
+    assert Export() == Export.from_dict({})
+    assert Export(page_number="1 of 6").page_number == "1 of 6"
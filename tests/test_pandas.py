@@ -1,6 +1,9 @@
+import io
+
 import pandas as pd
+import pytest
 
-from prelude_parser.pandas import to_dataframe
+from prelude_parser.pandas import parse_flat_bytes, parse_flat_string, to_dataframe
 
 
 def test_pandas_to_dataframe(test_file_1):
@@ -10,7 +13,7 @@ def test_pandas_to_dataframe(test_file_1):
             "communications.form.name.communications",
             "communications.form.name.communications",
         ],
-        "communications_made": ["Yes", "Yes"],
+        "communications_made": [True, True],
         "form_group": ["Communications", "Communications"],
         "form_number": [None, None],
         "form_state": ["In-Work", "In-Work"],
@@ -35,3 +38,34 @@ def test_pandas_to_dataframe_short_names(test_file_4):
     expected = pd.DataFrame.from_dict(data)
     result = result.reindex(sorted(result.columns), axis=1)
     assert expected.equals(result)
+
+
+def test_pandas_parse_flat_string_and_bytes(test_file_1):
+    expected = to_dataframe(test_file_1)
+
+    assert expected.equals(parse_flat_string(test_file_1.read_text()))
+    assert expected.equals(parse_flat_bytes(test_file_1.read_bytes()))
+    assert expected.equals(to_dataframe(io.BytesIO(test_file_1.read_bytes())))
+
+
+def test_pandas_to_dataframe_dtypes(test_file_2):
+    result = to_dataframe(
+        test_file_2, dtypes={"demographics": {"patient_id": "str", "breed": "category"}}
+    )
+
+    assert result["patient_id"].tolist() == ["1681574905819", "1681574994823"]
+    assert isinstance(result["breed"].dtype, pd.CategoricalDtype)
+
+
+def test_pandas_to_dataframe_dtypes_unknown_column(test_file_2):
+    with pytest.raises(ValueError, match="which is not in the form"):
+        to_dataframe(test_file_2, dtypes={"demographics": {"patient": "str"}})
+
+
+def test_pandas_to_dataframe_dtypes_conflict(test_file_1):
+    visits = "<visits><PatientId>1</PatientId></visits></export_from_vision_EDC>"
+    xml = test_file_1.read_text().replace("</export_from_vision_EDC>", visits)
+    dtypes = {"communications": {"patient_id": "str"}, "visits": {"patient_id": "int"}}
+
+    with pytest.raises(ValueError, match="share their columns"):
+        parse_flat_string(xml, dtypes=dtypes)
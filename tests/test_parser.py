@@ -1,8 +1,15 @@
-from datetime import date
+import io
+from concurrent.futures import ThreadPoolExecutor
+from datetime import date, datetime, time
 
 import pytest
 
 from prelude_parser import (
+    PartialDate,
+    SubjectNative,
+    flat_schema,
+    parse_flat_bytes,
+    parse_flat_string,
     parse_site_native_file,
     parse_site_native_string,
     parse_subject_native_file,
@@ -11,6 +18,7 @@ from prelude_parser import (
     parse_to_dict,
     parse_user_native_file,
     parse_user_native_string,
+    tqdm_callback,
 )
 from prelude_parser._prelude_parser import FileNotFoundError, InvalidFileTypeError, ParsingError
 
@@ -29,6 +37,13 @@ def test_parse_site_native_string(site_native_xml):
     assert result.sites[0].name == "Some Site"
 
 
+def test_parse_site_native_file_skip_export(site_native_xml):
+    result = parse_site_native_file(site_native_xml, skip_export=True)
+
+    assert result.export is None
+    assert result.sites[0].name == "Some Site"
+
+
 def test_site_native_to_dict(site_native_xml):
     result = parse_site_native_file(site_native_xml)
     result_dict = result.to_dict()
@@ -36,6 +51,20 @@ def test_site_native_to_dict(site_native_xml):
     assert result_dict["sites"][0]["name"] == "Some Site"
 
 
+def test_site_native_getitem(site_native_xml):
+    result = parse_site_native_file(site_native_xml)
+    site = result["Some Site"]
+
+    assert site.unique_id == "1681574834910"
+    assert result.by_unique_id("1681574834910") == site
+
+    with pytest.raises(KeyError):
+        result["missing"]
+
+    with pytest.raises(KeyError):
+        result.by_unique_id("missing")
+
+
 def test_site_native_to_json(site_native_small_xml):
     result = parse_site_native_file(site_native_small_xml)
     result_json = result.to_json()
@@ -61,6 +90,97 @@ def test_parse_subject_native_string(subject_native_xml):
     assert result.patients[0].patient_id == "ABC-001"
 
 
+def test_parse_subject_native_string_current_only():
+    xml = """<?xml version="1.0" encoding="UTF-8"?>
+<export_from_vision_EDC date="30-May-2024 10:35 -0500" createdBy="Test User" role="Project Manager" numberSubjectsProcessed="1">
+  <patient patientId="TEST-001" uniqueId="123456" whenCreated="2023-04-15 12:09:02 -0400" creator="Test User" siteName="Test Site" siteUniqueId="654321" lastLanguage="" numberOfForms="1">
+    <form name="test.form" lastModified="2023-04-15 12:09:15 -0400" whoLastModifiedName="Test User" whoLastModifiedRole="Manager" whenCreated="1681574905839" hasErrors="false" hasWarnings="false" locked="false" user="" dateTimeChanged="" formTitle="Test Form" formIndex="1" formGroup="Test" formState="In-Work">
+      <category name="Test Category" type="normal" highestIndex="0">
+        <field name="test_field" type="text" dataType="string" errorCode="valid" whenCreated="2023-04-15 12:08:26 -0400" keepHistory="true">
+          <entry id="1">
+            <value by="Test User" byUniqueId="123456" role="Manager" when="2023-04-15 12:09:02 -0400" xml:space="preserve">Current</value>
+          </entry>
+          <entry id="2">
+            <value by="Test User" byUniqueId="123456" role="Manager" when="2023-04-15 12:08:30 -0400" xml:space="preserve">Original</value>
+          </entry>
+        </field>
+      </category>
+    </form>
+  </patient>
+</export_from_vision_EDC>"""
+
+    full = parse_subject_native_string(xml)
+    current = parse_subject_native_string(xml, history="current_only")
+
+    assert len(full.patients[0].forms[0].categories[0].fields[0].entries) == 2
+    entries = current.patients[0].forms[0].categories[0].fields[0].entries
+    assert len(entries) == 1
+    assert entries[0].value.value == "Current"
+
+
+def test_parse_subject_native_file_current_only(subject_native_xml):
+    result = parse_subject_native_file(subject_native_xml, history="current_only")
+
+    assert result.patients[0].patient_id == "ABC-001"
+
+
+def test_parse_subject_native_file_skip_export(subject_native_xml):
+    result = parse_subject_native_file(subject_native_xml, skip_export=True)
+
+    assert result.export is None
+    assert result.patients[0].patient_id == "ABC-001"
+
+
+@pytest.mark.parametrize("threads", [1, 2])
+def test_parse_subject_native_file_threads(subject_native_xml, threads):
+    expected = parse_subject_native_file(subject_native_xml)
+    result = parse_subject_native_file(subject_native_xml, threads=threads)
+
+    assert result.to_dict() == expected.to_dict()
+
+
+def test_parse_subject_native_file_progress(subject_native_xml):
+    reports = []
+    result = parse_subject_native_file(subject_native_xml, progress=reports.append)
+
+    last = reports[-1]
+    assert last.records == last.total_records == len(result.patients)
+    assert last.bytes == last.total_bytes == subject_native_xml.stat().st_size
+    assert [r.bytes for r in reports] == sorted(r.bytes for r in reports)
+
+
+def test_parse_subject_native_string_progress_error(subject_native_xml):
+    def fail(_):
+        raise KeyboardInterrupt
+
+    with pytest.raises(KeyboardInterrupt):
+        parse_subject_native_string(subject_native_xml.read_text(), progress=fail)
+
+
+def test_tqdm_callback(user_native_xml):
+    class Bar:
+        total = None
+        n = 0
+        refreshed = 0
+
+        def update(self, n):
+            self.n += n
+
+        def refresh(self):
+            self.refreshed += 1
+
+    bar = Bar()
+    parse_user_native_file(user_native_xml, progress=tqdm_callback(bar))
+
+    assert bar.total == bar.n == user_native_xml.stat().st_size
+    assert bar.refreshed == 1
+
+
+def test_parse_subject_native_file_invalid_history(subject_native_xml):
+    with pytest.raises(ValueError):
+        parse_subject_native_file(subject_native_xml, history="latest")
+
+
 def test_subject_native_to_dict(subject_native_xml):
     result = parse_subject_native_file(subject_native_xml)
     result_dict = result.to_dict()
@@ -78,12 +198,93 @@ def test_subject_native_to_json(subject_native_small_xml):
     )
 
 
+def test_subject_native_select(subject_native_xml):
+    result = parse_subject_native_file(subject_native_xml)
+    matches = result.select("form/category/field[name=breed]/current")
+
+    assert len(matches) == 1
+    assert matches[0].patient.patient_id == "ABC-001"
+    assert matches[0].field.name == "breed"
+    assert matches[0].entry.value.value == "Labrador"
+    assert matches[0].site is None
+
+
+def test_subject_native_select_invalid(subject_native_xml):
+    result = parse_subject_native_file(subject_native_xml)
+
+    with pytest.raises(ValueError, match="cannot follow"):
+        result.select("form/field")
+
+
+def test_subject_native_getitem(subject_native_xml):
+    result = parse_subject_native_file(subject_native_xml)
+    patient = result["ABC-001"]
+
+    assert patient.unique_id == "1681574905819"
+    assert patient.forms[0]["breed"].field_type == "combo-box"
+
+
+def test_subject_native_getitem_missing(subject_native_xml):
+    result = parse_subject_native_file(subject_native_xml)
+
+    with pytest.raises(KeyError):
+        result["missing"]
+
+    with pytest.raises(KeyError):
+        result["ABC-001"].forms[0]["missing"]
+
+
+def test_subject_native_by_unique_id_and_site(subject_native_xml):
+    result = parse_subject_native_file(subject_native_xml)
+
+    assert result.by_unique_id("1681574905819") == result["ABC-001"]
+    assert [patient.patient_id for patient in result.by_site("Some Site")] == [
+        patient.patient_id for patient in result.patients if patient.site_name == "Some Site"
+    ]
+    assert result.by_site("missing") == []
+
+    with pytest.raises(KeyError):
+        result.by_unique_id("missing")
+
+
+def test_subject_native_merge_requires_page_numbers(subject_native_xml):
+    result = parse_subject_native_file(subject_native_xml)
+
+    with pytest.raises(ValueError, match="no page number"):
+        SubjectNative.merge([result])
+
+
+def test_subject_native_list_views(subject_native_xml):
+    result = parse_subject_native_file(subject_native_xml)
+    patients = result.patients
+    ids = [patient.patient_id for patient in patients]
+
+    assert len(patients) == len(ids)
+    assert patients[-1].patient_id == ids[-1]
+    assert [patient.patient_id for patient in patients[::-1]] == ids[::-1]
+    assert [patient.patient_id for patient in patients[1:][1:]] == ids[2:]
+    assert len(patients[5:2]) == 0
+    assert [patient.patient_id for patient in patients.to_list()] == ids
+    assert [form.name for form in patients[0].forms] == [
+        form.name for form in patients[0].forms.to_list()
+    ]
+
+    with pytest.raises(IndexError):
+        patients[len(ids)]
+
+
 def test_parse_user_native_file(user_native_xml):
     result = parse_user_native_file(user_native_xml)
 
     assert result.users[0].unique_id == "1691421275437"
 
 
+def test_user_native_getitem(user_native_xml):
+    result = parse_user_native_file(user_native_xml)
+
+    assert result["1691421275437"].unique_id == "1691421275437"
+
+
 def test_parse_user_native_string(user_native_xml):
     with open(user_native_xml) as f:
         xml = f.read()
@@ -92,6 +293,15 @@ def test_parse_user_native_string(user_native_xml):
     assert result.users[0].unique_id == "1691421275437"
 
 
+def test_parse_user_native_string_skip_export(user_native_xml):
+    with open(user_native_xml) as f:
+        xml = f.read()
+    result = parse_user_native_string(xml, skip_export=True)
+
+    assert result.export is None
+    assert result.users[0].unique_id == "1691421275437"
+
+
 def test_user_native_to_dict(user_native_xml):
     result = parse_user_native_file(user_native_xml)
     result_dict = result.to_dict()
@@ -123,7 +333,7 @@ def test_parse_to_classes(test_file_1):
     assert result[0].form_number is None
     assert result[0].form_group == "Communications"
     assert result[0].form_state == "In-Work"
-    assert result[0].communications_made == "Yes"
+    assert result[0].communications_made is True
 
 
 def test_parse_to_classes_with_float(test_file_2):
@@ -187,7 +397,7 @@ def test_parse_to_dict(test_file_1):
         "communications": [
             {
                 "base_form": "communications.form.name.communications",
-                "communications_made": "Yes",
+                "communications_made": True,
                 "form_group": "Communications",
                 "form_number": None,
                 "form_state": "In-Work",
@@ -200,7 +410,7 @@ def test_parse_to_dict(test_file_1):
             },
             {
                 "base_form": "communications.form.name.communications",
-                "communications_made": "Yes",
+                "communications_made": True,
                 "form_group": "Communications",
                 "form_number": None,
                 "form_state": "In-Work",
@@ -219,6 +429,124 @@ def test_parse_to_dict(test_file_1):
     assert result == expected
 
 
+@pytest.mark.parametrize("encoding", ["windows-1252", "iso-8859-1", "utf-16"])
+def test_parse_to_dict_declared_encoding(test_file_1, tmp_path, encoding):
+    contents = test_file_1.read_text().replace("Some Site", "Sité").replace("UTF-8", encoding)
+    xml_file = tmp_path / "encoded.xml"
+    xml_file.write_bytes(contents.encode(encoding))
+
+    result = parse_to_dict(xml_file)
+
+    assert [row["site_name"] for row in result["communications"]] == ["Sité", "Sité"]
+
+
+def test_parse_flat_string(test_file_1):
+    assert parse_flat_string(test_file_1.read_text()) == parse_to_dict(test_file_1)
+
+
+@pytest.mark.parametrize("encoding", ["utf-8", "windows-1252", "utf-16"])
+def test_parse_flat_bytes(test_file_1, encoding):
+    contents = test_file_1.read_text().replace("Some Site", "Sité").replace("UTF-8", encoding)
+
+    result = parse_flat_bytes(contents.encode(encoding))
+
+    assert [row["site_name"] for row in result["communications"]] == ["Sité", "Sité"]
+
+
+def test_parse_flat_string_short_names(test_file_4):
+    result = parse_flat_string(test_file_4.read_text(), short_names=True)
+
+    assert result == parse_to_dict(test_file_4, short_names=True)
+
+
+def test_parse_flat_string_parsing_error():
+    with pytest.raises(ParsingError):
+        parse_flat_string("")
+
+
+def test_parse_to_dict_threads(test_file_1, test_file_2, test_file_3):
+    files = [test_file_1, test_file_2, test_file_3] * 4
+
+    with ThreadPoolExecutor(max_workers=4) as executor:
+        results = list(executor.map(parse_to_dict, files))
+
+    assert results == [parse_to_dict(xml_file) for xml_file in files]
+
+
+def test_parse_to_dict_dtypes(test_file_2):
+    dtypes = {
+        "demographics": {
+            "patient_id": "str",
+            "weight": "str",
+            "visit_date": "datetime",
+            "breed": "category",
+            "screening_number": "int",
+        }
+    }
+
+    result = parse_to_dict(test_file_2, dtypes=dtypes)["demographics"][0]
+
+    assert result["patient_id"] == "1681574905819"
+    assert result["weight"] == "80.2"
+    assert result["visit_date"] == datetime(2023, 4, 15)
+    assert result["breed"] == "Labrador"
+    assert result["screening_number"] == 1
+    assert result["dob"] == date(2020, 4, 15)
+
+
+def test_parse_to_dict_dtypes_bool(test_file_1):
+    result = parse_to_dict(test_file_1, dtypes={"communications": {"communications_made": "bool"}})
+
+    assert [row["communications_made"] for row in result["communications"]] == [True, True]
+
+
+def test_parse_to_dict_dtypes_unknown_form(test_file_1):
+    with pytest.raises(ValueError, match='form "demographics", which is not in the export'):
+        parse_to_dict(test_file_1, dtypes={"demographics": {"patient_id": "str"}})
+
+
+def test_parse_to_dict_dtypes_unknown_column(test_file_1):
+    with pytest.raises(ValueError, match='column "patient" of form "communications"'):
+        parse_to_dict(test_file_1, dtypes={"communications": {"patient": "str"}})
+
+
+def test_parse_to_dict_dtypes_unreadable_value(test_file_2):
+    with pytest.raises(ValueError, match='"Labrador" in column "breed" of form "demographics"'):
+        parse_to_dict(test_file_2, dtypes={"demographics": {"breed": "int"}})
+
+
+def test_parse_to_dict_dtypes_invalid(test_file_2):
+    with pytest.raises(ValueError, match="dtype must be one of"):
+        parse_to_dict(test_file_2, dtypes={"demographics": {"breed": "text"}})
+
+
+def test_parse_to_dict_file_like(test_file_1):
+    expected = parse_to_dict(test_file_1)
+
+    with open(test_file_1, "rb") as f:
+        assert parse_to_dict(f) == expected
+    with open(test_file_1) as f:
+        assert parse_to_dict(f) == expected
+    assert parse_to_dict(io.StringIO(test_file_1.read_text())) == expected
+
+
+def test_parse_to_classes_file_like(test_file_1):
+    result = parse_to_classes(io.BytesIO(test_file_1.read_bytes()))
+
+    assert [x.patient_name for x in result] == ["ABC-001", "ABC-002"]
+
+
+@pytest.mark.parametrize("encoding", ["windows-1252", "utf-16"])
+def test_parse_subject_native_file_declared_encoding(subject_native_xml, tmp_path, encoding):
+    contents = subject_native_xml.read_text().replace("UTF-8", encoding)
+    xml_file = tmp_path / "encoded.xml"
+    xml_file.write_bytes(contents.encode(encoding))
+
+    result = parse_subject_native_file(xml_file)
+
+    assert result.to_dict() == parse_subject_native_file(subject_native_xml).to_dict()
+
+
 def test_parse_to_dict_with_float(test_file_2):
     result = parse_to_dict(test_file_2)
     expected = {
@@ -355,3 +683,130 @@ def test_parse_to_dict_short_names(test_file_4):
     result["communications"] = [dict(sorted(x.items())) for x in result["communications"]]
 
     assert result == expected
+
+
+def _visits_xml(*rows):
+    visits = "".join(
+        f"<visits><VisitDate>{visit_date}</VisitDate><VisitTime>{visit_time}</VisitTime>"
+        f"<Fasted>{fasted}</Fasted><OnsetDate>{onset_date}</OnsetDate></visits>"
+        for visit_date, visit_time, fasted, onset_date in rows
+    )
+    return (
+        '<?xml version="1.0" encoding="UTF-8"?>'
+        f"<export_from_vision_EDC>{visits}</export_from_vision_EDC>"
+    )
+
+
+def test_parse_flat_string_infers_types():
+    xml = _visits_xml(
+        ("15-Apr-2023", "12:09", "Yes", "UN-JAN-2023"),
+        ("2023-04-16 08:30", "08:30:15", "no", "2023-UN-UN"),
+    )
+
+    result = parse_flat_string(xml)["visits"]
+
+    assert [row["visit_date"] for row in result] == [
+        datetime(2023, 4, 15),
+        datetime(2023, 4, 16, 8, 30),
+    ]
+    assert [row["visit_time"] for row in result] == [time(12, 9), time(8, 30, 15)]
+    assert [row["fasted"] for row in result] == [True, False]
+    assert [row["onset_date"] for row in result] == [
+        PartialDate(year=2023, month=1),
+        PartialDate(year=2023),
+    ]
+
+
+def test_parse_flat_string_partial_date_column_with_full_dates():
+    xml = _visits_xml(("", "", "", "15-Apr-2023"), ("", "", "", "UN-APR-2023"))
+
+    result = parse_flat_string(xml)["visits"]
+
+    assert [row["onset_date"] for row in result] == [
+        PartialDate(year=2023, month=4, day=15),
+        PartialDate(year=2023, month=4),
+    ]
+
+
+def test_parse_flat_string_mixed_values_are_text():
+    xml = _visits_xml(("15-Apr-2023", "12:09", "Yes", "UN-JAN-2023"), ("", "", "1", "unknown"))
+
+    result = parse_flat_string(xml)["visits"]
+
+    assert [row["fasted"] for row in result] == ["Yes", "1"]
+    assert [row["onset_date"] for row in result] == ["UN-JAN-2023", "unknown"]
+
+
+def test_partial_date_str_and_repr():
+    partial_date = PartialDate(year=2023, month=1)
+
+    assert str(partial_date) == "2023-01-UN"
+    assert repr(partial_date) == "PartialDate(year=2023, month=1, day=None)"
+
+
+def test_flat_schema():
+    xml = _visits_xml(
+        ("15-Apr-2023", "12:09", "Yes", ""),
+        ("16-Apr-2023", "", "1", "2023-UN-UN"),
+        ("15-Apr-2023", "", "Y", "2023-UN-UN"),
+    )
+
+    result = flat_schema(io.StringIO(xml))["visits"]
+
+    assert list(result) == ["visit_date", "visit_time", "fasted", "onset_date"]
+    assert result["visit_date"] == {
+        "dtype": "date",
+        "null_count": 0,
+        "distinct_count": 2,
+        "widened_by": None,
+    }
+    assert result["visit_time"]["null_count"] == 2
+    assert result["fasted"] == {
+        "dtype": "str",
+        "null_count": 0,
+        "distinct_count": 3,
+        "widened_by": "1",
+    }
+    assert result["onset_date"] == {
+        "dtype": "partial_date",
+        "null_count": 1,
+        "distinct_count": 1,
+        "widened_by": None,
+    }
+
+
+def test_flat_schema_file(test_file_1):
+    result = flat_schema(test_file_1)
+
+    assert result["communications"]["patient_id"] == {
+        "dtype": "int",
+        "null_count": 0,
+        "distinct_count": 2,
+        "widened_by": None,
+    }
+    assert result["communications"]["form_number"]["null_count"] == 2
+
+
+def test_flat_schema_bytes(test_file_1):
+    assert flat_schema(io.BytesIO(test_file_1.read_bytes())) == flat_schema(test_file_1)
+
+
+def test_flat_schema_repeated_element():
+    xml = "<export><visits><A>1</A><A>2</A></visits><visits><A /></visits></export>"
+
+    result = flat_schema(io.StringIO(xml))["visits"]["a"]
+
+    assert result["null_count"] == 1
+    assert result["distinct_count"] == 2
+
+
+def test_flat_schema_dirty_value_first():
+    xml = _visits_xml(
+        ("N/A", "", "", ""), ("15-Apr-2023", "", "", ""), ("16-Apr-2023", "", "", "")
+    )
+
+    result = flat_schema(io.StringIO(xml))["visits"]
+
+    assert result["visit_date"]["dtype"] == "str"
+    assert result["visit_date"]["widened_by"] == "N/A"
+    assert result["visit_time"]["dtype"] == "unknown"
@@ -1,7 +1,10 @@
+import io
+
 import polars as pl
+import pytest
 from polars.testing import assert_frame_equal
 
-from prelude_parser.polars import to_dataframe
+from prelude_parser.polars import parse_flat_bytes, parse_flat_string, to_dataframe
 
 
 def test_pandas_to_dataframe(test_file_1):
@@ -11,7 +14,7 @@ def test_pandas_to_dataframe(test_file_1):
             "communications.form.name.communications",
             "communications.form.name.communications",
         ],
-        "communications_made": ["Yes", "Yes"],
+        "communications_made": [True, True],
         "form_group": ["Communications", "Communications"],
         "form_number": [None, None],
         "form_state": ["In-Work", "In-Work"],
@@ -36,3 +39,34 @@ def test_pandas_to_dataframe_short_names(test_file_4):
     expected = pl.from_dict(data)  # type: ignore
     result = result.pipe(lambda x: x.select(sorted(x.columns)))
     assert_frame_equal(expected, result)
+
+
+def test_polars_parse_flat_string_and_bytes(test_file_1):
+    expected = to_dataframe(test_file_1)
+
+    assert_frame_equal(expected, parse_flat_string(test_file_1.read_text()))
+    assert_frame_equal(expected, parse_flat_bytes(test_file_1.read_bytes()))
+    assert_frame_equal(expected, to_dataframe(io.StringIO(test_file_1.read_text())))
+
+
+def test_polars_to_dataframe_dtypes(test_file_2):
+    result = to_dataframe(
+        test_file_2, dtypes={"demographics": {"patient_id": "str", "breed": "category"}}
+    )
+
+    assert result["patient_id"].to_list() == ["1681574905819", "1681574994823"]
+    assert result["breed"].dtype == pl.Categorical
+
+
+def test_polars_to_dataframe_dtypes_unknown_column(test_file_2):
+    with pytest.raises(ValueError, match="which is not in the form"):
+        to_dataframe(test_file_2, dtypes={"demographics": {"patient": "str"}})
+
+
+def test_polars_to_dataframe_dtypes_conflict(test_file_1):
+    visits = "<visits><PatientId>1</PatientId></visits></export_from_vision_EDC>"
+    xml = test_file_1.read_text().replace("</export_from_vision_EDC>", visits)
+    dtypes = {"communications": {"patient_id": "str"}, "visits": {"patient_id": "int"}}
+
+    with pytest.raises(ValueError, match="share their columns"):
+        parse_flat_string(xml, dtypes=dtypes)